@@ -0,0 +1,13 @@
+use ark_algebra_bench_templates::*;
+use ark_ff_optimized::babybear::Fp as Specialized;
+use ark_ff_optimized::field_compare;
+use criterion::criterion_main;
+
+#[derive(ark_ff::MontConfig)]
+#[modulus = "2013265921"]
+#[generator = "31"]
+pub struct FpParams;
+pub type Generic = ark_ff::Fp64<ark_ff::MontBackend<FpParams, 1>>;
+
+field_compare!(prime; "Fp=2013265921"; fp2013265921; Generic, Specialized);
+criterion_main!(fp2013265921::benches);
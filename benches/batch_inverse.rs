@@ -0,0 +1,69 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::Field;
+use ark_ff_optimized::{fp31, fp64};
+use criterion::{criterion_group, criterion_main};
+
+const SIZE: usize = 1 << 16;
+
+fn bench_field<F: Field>(
+    c: &mut Criterion,
+    name: &str,
+    batch_inverse: fn(&mut [F]),
+    batch_inverse_into: fn(&[F], &mut [F]),
+    #[cfg(feature = "parallel")] par_batch_inverse: fn(&mut [F]),
+) {
+    let mut group = c.benchmark_group(format!("Batch inverse {name}"));
+    let mut rng = ark_std::test_rng();
+    let values = (0..SIZE).map(|_| F::rand(&mut rng)).collect::<Vec<_>>();
+
+    group.bench_function("Inverse 2^16 elements one by one", |b| {
+        b.iter(|| {
+            values
+                .iter()
+                .map(|value| value.inverse().unwrap_or_default())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("Batch inverse 2^16 elements", |b| {
+        b.iter(|| {
+            let mut values = values.clone();
+            batch_inverse(&mut values);
+            values
+        })
+    });
+    let mut out = vec![F::ZERO; SIZE];
+    group.bench_function("Batch inverse into 2^16 elements", |b| {
+        b.iter(|| batch_inverse_into(&values, &mut out))
+    });
+    #[cfg(feature = "parallel")]
+    group.bench_function("Parallel batch inverse 2^16 elements", |b| {
+        b.iter(|| {
+            let mut values = values.clone();
+            par_batch_inverse(&mut values);
+            values
+        })
+    });
+    group.finish();
+}
+
+fn bench_batch_inverse(c: &mut Criterion) {
+    bench_field(
+        c,
+        "Fp=18446744069414584321",
+        fp64::batch_inverse,
+        fp64::batch_inverse_into,
+        #[cfg(feature = "parallel")]
+        fp64::par_batch_inverse,
+    );
+    bench_field(
+        c,
+        "Fp=2147483647",
+        fp31::batch_inverse,
+        fp31::batch_inverse_into,
+        #[cfg(feature = "parallel")]
+        fp31::par_batch_inverse,
+    );
+}
+
+criterion_group!(benches, bench_batch_inverse);
+criterion_main!(benches);
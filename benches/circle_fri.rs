@@ -0,0 +1,51 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::UniformRand;
+use ark_ff_optimized::{
+    circle::{
+        fri::{self, FriOptions, FriProof},
+        CirclePoly,
+    },
+    fp31::{
+        poseidon2::{Digest, Poseidon2},
+        Fp,
+    },
+    qm31::Fp4,
+    transcript::Transcript,
+};
+use criterion::{criterion_group, criterion_main};
+
+fn bench_circle_fri(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Circle FRI Fp=2147483647");
+    group.sample_size(10);
+    let mut rng = ark_std::test_rng();
+
+    let poly = CirclePoly::new((0..1 << 14).map(|_| Fp::rand(&mut rng)).collect());
+    let evaluations = fri::lde(&poly, 3);
+    let hasher = Poseidon2::<16>::new();
+    let transcript = Transcript::new(Poseidon2::<16>::new(), b"bench");
+    let options = FriOptions::with_security_bits(100, 3, 5, 16).unwrap();
+    group.bench_function("Prove 2^14 coefficients", |b| {
+        b.iter(|| {
+            let proof: FriProof<Fp4, Digest> =
+                fri::prove(&options, &hasher, &mut transcript.clone(), &evaluations);
+            proof
+        })
+    });
+    let proof: FriProof<Fp4, Digest> =
+        fri::prove(&options, &hasher, &mut transcript.clone(), &evaluations);
+    group.bench_function("Verify 2^14 coefficients", |b| {
+        b.iter(|| {
+            fri::verify(
+                &options,
+                &hasher,
+                &mut transcript.clone(),
+                evaluations.len(),
+                &proof,
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_circle_fri);
+criterion_main!(benches);
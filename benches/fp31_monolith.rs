@@ -0,0 +1,25 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::UniformRand;
+use ark_ff_optimized::fp31::{monolith::Monolith, Fp};
+use criterion::{criterion_group, criterion_main};
+
+fn bench_monolith(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Monolith Fp=2147483647");
+    let mut rng = ark_std::test_rng();
+
+    let permutation = Monolith::<16>::new();
+    let mut state: [Fp; 16] = core::array::from_fn(|_| Fp::rand(&mut rng));
+    group.bench_function("Permute width 16", |b| {
+        b.iter(|| permutation.permute(&mut state))
+    });
+
+    let left = core::array::from_fn(|_| Fp::rand(&mut rng));
+    let right = core::array::from_fn(|_| Fp::rand(&mut rng));
+    group.bench_function("Compress width 16", |b| {
+        b.iter(|| permutation.compress(&left, &right))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_monolith);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::MontFp;
+use ark_ff_optimized::field_compare;
+use ark_ff_optimized::fp64::ext2::Fp2 as Specialized;
+use criterion::criterion_main;
+
+#[derive(ark_ff::MontConfig)]
+#[modulus = "18446744069414584321"]
+#[generator = "7"]
+pub struct FpParams;
+pub type Fp = ark_ff::Fp64<ark_ff::MontBackend<FpParams, 1>>;
+
+pub struct Fp2Params;
+impl ark_ff::Fp2Config for Fp2Params {
+    type Fp = Fp;
+    const NONRESIDUE: Fp = MontFp!("7");
+    const FROBENIUS_COEFF_FP2_C1: &'static [Fp] = &[MontFp!("1"), MontFp!("-1")];
+}
+pub type Generic = ark_ff::Fp2<Fp2Params>;
+
+field_compare!(extension; "Fp2=18446744069414584321^2"; fp18446744069414584321_2; Generic, Specialized);
+criterion_main!(fp18446744069414584321_2::benches);
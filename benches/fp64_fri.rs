@@ -0,0 +1,50 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::UniformRand;
+use ark_ff_optimized::{
+    fp64::{
+        ext2::Fp2,
+        fri::{self, FriOptions, FriProof},
+        poseidon2::{Digest, Poseidon2},
+        Fp,
+    },
+    transcript::Transcript,
+};
+use criterion::{criterion_group, criterion_main};
+
+fn bench_fri(c: &mut Criterion) {
+    let mut group = c.benchmark_group("FRI Fp=18446744069414584321");
+    group.sample_size(10);
+    let mut rng = ark_std::test_rng();
+
+    let coefficients = (0..1 << 14).map(|_| Fp::rand(&mut rng)).collect::<Vec<_>>();
+    let evaluations = fri::lde(&coefficients, 3);
+    let hasher = Poseidon2::<8>::new();
+    let transcript = Transcript::new(Poseidon2::<12>::new(), b"bench");
+    for arity in [2, 4, 8] {
+        let options = FriOptions::new(3, arity, 27, 31, 16).unwrap();
+        group.bench_function(format!("Prove 2^14 degree arity {arity}"), |b| {
+            b.iter(|| {
+                let proof: FriProof<Fp2, Digest> =
+                    fri::prove(&options, &hasher, &mut transcript.clone(), &evaluations);
+                proof
+            })
+        });
+        let proof: FriProof<Fp2, Digest> =
+            fri::prove(&options, &hasher, &mut transcript.clone(), &evaluations);
+        group.bench_function(format!("Verify 2^14 degree arity {arity}"), |b| {
+            b.iter(|| {
+                fri::verify(
+                    &options,
+                    &hasher,
+                    &mut transcript.clone(),
+                    evaluations.len(),
+                    &proof,
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_fri);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::UniformRand;
+use ark_ff_optimized::fp64::{monolith::Monolith, Fp};
+use criterion::{criterion_group, criterion_main};
+
+fn bench_monolith(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Monolith Fp=18446744069414584321");
+    let mut rng = ark_std::test_rng();
+
+    let permutation = Monolith::<8>::new();
+    let mut state: [Fp; 8] = core::array::from_fn(|_| Fp::rand(&mut rng));
+    group.bench_function("Permute width 8", |b| {
+        b.iter(|| permutation.permute(&mut state))
+    });
+
+    let permutation = Monolith::<12>::new();
+    let mut state: [Fp; 12] = core::array::from_fn(|_| Fp::rand(&mut rng));
+    group.bench_function("Permute width 12", |b| {
+        b.iter(|| permutation.permute(&mut state))
+    });
+
+    let left = core::array::from_fn(|_| Fp::rand(&mut rng));
+    let right = core::array::from_fn(|_| Fp::rand(&mut rng));
+    group.bench_function("Compress width 12", |b| {
+        b.iter(|| permutation.compress(&left, &right))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_monolith);
+criterion_main!(benches);
@@ -0,0 +1,109 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::{FftField, UniformRand};
+use ark_ff_optimized::fp64::{ntt, Fp};
+use ark_poly::{EvaluationDomain, MixedRadixEvaluationDomain, Radix2EvaluationDomain};
+use criterion::{criterion_group, criterion_main, BatchSize};
+
+const LOG_SIZES: [usize; 3] = [10, 14, 18];
+
+/// Sizes `3 * 2^k` supported by `MixedRadixEvaluationDomain`
+const LOG_MIXED_SIZES: [usize; 2] = [10, 14];
+
+fn bench_compare(c: &mut Criterion) {
+    let mut group = c.benchmark_group("NTT Fp=18446744069414584321");
+    let mut rng = ark_std::test_rng();
+    for log_n in LOG_SIZES {
+        let n = 1 << log_n;
+        let values = (0..n).map(|_| Fp::rand(&mut rng)).collect::<Vec<_>>();
+        let domain = Radix2EvaluationDomain::<Fp>::new(n).unwrap();
+        let coset = domain.get_coset(Fp::GENERATOR).unwrap();
+
+        let description = format!("Forward 2^{log_n}");
+        group.bench_function(
+            BenchmarkId::new("Radix2EvaluationDomain", &description),
+            |b| {
+                b.iter_batched_ref(
+                    || values.clone(),
+                    |v| domain.fft_in_place(v),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_function(BenchmarkId::new("fp64::ntt", &description), |b| {
+            b.iter_batched_ref(|| values.clone(), |v| ntt::ntt(v), BatchSize::LargeInput)
+        });
+
+        let description = format!("Inverse 2^{log_n}");
+        group.bench_function(
+            BenchmarkId::new("Radix2EvaluationDomain", &description),
+            |b| {
+                b.iter_batched_ref(
+                    || values.clone(),
+                    |v| domain.ifft_in_place(v),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_function(BenchmarkId::new("fp64::ntt", &description), |b| {
+            b.iter_batched_ref(|| values.clone(), |v| ntt::intt(v), BatchSize::LargeInput)
+        });
+
+        let description = format!("Coset forward 2^{log_n}");
+        group.bench_function(
+            BenchmarkId::new("Radix2EvaluationDomain", &description),
+            |b| {
+                b.iter_batched_ref(
+                    || values.clone(),
+                    |v| coset.fft_in_place(v),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_function(BenchmarkId::new("fp64::ntt", &description), |b| {
+            b.iter_batched_ref(
+                || values.clone(),
+                |v| ntt::coset_ntt(v, Fp::GENERATOR),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    for log_n in LOG_MIXED_SIZES {
+        let n = 3 << log_n;
+        let values = (0..n).map(|_| Fp::rand(&mut rng)).collect::<Vec<_>>();
+        let domain = MixedRadixEvaluationDomain::<Fp>::new(n).unwrap();
+
+        let description = format!("Forward 3*2^{log_n}");
+        group.bench_function(
+            BenchmarkId::new("MixedRadixEvaluationDomain", &description),
+            |b| {
+                b.iter_batched_ref(
+                    || values.clone(),
+                    |v| domain.fft_in_place(v),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_function(BenchmarkId::new("fp64::ntt", &description), |b| {
+            b.iter_batched_ref(|| values.clone(), |v| ntt::ntt(v), BatchSize::LargeInput)
+        });
+
+        let description = format!("Inverse 3*2^{log_n}");
+        group.bench_function(
+            BenchmarkId::new("MixedRadixEvaluationDomain", &description),
+            |b| {
+                b.iter_batched_ref(
+                    || values.clone(),
+                    |v| domain.ifft_in_place(v),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_function(BenchmarkId::new("fp64::ntt", &description), |b| {
+            b.iter_batched_ref(|| values.clone(), |v| ntt::intt(v), BatchSize::LargeInput)
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_compare);
+criterion_main!(benches);
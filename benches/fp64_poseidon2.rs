@@ -0,0 +1,30 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::UniformRand;
+use ark_ff_optimized::fp64::{poseidon2::Poseidon2, Fp};
+use criterion::{criterion_group, criterion_main};
+
+fn bench_poseidon2(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Poseidon2 Fp=18446744069414584321");
+    let mut rng = ark_std::test_rng();
+
+    let permutation = Poseidon2::<8>::new();
+    let mut state: [Fp; 8] = core::array::from_fn(|_| Fp::rand(&mut rng));
+    group.bench_function("Permute width 8", |b| {
+        b.iter(|| permutation.permute(&mut state))
+    });
+
+    let permutation = Poseidon2::<12>::new();
+    let mut state: [Fp; 12] = core::array::from_fn(|_| Fp::rand(&mut rng));
+    group.bench_function("Permute width 12", |b| {
+        b.iter(|| permutation.permute(&mut state))
+    });
+
+    let elements = (0..1024).map(|_| Fp::rand(&mut rng)).collect::<Vec<_>>();
+    group.bench_function("Hash 1024 elements width 12", |b| {
+        b.iter(|| permutation.hash(&elements))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_poseidon2);
+criterion_main!(benches);
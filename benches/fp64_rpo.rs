@@ -0,0 +1,24 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::UniformRand;
+use ark_ff_optimized::fp64::{rpo, Fp};
+use criterion::{criterion_group, criterion_main};
+
+fn bench_rpo(c: &mut Criterion) {
+    let mut group = c.benchmark_group("RPO Fp=18446744069414584321");
+    let mut rng = ark_std::test_rng();
+
+    let mut state: [Fp; rpo::STATE_WIDTH] = core::array::from_fn(|_| Fp::rand(&mut rng));
+    group.bench_function("Permute", |b| b.iter(|| rpo::permute(&mut state)));
+
+    let digests = [(); 2].map(|()| [(); rpo::DIGEST_LEN].map(|()| Fp::rand(&mut rng)));
+    group.bench_function("Merge", |b| b.iter(|| rpo::merge(&digests)));
+
+    let elements = (0..1024).map(|_| Fp::rand(&mut rng)).collect::<Vec<_>>();
+    group.bench_function("Hash 1024 elements", |b| {
+        b.iter(|| rpo::hash_elements(&elements))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_rpo);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::UniformRand;
+use ark_ff_optimized::fp64::{tip5, Fp};
+use criterion::{criterion_group, criterion_main};
+
+fn bench_tip5(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Tip5 Fp=18446744069414584321");
+    let mut rng = ark_std::test_rng();
+
+    let mut state: [Fp; tip5::STATE_WIDTH] = core::array::from_fn(|_| Fp::rand(&mut rng));
+    group.bench_function("Permute", |b| b.iter(|| tip5::permute(&mut state)));
+
+    let left = [(); tip5::DIGEST_LEN].map(|()| Fp::rand(&mut rng));
+    let right = [(); tip5::DIGEST_LEN].map(|()| Fp::rand(&mut rng));
+    group.bench_function("Hash pair", |b| b.iter(|| tip5::hash_pair(&left, &right)));
+
+    let elements = (0..1000).map(|_| Fp::rand(&mut rng)).collect::<Vec<_>>();
+    group.bench_function("Hash 1000 elements", |b| {
+        b.iter(|| tip5::hash_varlen(&elements))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_tip5);
+criterion_main!(benches);
@@ -0,0 +1,13 @@
+use ark_algebra_bench_templates::*;
+use ark_ff_optimized::field_compare;
+use ark_ff_optimized::koalabear::Fp as Specialized;
+use criterion::criterion_main;
+
+#[derive(ark_ff::MontConfig)]
+#[modulus = "2130706433"]
+#[generator = "3"]
+pub struct FpParams;
+pub type Generic = ark_ff::Fp64<ark_ff::MontBackend<FpParams, 1>>;
+
+field_compare!(prime; "Fp=2130706433"; fp2130706433; Generic, Specialized);
+criterion_main!(fp2130706433::benches);
@@ -0,0 +1,53 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::UniformRand;
+use ark_ff_optimized::{
+    fp31, fp64,
+    merkle::{MerkleHasher, MerkleTree},
+};
+use criterion::{criterion_group, criterion_main};
+
+const NUM_LEAVES: usize = 1 << 12;
+
+const LEAF_SIZE: usize = 8;
+
+fn bench_tree<H: MerkleHasher + Clone>(c: &mut Criterion, name: &str, hasher: H)
+where
+    H::Field: UniformRand,
+{
+    let mut group = c.benchmark_group(name);
+    let mut rng = ark_std::test_rng();
+    let leaves = (0..NUM_LEAVES)
+        .map(|_| (0..LEAF_SIZE).map(|_| H::Field::rand(&mut rng)).collect())
+        .collect::<Vec<Vec<_>>>();
+    group.bench_function("Commit 4096 leaves of 8 elements", |b| {
+        b.iter(|| MerkleTree::new(hasher.clone(), leaves.clone()))
+    });
+
+    let tree = MerkleTree::new(hasher, leaves).unwrap();
+    let indices = (0..NUM_LEAVES).step_by(64).collect::<Vec<_>>();
+    let proof = tree.open_batch(&indices).unwrap();
+    let opened = indices
+        .iter()
+        .map(|&i| tree.leaf(i).unwrap())
+        .collect::<Vec<_>>();
+    group.bench_function("Verify 64 leaf batch opening", |b| {
+        b.iter(|| proof.verify(tree.hasher(), &tree.root(), NUM_LEAVES, &indices, &opened))
+    });
+    group.finish();
+}
+
+fn bench_merkle(c: &mut Criterion) {
+    bench_tree(
+        c,
+        "Merkle Poseidon2 Fp=2147483647",
+        fp31::poseidon2::Poseidon2::<16>::new(),
+    );
+    bench_tree(
+        c,
+        "Merkle Poseidon2 Fp=18446744069414584321",
+        fp64::poseidon2::Poseidon2::<12>::new(),
+    );
+}
+
+criterion_group!(benches, bench_merkle);
+criterion_main!(benches);
@@ -0,0 +1,95 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::UniformRand;
+use ark_ff_optimized::{
+    fp31::{self, packed::PackedFp31x8},
+    fp64::{self, packed::PackedFp64x4},
+    packed::{Packed, PackedField},
+};
+use criterion::{black_box, criterion_group, criterion_main};
+
+const NUM_ELEMENTS: usize = 1 << 12;
+
+fn pack<P: PackedField>(values: &[P::Scalar]) -> Vec<P> {
+    values.chunks_exact(P::WIDTH).map(P::from_slice).collect()
+}
+
+/// Compares lane-wise multiplication and addition of packed values with the
+/// same operations on the scalar field.
+fn bench_packed<P: PackedField, Q: PackedField<Scalar = P::Scalar>>(
+    c: &mut Criterion,
+    name: &str,
+    packed_name: &str,
+    portable_name: &str,
+) where
+    P::Scalar: UniformRand,
+{
+    let mut group = c.benchmark_group(name);
+    let mut rng = ark_std::test_rng();
+    let a = (0..NUM_ELEMENTS)
+        .map(|_| P::Scalar::rand(&mut rng))
+        .collect::<Vec<_>>();
+    let b = (0..NUM_ELEMENTS)
+        .map(|_| P::Scalar::rand(&mut rng))
+        .collect::<Vec<_>>();
+    let (packed_a, packed_b) = (pack::<P>(&a), pack::<P>(&b));
+    let (portable_a, portable_b) = (pack::<Q>(&a), pack::<Q>(&b));
+
+    let description = format!("Multiplication of {NUM_ELEMENTS} elements");
+    group.bench_function(BenchmarkId::new("Scalar", &description), |bench| {
+        bench.iter(|| {
+            let (a, b) = (black_box(&a), black_box(&b));
+            a.iter().zip(b).map(|(x, y)| *x * y).collect::<Vec<_>>()
+        })
+    });
+    group.bench_function(BenchmarkId::new(packed_name, &description), |bench| {
+        bench.iter(|| {
+            let (a, b) = (black_box(&packed_a), black_box(&packed_b));
+            a.iter().zip(b).map(|(x, y)| *x * *y).collect::<Vec<_>>()
+        })
+    });
+    group.bench_function(BenchmarkId::new(portable_name, &description), |bench| {
+        bench.iter(|| {
+            let (a, b) = (black_box(&portable_a), black_box(&portable_b));
+            a.iter().zip(b).map(|(x, y)| *x * *y).collect::<Vec<_>>()
+        })
+    });
+
+    let description = format!("Addition of {NUM_ELEMENTS} elements");
+    group.bench_function(BenchmarkId::new("Scalar", &description), |bench| {
+        bench.iter(|| {
+            let (a, b) = (black_box(&a), black_box(&b));
+            a.iter().zip(b).map(|(x, y)| *x + y).collect::<Vec<_>>()
+        })
+    });
+    group.bench_function(BenchmarkId::new(packed_name, &description), |bench| {
+        bench.iter(|| {
+            let (a, b) = (black_box(&packed_a), black_box(&packed_b));
+            a.iter().zip(b).map(|(x, y)| *x + *y).collect::<Vec<_>>()
+        })
+    });
+    group.bench_function(BenchmarkId::new(portable_name, &description), |bench| {
+        bench.iter(|| {
+            let (a, b) = (black_box(&portable_a), black_box(&portable_b));
+            a.iter().zip(b).map(|(x, y)| *x + *y).collect::<Vec<_>>()
+        })
+    });
+    group.finish();
+}
+
+fn bench_compare(c: &mut Criterion) {
+    bench_packed::<PackedFp31x8, Packed<fp31::Fp, 8>>(
+        c,
+        "Packed Fp=2147483647",
+        "PackedFp31x8",
+        "Packed<Fp, 8>",
+    );
+    bench_packed::<PackedFp64x4, Packed<fp64::Fp, 4>>(
+        c,
+        "Packed Fp=18446744069414584321",
+        "PackedFp64x4",
+        "Packed<Fp, 4>",
+    );
+}
+
+criterion_group!(benches, bench_compare);
+criterion_main!(benches);
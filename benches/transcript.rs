@@ -0,0 +1,30 @@
+use ark_algebra_bench_templates::*;
+use ark_ff::UniformRand;
+use ark_ff_optimized::{fp31, fp64, transcript::Transcript};
+use criterion::{criterion_group, criterion_main};
+
+fn bench_transcript(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Transcript");
+    let mut rng = ark_std::test_rng();
+
+    let elements = (0..64)
+        .map(|_| fp64::Fp::rand(&mut rng))
+        .collect::<Vec<_>>();
+    let transcript = Transcript::new(fp64::poseidon2::Poseidon2::<12>::new(), b"bench");
+    group.bench_function("Absorb 64 and squeeze Fp=18446744069414584321", |b| {
+        b.iter(|| {
+            let mut transcript = transcript.clone();
+            transcript.absorb_slice(&elements);
+            transcript.squeeze()
+        })
+    });
+
+    let transcript = Transcript::new(fp31::poseidon2::Poseidon2::<16>::new(), b"bench");
+    group.bench_function("Grind 12 bits Fp=2147483647", |b| {
+        b.iter(|| transcript.clone().grind(12))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_transcript);
+criterion_main!(benches);
@@ -0,0 +1,41 @@
+//! An implementation of the 31-bit `BabyBear` prime field with modulus `2^31 -
+//! 2^27 + 1`.
+//!
+//! Unlike the Mersenne-31 field in [`crate::fp31`] this field has a large
+//! multiplicative subgroup of order `2^27` which makes it suitable for radix-2
+//! NTTs. Field elements are stored in Montgomery form with auxiliary modulus
+//! `R = 2^32`.
+//!
+//! This field and its implementation has a couple of attractive properties:
+//! * Addition of two field elements never overflows a 32-bit int.
+//! * Multiplication only needs a single 32x32 -> 64-bit product followed by a
+//!   32-bit Montgomery reduction.
+//! * Efficient for GPUs which optimize throughput for 32-bit arithmetic.
+
+crate::mont31::mont31_field! {
+    // `p = 2^31 - 2^27 + 1`
+    modulus: 2_013_265_921,
+    mu: 2_281_701_377,
+    r2: 1_172_168_163,
+    generator: 31,
+    two_adicity: 27,
+    two_adic_root_of_unity: 440_564_289,
+    // Computes `x^(p-2)` where `p-2 = 0b1110111...1` has 27 trailing ones
+    inverse: |x| {
+        let t11 = Fp::mul(Fp::sq(x), x);
+        let t111 = Fp::mul(Fp::sq(t11), x);
+        let x6 = Fp::mul(Fp::sqn(t111, 3), t111);
+        let x12 = Fp::mul(Fp::sqn(x6, 6), x6);
+        let x24 = Fp::mul(Fp::sqn(x12, 12), x12);
+        let x27 = Fp::mul(Fp::sqn(x24, 3), t111);
+        Fp::mul(Fp::sqn(t111, 28), x27)
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fp as TestField;
+    use ark_algebra_test_templates::test_field;
+
+    test_field!(generated; TestField; prime);
+}
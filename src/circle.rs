@@ -0,0 +1,603 @@
+//! The circle group `x^2 + y^2 = 1` over the Mersenne-31 field and the Circle
+//! FFT from <https://eprint.iacr.org/2024/278.pdf>.
+//!
+//! The Mersenne-31 field has no 2^k roots of unity for k>1 but the circle
+//! group over it has order `p + 1 = 2^31`. The group law is the complex
+//! multiplication `(x0, y0) * (x1, y1) = (x0*x1 - y0*y1, x0*y1 + x1*y0)`. It is
+//! written additively here so that `n * P` denotes repeated composition.
+//!
+//! A [`CircleDomain`] of size `2^n` is a union of a coset of size `2^(n-1)` and
+//! its conjugate. Polynomials over a domain are represented in the basis
+//! `y^j0 * x^j1 * π(x)^j2 * π(π(x))^j3 * ...` where `π(x) = 2x^2 - 1` is the
+//! x-coordinate of the doubling map and `j = j0 + 2*j1 + 4*j2 + ...` is the
+//! index of the coefficient.
+
+use crate::fp31::Fp;
+use ark_ff::{batch_inversion, Field, One, Zero};
+use ark_std::vec::Vec;
+use core::ops::{Add, AddAssign, Neg, Sub};
+
+pub mod fri;
+
+/// Base-2 logarithm of the order of the circle group
+pub const LOG_ORDER: u32 = 31;
+
+/// A point on the circle `x^2 + y^2 = 1`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CirclePoint {
+    pub x: Fp,
+    pub y: Fp,
+}
+
+impl CirclePoint {
+    /// The identity of the circle group `(1, 0)`
+    pub const IDENTITY: Self = Self { x: Fp(1), y: Fp(0) };
+
+    /// A generator of the circle group of order `2^31`
+    pub const GENERATOR: Self = Self {
+        x: Fp(2),
+        y: Fp(1_268_011_823),
+    };
+
+    /// Creates a new point. Returns `None` if `(x, y)` is not on the circle.
+    #[must_use]
+    pub fn new(x: Fp, y: Fp) -> Option<Self> {
+        let point = Self { x, y };
+        point.is_on_circle().then_some(point)
+    }
+
+    /// Returns a generator of the subgroup of order `2^log_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_size` is larger than [`LOG_ORDER`].
+    #[must_use]
+    pub fn subgroup_generator(log_size: u32) -> Self {
+        assert!(log_size <= LOG_ORDER, "subgroup is larger than the group");
+        Self::GENERATOR.repeated_double(LOG_ORDER - log_size)
+    }
+
+    #[must_use]
+    pub fn is_on_circle(&self) -> bool {
+        (self.x.square() + self.y.square()).is_one()
+    }
+
+    /// Returns `2 * self` which is `(2x^2 - 1, 2xy)`.
+    #[must_use]
+    pub fn double(&self) -> Self {
+        Self {
+            x: double_x(self.x),
+            y: (self.x * self.y).double(),
+        }
+    }
+
+    /// Returns `2^n * self`.
+    #[must_use]
+    pub fn repeated_double(&self, n: u32) -> Self {
+        let mut res = *self;
+        for _ in 0..n {
+            res = res.double();
+        }
+        res
+    }
+
+    /// Returns the inverse in the circle group `(x, -y)`.
+    #[must_use]
+    pub fn conjugate(&self) -> Self {
+        Self {
+            x: self.x,
+            y: -self.y,
+        }
+    }
+
+    /// Returns `self + (-1, 0)` which is the point `(-x, -y)`.
+    #[must_use]
+    pub fn antipode(&self) -> Self {
+        Self {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+
+    /// Returns `scalar * self` using double-and-add.
+    #[must_use]
+    pub fn scalar_mul(&self, mut scalar: u64) -> Self {
+        let mut res = Self::IDENTITY;
+        let mut base = *self;
+        while scalar != 0 {
+            if scalar & 1 == 1 {
+                res += base;
+            }
+            base = base.double();
+            scalar >>= 1;
+        }
+        res
+    }
+}
+
+/// The x-coordinate of the doubling map `π(x) = 2x^2 - 1`.
+#[inline]
+fn double_x(x: Fp) -> Fp {
+    x.square().double() - Fp::one()
+}
+
+impl Default for CirclePoint {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Add for CirclePoint {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            x: self.x * rhs.x - self.y * rhs.y,
+            y: self.x * rhs.y + rhs.x * self.y,
+        }
+    }
+}
+
+impl AddAssign for CirclePoint {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Neg for CirclePoint {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        self.conjugate()
+    }
+}
+
+impl Sub for CirclePoint {
+    type Output = Self;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self {
+        self + rhs.conjugate()
+    }
+}
+
+/// The coset `initial + <step>` of size `2^log_size` where `step` generates the
+/// subgroup of order `2^log_size`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Coset {
+    initial: CirclePoint,
+    step: CirclePoint,
+    log_size: u32,
+}
+
+impl Coset {
+    /// Creates the coset `initial + <G_log_size>`.
+    #[must_use]
+    pub fn new(initial: CirclePoint, log_size: u32) -> Self {
+        Self {
+            initial,
+            step: CirclePoint::subgroup_generator(log_size),
+            log_size,
+        }
+    }
+
+    /// The subgroup of order `2^log_size`.
+    #[must_use]
+    pub fn subgroup(log_size: u32) -> Self {
+        Self::new(CirclePoint::IDENTITY, log_size)
+    }
+
+    /// The coset `G_(log_size+1) + <G_log_size>` i.e. all odd multiples of
+    /// `G_(log_size+1)`. This coset is closed under conjugation.
+    #[must_use]
+    pub fn odds(log_size: u32) -> Self {
+        Self::new(CirclePoint::subgroup_generator(log_size + 1), log_size)
+    }
+
+    /// The coset `G_(log_size+2) + <G_log_size>`. The union of this coset and
+    /// its conjugate is [`Coset::odds`] of size `2^(log_size+1)`.
+    #[must_use]
+    pub fn half_odds(log_size: u32) -> Self {
+        Self::new(CirclePoint::subgroup_generator(log_size + 2), log_size)
+    }
+
+    #[must_use]
+    pub const fn initial(&self) -> CirclePoint {
+        self.initial
+    }
+
+    #[must_use]
+    pub const fn step(&self) -> CirclePoint {
+        self.step
+    }
+
+    #[must_use]
+    pub const fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        1 << self.log_size
+    }
+
+    /// Returns the point `initial + index * step`.
+    #[must_use]
+    pub fn at(&self, index: usize) -> CirclePoint {
+        self.initial + self.step.scalar_mul(index as u64)
+    }
+
+    /// Returns the coset `2 * self` which has half the size.
+    #[must_use]
+    pub fn double(&self) -> Self {
+        Self {
+            initial: self.initial.double(),
+            step: self.step.double(),
+            log_size: self.log_size.saturating_sub(1),
+        }
+    }
+
+    /// Returns the coset `-self`.
+    #[must_use]
+    pub fn conjugate(&self) -> Self {
+        Self {
+            initial: self.initial.conjugate(),
+            step: self.step.conjugate(),
+            log_size: self.log_size,
+        }
+    }
+
+    /// Iterates over the points of the coset in order.
+    pub fn iter(&self) -> impl Iterator<Item = CirclePoint> {
+        let step = self.step;
+        core::iter::successors(Some(self.initial), move |&p| Some(p + step)).take(self.size())
+    }
+}
+
+/// A domain of size `2^log_size` made up of a half coset and its conjugate.
+/// Points `i` and `i + size/2` are conjugates of each other.
+///
+/// Domains are only built by [`CircleDomain::new`] and
+/// [`CircleDomain::standard`], so the FFT can rely on the half coset being
+/// valid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CircleDomain {
+    half_coset: Coset,
+}
+
+impl CircleDomain {
+    /// Creates the domain `half_coset ∪ -half_coset`. Returns `None` if the
+    /// step of the half coset doesn't have order `2^log_size` or the half
+    /// coset intersects its conjugate, which also rules out points with
+    /// `y = 0`.
+    ///
+    /// The half coset `P + <G>` meets `-P + <G>` exactly when `2P` is in
+    /// `<G>`, and the group has order `2^31` so that is when `2^log_size * 2P`
+    /// is the identity.
+    #[must_use]
+    pub fn new(half_coset: Coset) -> Option<Self> {
+        let Coset {
+            initial,
+            step,
+            log_size,
+        } = half_coset;
+        let has_order = match log_size {
+            0 => step == CirclePoint::IDENTITY,
+            _ => step.repeated_double(log_size - 1) == CirclePoint::IDENTITY.antipode(),
+        };
+        let is_disjoint = initial.repeated_double(log_size + 1) != CirclePoint::IDENTITY;
+        (has_order && is_disjoint).then_some(Self { half_coset })
+    }
+
+    /// The standard domain of size `2^log_size` made up of all odd multiples
+    /// of `G_(log_size+1)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_size` is zero or larger than `LOG_ORDER - 1`.
+    #[must_use]
+    pub fn standard(log_size: u32) -> Self {
+        assert!(log_size >= 1, "circle domains have at least two points");
+        assert!(log_size < LOG_ORDER, "domain is too large");
+        Self {
+            half_coset: Coset::half_odds(log_size - 1),
+        }
+    }
+
+    /// Returns the half coset whose union with its conjugate is the domain.
+    #[must_use]
+    pub const fn half_coset(&self) -> Coset {
+        self.half_coset
+    }
+
+    #[must_use]
+    pub const fn log_size(&self) -> u32 {
+        self.half_coset.log_size + 1
+    }
+
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        1 << self.log_size()
+    }
+
+    /// Returns the point at `index`.
+    #[must_use]
+    pub fn at(&self, index: usize) -> CirclePoint {
+        let half_size = self.half_coset.size();
+        if index < half_size {
+            self.half_coset.at(index)
+        } else {
+            self.half_coset.at(index - half_size).conjugate()
+        }
+    }
+
+    /// Iterates over the points of the domain in order.
+    pub fn iter(&self) -> impl Iterator<Item = CirclePoint> {
+        self.half_coset
+            .iter()
+            .chain(self.half_coset.iter().map(|p| p.conjugate()))
+    }
+
+    /// Interpolates evaluations over the domain (inverse circle FFT).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of evaluations differs from the domain size.
+    #[must_use]
+    pub fn interpolate(&self, mut evals: Vec<Fp>) -> CirclePoly {
+        assert_eq!(
+            evals.len(),
+            self.size(),
+            "evaluations must match domain size"
+        );
+        let twiddles = self.twiddles();
+        for (layer, twiddles) in twiddles.into_iter().enumerate() {
+            let mut inv_twiddles = twiddles;
+            batch_inversion(&mut inv_twiddles);
+            let half = evals.len() >> (layer + 1);
+            for block in evals.chunks_mut(2 * half) {
+                let (lo, hi) = block.split_at_mut(half);
+                for ((a, b), t) in lo.iter_mut().zip(hi).zip(&inv_twiddles) {
+                    let (v0, v1) = (*a, *b);
+                    *a = v0 + v1;
+                    *b = (v0 - v1) * t;
+                }
+            }
+        }
+        let size_inv = Fp::from(self.size() as u64).inverse().unwrap();
+        for v in &mut evals {
+            *v *= size_inv;
+        }
+        bit_reverse_permute(&mut evals);
+        CirclePoly::new(evals)
+    }
+
+    /// Returns the twiddles used by each layer of the circle FFT. The first
+    /// layer uses the y-coordinates of the half coset and every further layer
+    /// uses the x-coordinates of the repeatedly doubled half coset.
+    fn twiddles(&self) -> Vec<Vec<Fp>> {
+        let mut twiddles = Vec::with_capacity(self.log_size() as usize);
+        twiddles.push(self.half_coset.iter().map(|p| p.y).collect());
+        let mut coset = self.half_coset;
+        for _ in 1..self.log_size() {
+            twiddles.push(coset.iter().take(coset.size() / 2).map(|p| p.x).collect());
+            coset = coset.double();
+        }
+        twiddles
+    }
+}
+
+/// A polynomial over the circle with coefficients in the basis described in
+/// the [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CirclePoly {
+    pub coeffs: Vec<Fp>,
+}
+
+impl CirclePoly {
+    /// Creates a polynomial from its coefficients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of coefficients is not a power of two.
+    #[must_use]
+    pub fn new(coeffs: Vec<Fp>) -> Self {
+        assert!(
+            coeffs.len().is_power_of_two(),
+            "coefficients must be a power of two"
+        );
+        Self { coeffs }
+    }
+
+    #[must_use]
+    pub const fn log_size(&self) -> u32 {
+        self.coeffs.len().ilog2()
+    }
+
+    /// Evaluates the polynomial at an arbitrary point on the circle.
+    #[must_use]
+    pub fn eval_at_point(&self, point: CirclePoint) -> Fp {
+        // Folds coefficients from the highest basis variable down, mirroring
+        // Horner's rule for the multilinear basis.
+        let mut mappings = Vec::with_capacity(self.log_size() as usize);
+        if self.log_size() > 0 {
+            mappings.push(point.y);
+            let mut x = point.x;
+            for _ in 1..self.log_size() {
+                mappings.push(x);
+                x = double_x(x);
+            }
+        }
+        fold(&self.coeffs, &mappings)
+    }
+
+    /// Evaluates the polynomial over a domain (forward circle FFT). The domain
+    /// can be larger than the polynomial for low degree extensions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the domain is smaller than the polynomial.
+    #[must_use]
+    pub fn evaluate(&self, domain: &CircleDomain) -> Vec<Fp> {
+        assert!(domain.size() >= self.coeffs.len(), "domain is too small");
+        let mut values = self.coeffs.clone();
+        values.resize(domain.size(), Fp::zero());
+        bit_reverse_permute(&mut values);
+        let twiddles = domain.twiddles();
+        for (layer, twiddles) in twiddles.into_iter().enumerate().rev() {
+            let half = values.len() >> (layer + 1);
+            for block in values.chunks_mut(2 * half) {
+                let (lo, hi) = block.split_at_mut(half);
+                for ((a, b), t) in lo.iter_mut().zip(hi).zip(&twiddles) {
+                    let tmp = *b * t;
+                    *b = *a - tmp;
+                    *a += tmp;
+                }
+            }
+        }
+        values
+    }
+}
+
+/// Computes `Σ c_j Π_k m_k^(j_k)` where `j_k` is the k-th bit of `j`.
+fn fold(coeffs: &[Fp], mappings: &[Fp]) -> Fp {
+    match mappings.split_last() {
+        None => coeffs[0],
+        Some((&m, rest)) => {
+            let (lo, hi) = coeffs.split_at(coeffs.len() / 2);
+            fold(lo, rest) + m * fold(hi, rest)
+        }
+    }
+}
+
+/// Permutes a slice of length `2^k` by reversing the bits of each index.
+fn bit_reverse_permute<T>(values: &mut [T]) {
+    let n = values.len();
+    if n <= 1 {
+        return;
+    }
+    let shift = usize::BITS - n.ilog2();
+    for i in 0..n {
+        let j = i.reverse_bits() >> shift;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CircleDomain, CirclePoint, CirclePoly, Coset, LOG_ORDER};
+    use crate::fp31::Fp;
+    use ark_ff::Zero;
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    #[test]
+    fn generator_has_order_two_pow_31() {
+        let g = CirclePoint::GENERATOR;
+        assert!(g.is_on_circle());
+        let half = g.repeated_double(LOG_ORDER - 1);
+        assert_eq!(half, CirclePoint::new(-Fp(1), Fp(0)).unwrap());
+        assert_eq!(half.double(), CirclePoint::IDENTITY);
+    }
+
+    #[test]
+    fn group_law() {
+        let g = CirclePoint::subgroup_generator(10);
+        let a = g.scalar_mul(123);
+        let b = g.scalar_mul(456);
+        assert_eq!(a + b, g.scalar_mul(579));
+        assert_eq!(a - a, CirclePoint::IDENTITY);
+        assert_eq!(a.double(), a + a);
+        assert_eq!(g.scalar_mul(1 << 10), CirclePoint::IDENTITY);
+        assert_eq!(a.antipode(), a + g.scalar_mul(1 << 9));
+    }
+
+    #[test]
+    fn coset_points() {
+        let coset = Coset::odds(5);
+        let points = coset.iter().collect::<Vec<_>>();
+        assert_eq!(points.len(), 32);
+        for (i, p) in points.iter().enumerate() {
+            assert_eq!(*p, coset.at(i));
+            assert!(p.is_on_circle());
+            assert!(points.contains(&p.conjugate()));
+        }
+    }
+
+    #[test]
+    fn domain_is_union_of_half_coset_and_conjugate() {
+        let domain = CircleDomain::standard(6);
+        let odds = Coset::odds(6).iter().collect::<Vec<_>>();
+        let points = domain.iter().collect::<Vec<_>>();
+        assert_eq!(points.len(), domain.size());
+        for (i, p) in points.iter().enumerate() {
+            assert_eq!(*p, domain.at(i));
+            assert!(odds.contains(p));
+        }
+    }
+
+    #[test]
+    fn interpolate_evaluate_round_trip() {
+        let mut rng = test_rng();
+        for log_size in 1..=10 {
+            let domain = CircleDomain::standard(log_size);
+            let evals = (0..domain.size())
+                .map(|_| Fp::rand(&mut rng))
+                .collect::<Vec<_>>();
+            let poly = domain.interpolate(evals.clone());
+            assert_eq!(poly.evaluate(&domain), evals);
+            for (i, p) in domain.iter().enumerate() {
+                assert_eq!(poly.eval_at_point(p), evals[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn evaluate_on_larger_domain_extends_polynomial() {
+        let mut rng = test_rng();
+        let coeffs = (0..16).map(|_| Fp::rand(&mut rng)).collect::<Vec<_>>();
+        let poly = CirclePoly::new(coeffs);
+        let domain = CircleDomain::standard(7);
+        let evals = poly.evaluate(&domain);
+        for (i, p) in domain.iter().enumerate() {
+            assert_eq!(poly.eval_at_point(p), evals[i]);
+        }
+        let mut coeffs = domain.interpolate(evals).coeffs;
+        assert!(coeffs.split_off(16).iter().all(Fp::is_zero));
+        assert_eq!(coeffs, poly.coeffs);
+    }
+
+    #[test]
+    fn interpolate_over_non_standard_domain() {
+        let mut rng = test_rng();
+        let initial = CirclePoint::GENERATOR.scalar_mul(12_345);
+        let domain = CircleDomain::new(Coset::new(initial, 4)).unwrap();
+        let evals = (0..domain.size())
+            .map(|_| Fp::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let poly = domain.interpolate(evals.clone());
+        assert_eq!(poly.evaluate(&domain), evals);
+    }
+
+    #[test]
+    fn domain_rejects_half_cosets_meeting_their_conjugate() {
+        let standard = CircleDomain::standard(6);
+        assert_eq!(CircleDomain::new(standard.half_coset()), Some(standard));
+        // all contain a point with y = 0
+        assert_eq!(CircleDomain::new(Coset::subgroup(5)), None);
+        assert_eq!(CircleDomain::new(Coset::subgroup(0)), None);
+        let antipode = CirclePoint::IDENTITY.antipode();
+        assert_eq!(CircleDomain::new(Coset::new(antipode, 3)), None);
+        // closed under conjugation
+        assert_eq!(CircleDomain::new(Coset::odds(5)), None);
+        // the step generates a smaller subgroup so points repeat
+        let mut coset = standard.half_coset;
+        coset.step = coset.step.double();
+        assert_eq!(CircleDomain::new(coset), None);
+    }
+}
@@ -0,0 +1,688 @@
+//! The circle FRI low degree test over [`Fp`] from
+//! <https://eprint.iacr.org/2024/278.pdf> with challenges in an extension field.
+//!
+//! Mersenne-31 lacks the multiplicative subgroups of order `2^k` the regular
+//! FRI folds over. Instead the prover commits to the evaluations of a
+//! [`CirclePoly`] with `d` coefficients on the standard [`CircleDomain`] of
+//! size `n = d * 2^log_blowup`, which [`lde`] computes with the circle FFT.
+//!
+//! The first round folds along y. Writing `f(x, y) = f0(x) + y * f1(x)`, the
+//! values at the conjugate points `(x, y)` and `(x, -y)`, which are at indices
+//! `i` and `i + n/2`, determine `f0(x) + β * f1(x)` on the x-coordinates of
+//! the half coset. Every further round folds a line polynomial along x with
+//! the doubling map `π(x) = 2x^2 - 1`. Writing `g(x) = g0(π(x)) + x * g1(π(x))`,
+//! the values at `x` and `-x`, which are at indices `i` and `i + m/2` of a
+//! layer of size `m`, determine `g0 + β * g1` on the doubled coset. The two
+//! values that fold together form leaf `i` of the layer's Merkle tree, so a
+//! query opens a single leaf per layer.
+//!
+//! Folding stops once the line polynomial has at most
+//! `2^log_last_layer_degree_bound` coefficients. The prover then sends them in
+//! the basis `x^j0 * π(x)^j1 * π(π(x))^j2 * ...` instead of committing to the
+//! last layer. After an optional proof of work the verifier draws query
+//! positions, recomputes the folded values from the opened pairs and checks
+//! them against the next layer and finally against the last layer polynomial.
+//!
+//! Challenges live in any field `E` with base prime field [`Fp`]. Only the
+//! quartic extension [`qm31::Fp4`](crate::qm31::Fp4) is large enough for the
+//! soundness of [`FriOptions::security_bits`] to hold. The commitments, the
+//! transcript and the openings are handled by [`crate::fri`] with pairs as
+//! cosets of size 2.
+
+use super::{bit_reverse_permute, double_x, CircleDomain, CirclePoly, Coset, LOG_ORDER};
+pub use crate::fri::{FriError, FriQueries};
+use crate::{
+    fp31::Fp,
+    fri::{
+        commit, finish_commit_phase, open, open_layers, opened_cosets, replay_commit_phase,
+        verify_layers,
+    },
+    merkle::{MerkleHasher, MerkleTree},
+    transcript::{DuplexPermutation, Transcript},
+};
+use ark_ff::{batch_inversion, Field, PrimeField};
+use ark_std::vec::Vec;
+
+/// The inverse of two
+const TWO_INV: Fp = Fp(1 << 30);
+
+/// Bound on [`FriOptions::security_bits`] given by the size of the challenge
+/// field [`qm31::Fp4`](crate::qm31::Fp4), which has about `2^124` elements
+pub const MAX_SECURITY_BITS: u32 = 4 * Fp::MODULUS_BIT_SIZE;
+
+/// Parameters of the circle FRI protocol shared by the prover and verifier
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FriOptions {
+    log_blowup: u32,
+    log_last_layer_degree_bound: u32,
+    num_queries: usize,
+    proof_of_work_bits: u32,
+}
+
+impl FriOptions {
+    /// Returns `None` unless `log_blowup` is positive, both logarithms are
+    /// below [`LOG_ORDER`], there is at least one query and the proof of work
+    /// has fewer bits than the modulus.
+    #[must_use]
+    pub const fn new(
+        log_blowup: u32,
+        log_last_layer_degree_bound: u32,
+        num_queries: usize,
+        proof_of_work_bits: u32,
+    ) -> Option<Self> {
+        if log_blowup == 0
+            || log_blowup >= LOG_ORDER
+            || log_last_layer_degree_bound >= LOG_ORDER
+            || num_queries == 0
+            || proof_of_work_bits >= Fp::MODULUS_BIT_SIZE
+        {
+            return None;
+        }
+        Some(Self {
+            log_blowup,
+            log_last_layer_degree_bound,
+            num_queries,
+            proof_of_work_bits,
+        })
+    }
+
+    /// Returns the options with the fewest queries whose
+    /// [`Self::security_bits`] reach `security_bits`, or `None` if
+    /// `security_bits` exceeds [`MAX_SECURITY_BITS`] or [`Self::new`] rejects
+    /// the parameters.
+    #[must_use]
+    pub const fn with_security_bits(
+        security_bits: u32,
+        log_blowup: u32,
+        log_last_layer_degree_bound: u32,
+        proof_of_work_bits: u32,
+    ) -> Option<Self> {
+        if log_blowup == 0 || security_bits > MAX_SECURITY_BITS {
+            return None;
+        }
+        let num_queries = security_bits
+            .saturating_sub(proof_of_work_bits)
+            .div_ceil(log_blowup);
+        Self::new(
+            log_blowup,
+            log_last_layer_degree_bound,
+            if num_queries == 0 {
+                1
+            } else {
+                num_queries as usize
+            },
+            proof_of_work_bits,
+        )
+    }
+
+    #[must_use]
+    pub const fn log_blowup(&self) -> u32 {
+        self.log_blowup
+    }
+
+    #[must_use]
+    pub const fn log_last_layer_degree_bound(&self) -> u32 {
+        self.log_last_layer_degree_bound
+    }
+
+    #[must_use]
+    pub const fn num_queries(&self) -> usize {
+        self.num_queries
+    }
+
+    #[must_use]
+    pub const fn proof_of_work_bits(&self) -> u32 {
+        self.proof_of_work_bits
+    }
+
+    /// Returns the conjectured security `log_blowup * num_queries +
+    /// proof_of_work_bits` in bits. It is further capped by the size of the
+    /// challenge field at [`MAX_SECURITY_BITS`].
+    #[must_use]
+    pub const fn security_bits(&self) -> usize {
+        let bits = self.log_blowup as usize * self.num_queries + self.proof_of_work_bits as usize;
+        if bits > MAX_SECURITY_BITS as usize {
+            MAX_SECURITY_BITS as usize
+        } else {
+            bits
+        }
+    }
+
+    /// Returns the number of committed layers, including the circle layer,
+    /// for an input of `domain_size` evaluations, or `None` if the domain is
+    /// not a power of two below `2^LOG_ORDER` that is large enough for a
+    /// folding round.
+    #[must_use]
+    pub const fn num_layers(&self, domain_size: usize) -> Option<usize> {
+        let log_size = domain_size.trailing_zeros();
+        if !domain_size.is_power_of_two()
+            || log_size >= LOG_ORDER
+            || log_size <= self.log_blowup + self.log_last_layer_degree_bound
+        {
+            return None;
+        }
+        Some((log_size - self.log_blowup - self.log_last_layer_degree_bound) as usize)
+    }
+}
+
+/// A circle FRI proof with challenges in `E` and Merkle digests `D`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FriProof<E, D> {
+    /// Merkle roots of the circle layer followed by the line layers
+    pub commitments: Vec<D>,
+    pub circle_queries: FriQueries<Fp, D>,
+    pub line_queries: Vec<FriQueries<E, D>>,
+    /// Coefficients of the last layer in the line basis
+    pub last_layer: Vec<E>,
+    pub proof_of_work: Fp,
+}
+
+/// Evaluates `poly` on the standard circle domain that is `2^log_blowup`
+/// times larger than the polynomial.
+///
+/// # Panics
+///
+/// Panics if the domain has `2^LOG_ORDER` or more points.
+#[must_use]
+pub fn lde(poly: &CirclePoly, log_blowup: u32) -> Vec<Fp> {
+    poly.evaluate(&CircleDomain::standard(poly.log_size() + log_blowup))
+}
+
+/// Proves that `evaluations` on the standard circle domain are close to a
+/// circle polynomial with `evaluations.len() / 2^log_blowup` coefficients.
+///
+/// # Panics
+///
+/// Panics if [`FriOptions::num_layers`] rejects the number of evaluations.
+pub fn prove<E, H, P, const WIDTH: usize>(
+    options: &FriOptions,
+    hasher: &H,
+    transcript: &mut Transcript<P, WIDTH>,
+    evaluations: &[Fp],
+) -> FriProof<E, H::Digest>
+where
+    E: Field<BasePrimeField = Fp>,
+    H: MerkleHasher<Field = Fp> + Clone,
+    H::Digest: AsRef<[Fp]>,
+    P: DuplexPermutation<WIDTH, Field = Fp> + Clone,
+{
+    let domain_size = evaluations.len();
+    let num_layers = options
+        .num_layers(domain_size)
+        .expect("unsupported domain size");
+    let domain = CircleDomain::standard(domain_size.ilog2());
+
+    let circle_tree = commit(hasher, transcript, evaluations, 2);
+    let beta: E = transcript.squeeze_extension();
+    let lifted: Vec<E> = evaluations
+        .iter()
+        .map(|&value| E::from_base_prime_field(value))
+        .collect();
+    let mut coset = domain.half_coset();
+    let mut layer = fold_layer(&lifted, coset.iter().map(|p| p.y).collect(), beta);
+    let mut line_trees = Vec::with_capacity(num_layers - 1);
+    for _ in 1..num_layers {
+        let tree = commit(hasher, transcript, &layer, 2);
+        let beta: E = transcript.squeeze_extension();
+        layer = fold_layer(&layer, line_twiddles(coset), beta);
+        coset = coset.double();
+        line_trees.push(tree);
+    }
+
+    let degree_bound = layer.len() >> options.log_blowup;
+    let mut last_layer = interpolate_line(layer, coset);
+    last_layer.truncate(degree_bound);
+    let num_leaves = domain_size / 2;
+    let (proof_of_work, positions) = finish_commit_phase(
+        transcript,
+        &last_layer,
+        options.proof_of_work_bits,
+        options.num_queries,
+        num_leaves,
+    );
+
+    let commitments = core::iter::once(&circle_tree)
+        .chain(&line_trees)
+        .map(MerkleTree::root)
+        .collect();
+    let circle_queries = open(&circle_tree, &positions, 2);
+    let line_queries = open_layers(&line_trees, positions, num_leaves, 2);
+
+    FriProof {
+        commitments,
+        circle_queries,
+        line_queries,
+        last_layer,
+        proof_of_work,
+    }
+}
+
+/// Verifies a proof that the committed evaluations on the standard circle
+/// domain of size `domain_size` are close to a circle polynomial with
+/// `domain_size / 2^log_blowup` coefficients.
+///
+/// # Errors
+///
+/// Returns a [`FriError`] describing the first check that fails.
+pub fn verify<E, H, P, const WIDTH: usize>(
+    options: &FriOptions,
+    hasher: &H,
+    transcript: &mut Transcript<P, WIDTH>,
+    domain_size: usize,
+    proof: &FriProof<E, H::Digest>,
+) -> Result<(), FriError>
+where
+    E: Field<BasePrimeField = Fp>,
+    H: MerkleHasher<Field = Fp>,
+    H::Digest: AsRef<[Fp]>,
+    P: DuplexPermutation<WIDTH, Field = Fp> + Clone,
+{
+    let num_layers = options
+        .num_layers(domain_size)
+        .ok_or(FriError::InvalidDomainSize(domain_size))?;
+    let last_size = domain_size >> num_layers;
+    if proof.commitments.len() != num_layers
+        || proof.line_queries.len() != num_layers - 1
+        || proof.last_layer.len() != last_size >> options.log_blowup
+    {
+        return Err(FriError::InvalidProofShape);
+    }
+
+    let num_leaves = domain_size / 2;
+    let (betas, mut positions) = replay_commit_phase(
+        transcript,
+        &proof.commitments,
+        &proof.last_layer,
+        options.proof_of_work_bits,
+        proof.proof_of_work,
+        options.num_queries,
+        num_leaves,
+    )?;
+
+    // the circle layer and the first line layer share the half coset, which is
+    // doubled by every further layer
+    let half_coset = CircleDomain::standard(domain_size.ilog2()).half_coset();
+    let cosets: Vec<Coset> = core::iter::successors(Some(half_coset), |coset| Some(coset.double()))
+        .take(num_layers)
+        .collect();
+    let pairs = opened_cosets(
+        0,
+        hasher,
+        &proof.commitments[0],
+        &proof.circle_queries,
+        &positions,
+        num_leaves,
+        2,
+    )?;
+    let mut folded: Vec<E> = positions
+        .iter()
+        .zip(pairs)
+        .map(|(&position, pair)| {
+            let pair = [pair[0], pair[1]].map(E::from_base_prime_field);
+            fold_pair_at(pair, cosets[0].at(position).y, betas[0])
+        })
+        .collect();
+    verify_layers(
+        hasher,
+        &proof.commitments[1..],
+        &proof.line_queries,
+        &mut positions,
+        &mut folded,
+        num_leaves,
+        2,
+        |layer, pair, position, _| {
+            fold_pair_at(
+                [pair[0], pair[1]],
+                cosets[layer - 1].at(position).x,
+                betas[layer],
+            )
+        },
+    )?;
+
+    let coset = cosets[num_layers - 1];
+    for (&position, value) in positions.iter().zip(&folded) {
+        if *value != evaluate_line(&proof.last_layer, coset.at(position).x) {
+            return Err(FriError::RemainderMismatch { position });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the x-coordinates of the first half of a line layer's coset. The
+/// second half holds their negations.
+fn line_twiddles(coset: Coset) -> Vec<Fp> {
+    coset.iter().take(coset.size() / 2).map(|p| p.x).collect()
+}
+
+/// Folds the values at indices `i` and `i + m/2` of a layer of size `m` where
+/// the `i`-th twiddle is the coordinate that changes sign between them.
+fn fold_layer<E>(values: &[E], mut twiddles: Vec<Fp>, beta: E) -> Vec<E>
+where
+    E: Field<BasePrimeField = Fp>,
+{
+    batch_inversion(&mut twiddles);
+    let (lo, hi) = values.split_at(values.len() / 2);
+    lo.iter()
+        .zip(hi)
+        .zip(&twiddles)
+        .map(|((&a, &b), &twiddle_inv)| fold_pair([a, b], twiddle_inv, beta))
+        .collect()
+}
+
+/// Folds the pair of values at the points with coordinate `twiddle` and
+/// `-twiddle`.
+fn fold_pair_at<E>(pair: [E; 2], twiddle: Fp, beta: E) -> E
+where
+    E: Field<BasePrimeField = Fp>,
+{
+    fold_pair(pair, twiddle.inverse().unwrap(), beta)
+}
+
+/// Computes `(f(t) + f(-t)) / 2 + β * (f(t) - f(-t)) / 2t`, the even and odd
+/// parts of `f` in the coordinate `t`.
+fn fold_pair<E>([a, b]: [E; 2], twiddle_inv: Fp, beta: E) -> E
+where
+    E: Field<BasePrimeField = Fp>,
+{
+    let odd = (a - b) * E::from_base_prime_field(twiddle_inv);
+    (a + b + beta * odd) * E::from_base_prime_field(TWO_INV)
+}
+
+/// Interpolates the coefficients of a line polynomial from its evaluations
+/// on the x-coordinates of `coset`.
+fn interpolate_line<E>(mut values: Vec<E>, mut coset: Coset) -> Vec<E>
+where
+    E: Field<BasePrimeField = Fp>,
+{
+    let mut half = values.len() / 2;
+    while half > 0 {
+        let mut twiddles = line_twiddles(coset);
+        batch_inversion(&mut twiddles);
+        for block in values.chunks_mut(2 * half) {
+            let (lo, hi) = block.split_at_mut(half);
+            for ((a, b), t) in lo.iter_mut().zip(hi).zip(&twiddles) {
+                let (v0, v1) = (*a, *b);
+                *a = v0 + v1;
+                *b = (v0 - v1) * E::from_base_prime_field(*t);
+            }
+        }
+        coset = coset.double();
+        half /= 2;
+    }
+    let size_inv = E::from_base_prime_field(Fp::from(values.len() as u64).inverse().unwrap());
+    for value in &mut values {
+        *value *= size_inv;
+    }
+    bit_reverse_permute(&mut values);
+    values
+}
+
+/// Evaluates a line polynomial at `x`.
+fn evaluate_line<E>(coefficients: &[E], x: Fp) -> E
+where
+    E: Field<BasePrimeField = Fp>,
+{
+    let mut mappings = Vec::new();
+    let mut x = x;
+    for _ in 0..coefficients.len().ilog2() {
+        mappings.push(x);
+        x = double_x(x);
+    }
+    fold_line(coefficients, &mappings)
+}
+
+/// Computes `Σ c_j Π_k m_k^(j_k)` where `j_k` is the k-th bit of `j`.
+fn fold_line<E>(coefficients: &[E], mappings: &[Fp]) -> E
+where
+    E: Field<BasePrimeField = Fp>,
+{
+    match mappings.split_last() {
+        None => coefficients[0],
+        Some((&m, rest)) => {
+            let (lo, hi) = coefficients.split_at(coefficients.len() / 2);
+            fold_line(lo, rest) + E::from_base_prime_field(m) * fold_line(hi, rest)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        evaluate_line, fold_layer, interpolate_line, lde, line_twiddles, prove, verify, FriError,
+        FriOptions, FriProof, MAX_SECURITY_BITS,
+    };
+    use crate::{
+        circle::{CircleDomain, CirclePoly},
+        cm31::Fp2,
+        fp31::{poseidon2::Poseidon2, Fp},
+        merkle::MerkleError,
+        qm31::Fp4,
+        transcript::Transcript,
+    };
+    use ark_ff::Field;
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    type Digest = crate::fp31::poseidon2::Digest;
+
+    fn transcript() -> Transcript<Poseidon2<16>, 16> {
+        Transcript::new(Poseidon2::<16>::new(), b"circle fri")
+    }
+
+    fn random_poly(log_size: u32) -> CirclePoly {
+        let mut rng = test_rng();
+        CirclePoly::new((0..1 << log_size).map(|_| Fp::rand(&mut rng)).collect())
+    }
+
+    fn prove_and_verify<E: Field<BasePrimeField = Fp>>(
+        options: &FriOptions,
+        evaluations: &[Fp],
+    ) -> Result<(), FriError> {
+        let hasher = Poseidon2::<16>::new();
+        let proof: FriProof<E, Digest> = prove(options, &hasher, &mut transcript(), evaluations);
+        verify(
+            options,
+            &hasher,
+            &mut transcript(),
+            evaluations.len(),
+            &proof,
+        )
+    }
+
+    #[test]
+    fn lde_evaluates_on_standard_domain() {
+        let poly = random_poly(4);
+        let evaluations = lde(&poly, 2);
+        let domain = CircleDomain::standard(6);
+        for (point, value) in domain.iter().zip(&evaluations) {
+            assert_eq!(*value, poly.eval_at_point(point));
+        }
+    }
+
+    #[test]
+    fn folding_halves_degree() {
+        let mut rng = test_rng();
+        let poly = random_poly(5);
+        let domain = CircleDomain::standard(7);
+        let values: Vec<Fp4> = lde(&poly, 2).into_iter().map(Fp4::from).collect();
+
+        // the y-fold has the coefficients `c_2j + β c_(2j+1)`
+        let beta = Fp4::rand(&mut rng);
+        let coset = domain.half_coset();
+        let line = fold_layer(&values, coset.iter().map(|p| p.y).collect(), beta);
+        let coefficients = interpolate_line(line.clone(), coset);
+        for (j, coefficient) in coefficients.iter().enumerate() {
+            let c = |k: usize| Fp4::from(poly.coeffs.get(k).copied().unwrap_or_default());
+            assert_eq!(*coefficient, c(2 * j) + beta * c(2 * j + 1));
+        }
+        for (p, value) in coset.iter().zip(&line) {
+            assert_eq!(evaluate_line(&coefficients, p.x), *value);
+        }
+
+        // so does the x-fold of a line polynomial
+        let beta = Fp4::rand(&mut rng);
+        let folded = fold_layer(&line, line_twiddles(coset), beta);
+        let folded_coefficients = interpolate_line(folded, coset.double());
+        for (j, coefficient) in folded_coefficients.iter().enumerate() {
+            assert_eq!(
+                *coefficient,
+                coefficients[2 * j] + beta * coefficients[2 * j + 1]
+            );
+        }
+    }
+
+    #[test]
+    fn options_validate_parameters() {
+        assert!(FriOptions::new(0, 0, 10, 0).is_none());
+        assert!(FriOptions::new(31, 0, 10, 0).is_none());
+        assert!(FriOptions::new(1, 31, 10, 0).is_none());
+        assert!(FriOptions::new(1, 0, 0, 0).is_none());
+        assert!(FriOptions::new(1, 0, 10, 31).is_none());
+
+        let options = FriOptions::new(2, 3, 10, 0).unwrap();
+        // 256 coefficients fold along y to 128 and along x to 64, 32, 16 and 8
+        assert_eq!(options.num_layers(1024), Some(5));
+        assert_eq!(options.num_layers(64), Some(1));
+        assert_eq!(options.num_layers(32), None);
+        assert_eq!(options.num_layers(1000), None);
+        assert_eq!(options.num_layers(1 << 31), None);
+    }
+
+    #[test]
+    fn options_reach_security_level() {
+        let options = FriOptions::with_security_bits(100, 3, 0, 20).unwrap();
+        assert_eq!(options.num_queries(), 27);
+        assert_eq!(options.security_bits(), 101);
+        let options = FriOptions::with_security_bits(10, 1, 0, 20).unwrap();
+        assert_eq!(options.num_queries(), 1);
+        assert!(FriOptions::with_security_bits(100, 0, 0, 0).is_none());
+
+        // the challenge field bounds the security
+        assert_eq!(MAX_SECURITY_BITS, 124);
+        let options = FriOptions::with_security_bits(MAX_SECURITY_BITS, 4, 0, 0).unwrap();
+        assert_eq!(options.security_bits(), 124);
+        assert!(FriOptions::with_security_bits(MAX_SECURITY_BITS + 1, 4, 0, 0).is_none());
+        let options = FriOptions::new(4, 0, 100, 20).unwrap();
+        assert_eq!(options.security_bits(), 124);
+    }
+
+    #[test]
+    fn honest_proofs_verify() {
+        for log_blowup in [1, 3] {
+            for log_last_layer_degree_bound in [0, 2] {
+                let options =
+                    FriOptions::new(log_blowup, log_last_layer_degree_bound, 20, 4).unwrap();
+                let evaluations = lde(&random_poly(8), log_blowup);
+                assert_eq!(prove_and_verify::<Fp>(&options, &evaluations), Ok(()));
+                assert_eq!(prove_and_verify::<Fp2>(&options, &evaluations), Ok(()));
+                assert_eq!(prove_and_verify::<Fp4>(&options, &evaluations), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn lower_degree_proofs_verify() {
+        let options = FriOptions::new(2, 0, 20, 0).unwrap();
+        let mut coefficients = random_poly(3).coeffs;
+        coefficients.resize(512, Fp::ZERO);
+        let evaluations = lde(&CirclePoly::new(coefficients), 2);
+        assert_eq!(prove_and_verify::<Fp4>(&options, &evaluations), Ok(()));
+    }
+
+    #[test]
+    fn rejects_high_degree() {
+        let options = FriOptions::new(2, 0, 30, 0).unwrap();
+        // 512 coefficients exceed the bound of 256
+        let evaluations = random_poly(9).evaluate(&CircleDomain::standard(10));
+        assert!(prove_and_verify::<Fp4>(&options, &evaluations).is_err());
+
+        let mut rng = test_rng();
+        let random: Vec<Fp> = (0..1024).map(|_| Fp::rand(&mut rng)).collect();
+        assert!(prove_and_verify::<Fp4>(&options, &random).is_err());
+    }
+
+    fn honest_proof(options: &FriOptions, evaluations: &[Fp]) -> FriProof<Fp4, Digest> {
+        prove(
+            options,
+            &Poseidon2::<16>::new(),
+            &mut transcript(),
+            evaluations,
+        )
+    }
+
+    fn check(
+        options: &FriOptions,
+        domain_size: usize,
+        proof: &FriProof<Fp4, Digest>,
+    ) -> Result<(), FriError> {
+        verify(
+            options,
+            &Poseidon2::<16>::new(),
+            &mut transcript(),
+            domain_size,
+            proof,
+        )
+    }
+
+    #[test]
+    fn rejects_tampered_proofs() {
+        let options = FriOptions::new(2, 2, 16, 6).unwrap();
+        let evaluations = lde(&random_poly(8), 2);
+        let proof = honest_proof(&options, &evaluations);
+        assert_eq!(check(&options, 1024, &proof), Ok(()));
+
+        assert_eq!(
+            check(&options, 512, &proof),
+            Err(FriError::InvalidProofShape)
+        );
+        assert_eq!(
+            check(&options, 1000, &proof),
+            Err(FriError::InvalidDomainSize(1000))
+        );
+
+        let mut tampered = proof.clone();
+        tampered.last_layer[0] += Fp4::ONE;
+        assert!(check(&options, 1024, &tampered).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.last_layer.push(Fp4::ZERO);
+        assert_eq!(
+            check(&options, 1024, &tampered),
+            Err(FriError::InvalidProofShape)
+        );
+
+        let mut tampered = proof.clone();
+        tampered.proof_of_work += Fp::ONE;
+        assert!(check(&options, 1024, &tampered).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.commitments[1][0] += Fp::ONE;
+        assert!(check(&options, 1024, &tampered).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.circle_queries.cosets[0][1] += Fp::ONE;
+        assert_eq!(
+            check(&options, 1024, &tampered),
+            Err(FriError::InvalidOpening {
+                layer: 0,
+                error: MerkleError::RootMismatch
+            })
+        );
+
+        let mut tampered = proof.clone();
+        tampered.line_queries[1].cosets[0][0] += Fp4::ONE;
+        assert_eq!(
+            check(&options, 1024, &tampered),
+            Err(FriError::InvalidOpening {
+                layer: 2,
+                error: MerkleError::RootMismatch
+            })
+        );
+
+        let mut tampered = proof;
+        tampered.line_queries.pop();
+        assert_eq!(
+            check(&options, 1024, &tampered),
+            Err(FriError::InvalidProofShape)
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! The 2-to-1 compression function shared by the permutations in this crate.
+//!
+//! Two digests are compressed by writing `left || right` to the start of the
+//! state, zeroing the rest, permuting and keeping the first digest length
+//! elements. The permutation is only secure as a compression function this way
+//! if the width leaves a capacity of at least one digest after the output, so
+//! it is used with widths of at least twice the digest length.
+
+use ark_ff::Field;
+
+/// Compresses `left` and `right` by truncating `permute` applied to
+/// `left || right` padded with zeros.
+#[inline]
+pub fn truncated_permutation<F: Field, const DIGEST_LEN: usize, const WIDTH: usize>(
+    permute: impl FnOnce(&mut [F; WIDTH]),
+    left: &[F; DIGEST_LEN],
+    right: &[F; DIGEST_LEN],
+) -> [F; DIGEST_LEN] {
+    let mut state = [F::ZERO; WIDTH];
+    state[..DIGEST_LEN].copy_from_slice(left);
+    state[DIGEST_LEN..2 * DIGEST_LEN].copy_from_slice(right);
+    permute(&mut state);
+    core::array::from_fn(|i| state[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fp31, fp64};
+    use ark_ff::Field;
+    use ark_std::test_rng;
+
+    /// Checks `compress` against the permutation of `left || right` padded
+    /// with zeros and truncated to a digest.
+    fn check_compression<F: Field, const DIGEST_LEN: usize, const WIDTH: usize>(
+        permute: impl Fn(&mut [F; WIDTH]),
+        compress: impl Fn(&[F; DIGEST_LEN], &[F; DIGEST_LEN]) -> [F; DIGEST_LEN],
+    ) {
+        let mut rng = test_rng();
+        let left: [F; DIGEST_LEN] = core::array::from_fn(|_| F::rand(&mut rng));
+        let right: [F; DIGEST_LEN] = core::array::from_fn(|_| F::rand(&mut rng));
+        let mut state: [F; WIDTH] = core::array::from_fn(|i| match i {
+            i if i < DIGEST_LEN => left[i],
+            i if i < 2 * DIGEST_LEN => right[i - DIGEST_LEN],
+            _ => F::ZERO,
+        });
+        permute(&mut state);
+        assert_eq!(compress(&left, &right)[..], state[..DIGEST_LEN]);
+        assert_ne!(compress(&left, &right), compress(&right, &left));
+    }
+
+    #[test]
+    fn compression_truncates_permutation() {
+        let poseidon2 = fp64::poseidon2::Poseidon2::<8>::new();
+        check_compression(|s| poseidon2.permute(s), |l, r| poseidon2.compress(l, r));
+        let poseidon2 = fp64::poseidon2::Poseidon2::<12>::new();
+        check_compression(|s| poseidon2.permute(s), |l, r| poseidon2.compress(l, r));
+        let monolith = fp64::monolith::Monolith::<8>::new();
+        check_compression(|s| monolith.permute(s), |l, r| monolith.compress(l, r));
+        let monolith = fp64::monolith::Monolith::<12>::new();
+        check_compression(|s| monolith.permute(s), |l, r| monolith.compress(l, r));
+
+        let poseidon2 = fp31::poseidon2::Poseidon2::<16>::new();
+        check_compression(|s| poseidon2.permute(s), |l, r| poseidon2.compress(l, r));
+        let poseidon2 = fp31::poseidon2::Poseidon2::<24>::new();
+        check_compression(|s| poseidon2.permute(s), |l, r| poseidon2.compress(l, r));
+        let monolith = fp31::monolith::Monolith::<16>::new();
+        check_compression(|s| monolith.permute(s), |l, r| monolith.compress(l, r));
+    }
+}
@@ -0,0 +1,551 @@
+//! The `EcGFp5` elliptic curve `y^2 = x(x^2 + 2x + 263z)` over `GF(p^5)` from
+//! <https://eprint.iacr.org/2022/274.pdf>.
+//!
+//! `EcGFp5` is a double-odd curve: it has order `2n` for a prime `n` close to
+//! `2^319` and the group used is its subgroup of order `n`. Following
+//! <https://eprint.iacr.org/2020/1558.pdf> points are stored on the isomorphic
+//! Jacobi quartic `e^2 = (a^2 - 4b)u^4 - 2au^2 + 1` where `u = x/y`. The
+//! extended coordinates `(E:Z:U:T)` represent `e = E/Z`, `u = U/Z` and `u^2 =
+//! T/Z`. Since `a^2 - 4b` is not a square the addition formulas are complete.
+//!
+//! An element of the group is encoded as `w = y/x = 1/u` with the neutral
+//! element encoded as zero. Decoding recovers the unique point of the group
+//! with that `w`.
+
+use crate::fp64::{ext5::Fp5, into_mont};
+use ark_ec::Group;
+use ark_ff::{fields::Fp320, BigInt, Field, MontBackend, MontConfig, MontFp, PrimeField, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
+use core::{
+    fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
+    iter::Sum,
+    marker::PhantomData,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// Parameters of the scalar field. They are written out rather than derived
+/// with `#[derive(MontConfig)]`, whose expansion defines non-local impls.
+pub struct ScalarConfig;
+
+impl MontConfig<5> for ScalarConfig {
+    const MODULUS: BigInt<5> = BigInt!(
+        "1067993516717146951041484916571792702745057740581727230159139685185762082554198619328292418486241"
+    );
+
+    const GENERATOR: Scalar = MontFp!("6");
+
+    /// `6^((n - 1) / 2^5)`
+    const TWO_ADIC_ROOT_OF_UNITY: Scalar = MontFp!(
+        "802792335124435116389442070563107586531847618650331968930371854656471908876541581004839620735197"
+    );
+}
+
+/// The scalar field of `EcGFp5` i.e. integers modulo the group order `n`
+pub type Scalar = Fp320<MontBackend<ScalarConfig, 5>>;
+
+/// `b = 263z` of the curve equation
+const B: Fp5 = Fp5::new(
+    into_mont(0),
+    into_mont(263),
+    into_mont(0),
+    into_mont(0),
+    into_mont(0),
+);
+
+/// Multiplies by `d = a^2 - 4b = 4 - 1052z` of the Jacobi quartic.
+#[inline]
+fn mul_by_d(v: Fp5) -> Fp5 {
+    let mut vz = v.mul_by_z();
+    vz.mul_assign_by_base_field(&into_mont(1052));
+    v.double().double() - vz
+}
+
+/// An element of the prime order subgroup of `EcGFp5`
+#[derive(Clone, Copy, Debug)]
+pub struct Point {
+    e: Fp5,
+    z: Fp5,
+    u: Fp5,
+    t: Fp5,
+}
+
+impl Point {
+    /// The neutral element i.e. the point at infinity
+    pub const NEUTRAL: Self = Self {
+        e: Fp5::ONE,
+        z: Fp5::ONE,
+        u: Fp5::ZERO,
+        t: Fp5::ZERO,
+    };
+
+    /// The conventional generator which is encoded as `w = 4`
+    pub const GENERATOR: Self = Self {
+        e: Fp5::new(
+            into_mont(3_001_294_069_081_535_885),
+            into_mont(8_678_807_079_362_910_252),
+            into_mont(6_261_412_705_293_607_007),
+            into_mont(13_564_311_315_250_898_863),
+            into_mont(1_999_791_749_789_866_942),
+        ),
+        z: Fp5::ONE,
+        u: Fp5::new(
+            into_mont(13_835_058_052_060_938_241),
+            into_mont(0),
+            into_mont(0),
+            into_mont(0),
+            into_mont(0),
+        ),
+        t: Fp5::new(
+            into_mont(17_293_822_565_076_172_801),
+            into_mont(0),
+            into_mont(0),
+            into_mont(0),
+            into_mont(0),
+        ),
+    };
+
+    /// Encodes the point as the field element `w = y/x`.
+    #[must_use]
+    pub fn encode(&self) -> Fp5 {
+        self.u.inverse().map_or(Fp5::ZERO, |u_inv| self.z * u_inv)
+    }
+
+    /// Decodes a field element produced by [`Point::encode`]. Returns `None` if
+    /// `w` is not the encoding of a group element.
+    #[must_use]
+    pub fn decode(w: &Fp5) -> Option<Self> {
+        let Some(u) = w.inverse() else {
+            return Some(Self::NEUTRAL);
+        };
+        // The x-coordinates of the two curve points with `y = w * x` are the
+        // roots of `x^2 - (w^2 - a)x + b`. Their product is `b` which is not a
+        // square so exactly one of them is a square i.e. in the subgroup.
+        let sum = w.square() - Fp5::from(2u8);
+        let diff = (sum.square() - B.double().double()).sqrt()?;
+        let diff = if (sum + diff).legendre().is_qr() {
+            diff
+        } else {
+            -diff
+        };
+        // `e = u^2 * (x - b/x)` is the difference of the roots times `u^2`.
+        let u2 = u.square();
+        Some(Self {
+            e: diff * u2,
+            z: Fp5::ONE,
+            u,
+            t: u2,
+        })
+    }
+
+    /// Multiplies the point by a scalar given as little-endian 64-bit limbs
+    /// using a fixed window of 4 bits. This is not constant time, see
+    /// [`Point::mul_ct`] for secret scalars.
+    #[must_use]
+    pub fn mul_limbs(&self, limbs: &[u64]) -> Self {
+        let mut table = [Self::NEUTRAL; 16];
+        for i in 1..16 {
+            table[i] = table[i - 1] + self;
+        }
+        let mut res = Self::NEUTRAL;
+        for limb in limbs.iter().rev() {
+            for i in (0..16).rev() {
+                for _ in 0..4 {
+                    res.double_in_place();
+                }
+                #[allow(clippy::cast_possible_truncation)]
+                let digit = ((limb >> (4 * i)) & 0xF) as usize;
+                res += &table[digit];
+            }
+        }
+        res
+    }
+
+    /// Multiplies the point by `scalar` in constant time. Like
+    /// [`Point::mul_limbs`] it uses a fixed window of 4 bits, but every window
+    /// scans the whole table with masks instead of indexing it with the secret
+    /// digit, and the complete addition formulas have no special cases.
+    #[must_use]
+    pub fn mul_ct(&self, scalar: &Scalar) -> Self {
+        let mut table = [Self::NEUTRAL; 16];
+        for i in 1..16 {
+            table[i] = table[i - 1] + self;
+        }
+        let mut res = Self::NEUTRAL;
+        for limb in scalar.into_bigint().0.iter().rev() {
+            for i in (0..16).rev() {
+                for _ in 0..4 {
+                    res.double_in_place();
+                }
+                res += &select(&table, (limb >> (4 * i)) & 0xF);
+            }
+        }
+        res
+    }
+
+    /// Returns `scalar * G` in constant time.
+    #[must_use]
+    pub fn mul_generator(scalar: &Scalar) -> Self {
+        Self::GENERATOR.mul_ct(scalar)
+    }
+
+    /// Returns the raw Montgomery limbs of the coordinates.
+    fn to_limbs(self) -> [u64; 20] {
+        let mut limbs = [0; 20];
+        for (chunk, c) in limbs
+            .chunks_exact_mut(5)
+            .zip([self.e, self.z, self.u, self.t])
+        {
+            for (limb, coefficient) in chunk.iter_mut().zip([c.c0, c.c1, c.c2, c.c3, c.c4]) {
+                *limb = (coefficient.0).0[0];
+            }
+        }
+        limbs
+    }
+
+    /// Inverse of [`Point::to_limbs`].
+    fn from_limbs(limbs: &[u64; 20]) -> Self {
+        let coordinate = |i: usize| {
+            let c = |j: usize| ark_ff::Fp(BigInt([limbs[5 * i + j]]), PhantomData);
+            Fp5::new(c(0), c(1), c(2), c(3), c(4))
+        };
+        Self {
+            e: coordinate(0),
+            z: coordinate(1),
+            u: coordinate(2),
+            t: coordinate(3),
+        }
+    }
+}
+
+/// Returns `table[index]` after reading every entry, so the memory accesses
+/// don't depend on `index`.
+fn select(table: &[Point; 16], index: u64) -> Point {
+    let mut limbs = [0u64; 20];
+    for (i, point) in (0u64..).zip(table) {
+        let diff = i ^ index;
+        // all ones if `diff` is zero and zero otherwise
+        let mask = core::hint::black_box(((diff | diff.wrapping_neg()) >> 63).wrapping_sub(1));
+        for (limb, value) in limbs.iter_mut().zip(point.to_limbs()) {
+            *limb |= value & mask;
+        }
+    }
+    Point::from_limbs(&limbs)
+}
+
+impl Group for Point {
+    type ScalarField = Scalar;
+
+    fn generator() -> Self {
+        Self::GENERATOR
+    }
+
+    /// Doubling on the Jacobi quartic with 2 multiplications and 8 squarings
+    /// using `U^2 = T*Z`.
+    fn double_in_place(&mut self) -> &mut Self {
+        let ee = self.e.square();
+        let uu = self.u.square();
+        let zz = self.z.square();
+        let dtt = mul_by_d(self.t.square());
+        let a = (self.u + self.e).square() - uu - ee;
+        let b = zz - dtt;
+        self.e = (ee - uu.double().double()) * (zz + dtt) + mul_by_d(uu.square()).double().double();
+        self.u = a * b;
+        self.t = a.square();
+        self.z = b.square();
+        self
+    }
+
+    fn mul_bigint(&self, other: impl AsRef<[u64]>) -> Self {
+        self.mul_limbs(other.as_ref())
+    }
+}
+
+impl Default for Point {
+    fn default() -> Self {
+        Self::NEUTRAL
+    }
+}
+
+impl PartialEq for Point {
+    fn eq(&self, other: &Self) -> bool {
+        self.u * other.z == other.u * self.z && self.e * other.z == other.e * self.z
+    }
+}
+
+impl Eq for Point {}
+
+impl Hash for Point {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.encode().hash(state);
+    }
+}
+
+impl Zero for Point {
+    fn zero() -> Self {
+        Self::NEUTRAL
+    }
+
+    fn is_zero(&self) -> bool {
+        self.u.is_zero() && self.e == self.z
+    }
+}
+
+impl zeroize::Zeroize for Point {
+    fn zeroize(&mut self) {
+        self.e.zeroize();
+        self.z.zeroize();
+        self.u.zeroize();
+        self.t.zeroize();
+    }
+}
+
+impl ark_std::rand::distributions::Distribution<Point> for ark_std::rand::distributions::Standard {
+    #[inline]
+    fn sample<R: ark_std::rand::Rng + ?Sized>(&self, rng: &mut R) -> Point {
+        Point::GENERATOR * rng.gen::<Scalar>()
+    }
+}
+
+/// Outputs the encoding of the point.
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+impl CanonicalSerialize for Point {
+    fn serialize_with_mode<W: ark_std::io::Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.encode().serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        Fp5::ZERO.serialized_size(compress)
+    }
+}
+
+impl Valid for Point {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for Point {
+    fn deserialize_with_mode<R: ark_std::io::Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let w = Fp5::deserialize_with_mode(reader, compress, validate)?;
+        Self::decode(&w).ok_or(SerializationError::InvalidData)
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    #[inline]
+    fn neg(mut self) -> Self {
+        self.u = -self.u;
+        self
+    }
+}
+
+impl AddAssign<&Self> for Point {
+    /// Unified addition on the extended Jacobi quartic.
+    fn add_assign(&mut self, other: &Self) {
+        let ee = self.e * other.e;
+        let uu = self.u * other.u;
+        let zz = self.z * other.z;
+        let tt = self.t * other.t;
+        let a = (self.u + self.e) * (other.u + other.e) - uu - ee;
+        let tz = (self.t + self.z) * (other.t + other.z) - tt - zz;
+        let dtt = mul_by_d(tt);
+        let b = zz - dtt;
+        self.e = (ee - uu.double().double()) * (zz + dtt) + mul_by_d(uu).double() * tz;
+        self.u = a * b;
+        self.t = a.square();
+        self.z = b.square();
+    }
+}
+
+impl SubAssign<&Self> for Point {
+    fn sub_assign(&mut self, other: &Self) {
+        self.add_assign(&-*other);
+    }
+}
+
+impl MulAssign<&Scalar> for Point {
+    fn mul_assign(&mut self, other: &Scalar) {
+        *self = self.mul_limbs(other.into_bigint().as_ref());
+    }
+}
+
+impl AddAssign<Self> for Point {
+    fn add_assign(&mut self, other: Self) {
+        self.add_assign(&other);
+    }
+}
+
+impl SubAssign<Self> for Point {
+    fn sub_assign(&mut self, other: Self) {
+        self.sub_assign(&other);
+    }
+}
+
+impl MulAssign<Scalar> for Point {
+    fn mul_assign(&mut self, other: Scalar) {
+        self.mul_assign(&other);
+    }
+}
+
+impl Add<&Self> for Point {
+    type Output = Self;
+
+    fn add(mut self, other: &Self) -> Self {
+        self.add_assign(other);
+        self
+    }
+}
+
+impl Sub<&Self> for Point {
+    type Output = Self;
+
+    fn sub(mut self, other: &Self) -> Self {
+        self.sub_assign(other);
+        self
+    }
+}
+
+impl Mul<&Scalar> for Point {
+    type Output = Self;
+
+    fn mul(mut self, other: &Scalar) -> Self {
+        self.mul_assign(other);
+        self
+    }
+}
+
+impl Add<Self> for Point {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self.add_assign(&other);
+        self
+    }
+}
+
+impl Sub<Self> for Point {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self.sub_assign(&other);
+        self
+    }
+}
+
+impl Mul<Scalar> for Point {
+    type Output = Self;
+
+    fn mul(mut self, other: Scalar) -> Self {
+        self.mul_assign(&other);
+        self
+    }
+}
+
+impl Sum<Self> for Point {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::NEUTRAL, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Self> for Point {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::NEUTRAL, Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Point, Scalar};
+    use crate::fp64::{ext5::Fp5, Fp};
+    use ark_algebra_test_templates::test_group;
+    use ark_ec::Group;
+    use ark_ff::{PrimeField, Zero};
+    use ark_std::{test_rng, UniformRand};
+
+    test_group!(group; Point);
+
+    #[test]
+    fn generator_is_encoded_as_four() {
+        assert_eq!(Point::GENERATOR.encode(), Fp5::from(4u8));
+        assert_eq!(Point::decode(&Fp5::from(4u8)), Some(Point::GENERATOR));
+    }
+
+    #[test]
+    fn constant_time_multiplication_matches() {
+        let mut rng = test_rng();
+        let point = Point::rand(&mut rng);
+        for _ in 0..20 {
+            let scalar = Scalar::rand(&mut rng);
+            assert_eq!(point.mul_ct(&scalar), point * scalar);
+            assert_eq!(Point::mul_generator(&scalar), Point::GENERATOR * scalar);
+        }
+        assert!(Point::mul_generator(&Scalar::zero()).is_zero());
+        assert_eq!(Point::mul_generator(&-Scalar::from(1u8)), -Point::GENERATOR);
+    }
+
+    #[test]
+    fn generator_has_order_n() {
+        let n = Scalar::MODULUS;
+        assert!(Point::GENERATOR.mul_bigint(n).is_zero());
+        assert!(!Point::GENERATOR.is_zero());
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let mut rng = test_rng();
+        assert_eq!(Point::NEUTRAL.encode(), Fp5::zero());
+        assert_eq!(Point::decode(&Fp5::zero()), Some(Point::NEUTRAL));
+        for _ in 0..100 {
+            let a = Point::rand(&mut rng);
+            assert_eq!(Point::decode(&a.encode()), Some(a));
+        }
+    }
+
+    #[test]
+    fn decoded_points_are_in_the_subgroup() {
+        let mut rng = test_rng();
+        let mut decoded = 0;
+        while decoded < 10 {
+            let w = Fp5::new(
+                Fp::rand(&mut rng),
+                Fp::rand(&mut rng),
+                Fp::rand(&mut rng),
+                Fp::rand(&mut rng),
+                Fp::rand(&mut rng),
+            );
+            if let Some(point) = Point::decode(&w) {
+                assert!(point.mul_bigint(Scalar::MODULUS).is_zero());
+                assert_eq!(point.encode(), w);
+                decoded += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn double_matches_add() {
+        let mut rng = test_rng();
+        for _ in 0..100 {
+            let a = Point::rand(&mut rng);
+            assert_eq!(a.double(), a + a);
+        }
+        assert_eq!(Point::NEUTRAL.double(), Point::NEUTRAL);
+    }
+}
@@ -22,6 +22,11 @@ use core::{
     str::FromStr,
 };
 
+pub mod monolith;
+pub mod ntt;
+pub mod packed;
+pub mod poseidon2;
+
 /// Field modulus `p = 2^31 - 1`
 const MODULUS: u32 = 2_147_483_647;
 
@@ -61,6 +66,17 @@ impl Fp {
         Self((x & MODULUS) + (x >> 31))
     }
 
+    /// Reduces `x < 2^62` to `[0, p]` with the two folds `(x & p) + (x >> 31)`
+    /// that [`Self::mul`] uses, so several products or shifted values can be
+    /// summed before a single reduction.
+    #[inline]
+    pub(crate) const fn reduce_u62(x: u64) -> Self {
+        // `x >> 31 < 2^31` so the sum fits into 32 bits
+        #[allow(clippy::cast_possible_truncation)]
+        let x = (x as u32 & MODULUS) + (x >> 31) as u32;
+        Self((x & MODULUS) + (x >> 31))
+    }
+
     #[inline]
     const fn sq(self) -> Self {
         self.mul(self)
@@ -88,6 +104,40 @@ impl Fp {
     }
 }
 
+/// Replaces every non-zero element of `values` with its inverse using
+/// Montgomery's trick, which needs a single addition chain inversion for the
+/// whole slice. Zeros in either encoding are left unchanged.
+pub fn batch_inverse(values: &mut [Fp]) {
+    crate::inversion::batch_inverse(values);
+}
+
+/// Writes the inverse of every element of `values` to `out`, or zero for zero
+/// elements in either encoding, using a single inversion.
+///
+/// # Panics
+///
+/// Panics if `values` and `out` have different lengths.
+pub fn batch_inverse_into(values: &[Fp], out: &mut [Fp]) {
+    crate::inversion::batch_inverse_into(values, out);
+}
+
+/// [`batch_inverse`] on chunks of `values` in parallel with rayon. Each chunk
+/// costs one inversion.
+#[cfg(feature = "parallel")]
+pub fn par_batch_inverse(values: &mut [Fp]) {
+    crate::inversion::par_batch_inverse(values);
+}
+
+/// [`batch_inverse_into`] on chunks of `values` in parallel with rayon.
+///
+/// # Panics
+///
+/// Panics if `values` and `out` have different lengths.
+#[cfg(feature = "parallel")]
+pub fn par_batch_inverse_into(values: &[Fp], out: &mut [Fp]) {
+    crate::inversion::par_batch_inverse_into(values, out);
+}
+
 impl Field for Fp {
     type BasePrimeField = Self;
     type BasePrimeFieldIter = core::iter::Once<Self::BasePrimeField>;
@@ -230,13 +280,16 @@ impl PrimeField for Fp {
     }
 }
 
+/// `p - 1 = 2 * 3^2 * 7 * 11 * 31 * 151 * 331`. Note that 3 is a cubic residue
+/// so the smallest generator of the multiplicative group is 7.
 impl FftField for Fp {
-    const GENERATOR: Self = Self(3);
+    const GENERATOR: Self = Self(7);
     const TWO_ADICITY: u32 = 1;
     const TWO_ADIC_ROOT_OF_UNITY: Self = Self(MODULUS - 1);
-    const SMALL_SUBGROUP_BASE: Option<u32> = None;
-    const SMALL_SUBGROUP_BASE_ADICITY: Option<u32> = None;
-    const LARGE_SUBGROUP_ROOT_OF_UNITY: Option<Self> = None;
+    const SMALL_SUBGROUP_BASE: Option<u32> = Some(3);
+    const SMALL_SUBGROUP_BASE_ADICITY: Option<u32> = Some(2);
+    // `7^((p - 1) / 18)`, its ninth power is `TWO_ADIC_ROOT_OF_UNITY`
+    const LARGE_SUBGROUP_ROOT_OF_UNITY: Option<Self> = Some(Self(1_674_186_060));
 }
 
 impl zeroize::Zeroize for Fp {
@@ -456,7 +509,11 @@ impl ark_std::rand::distributions::Distribution<Fp> for ark_std::rand::distribut
     }
 }
 
-// TODO:
+// Elements are encoded as the canonical integer in little-endian order with
+// the flags packed into the unused high bits, so an element takes 4 bytes with
+// up to one flag bit. Earlier releases always appended a fifth byte for the
+// flags, which disagreed with `serialized_size` and can't be read back.
+// Integers above `MODULUS` are rejected when decoding.
 impl CanonicalSerializeWithFlags for Fp {
     fn serialize_with_flags<W: ark_std::io::Write, F: Flags>(
         &self,
@@ -469,10 +526,14 @@ impl CanonicalSerializeWithFlags for Fp {
             return Err(SerializationError::NotEnoughSpace);
         }
 
-        let b = self.into_integer().to_le_bytes();
-        // TODO: Double check this
+        // Calculate the number of bytes required to represent a field element
+        // serialized with `flags`.
+        let output_byte_size = buffer_byte_size(MODULUS_BIT_SIZE as usize + F::BIT_SIZE);
+        let mut b = [0u8; 5];
+        b[..4].copy_from_slice(&self.into_integer().to_le_bytes());
         // Mask out the bits of the last byte that correspond to the flag.
-        writer.write_all(&[b[0], b[1], b[2], b[3], flags.u8_bitmask()])?;
+        b[output_byte_size - 1] |= flags.u8_bitmask();
+        writer.write_all(&b[..output_byte_size])?;
         Ok(())
     }
 
@@ -512,17 +573,24 @@ impl CanonicalDeserializeWithFlags for Fp {
         }
         // Calculate the number of bytes required to represent a field element
         // serialized with `flags`.
+        let output_byte_size = buffer_byte_size(MODULUS_BIT_SIZE as usize + F::BIT_SIZE);
         let mut b = [0u8; 5];
-        reader.read_exact(&mut b)?;
-        let flags = F::from_u8_remove_flags(&mut b[b.len() - 1])
+        reader.read_exact(&mut b[..output_byte_size])?;
+        let flags = F::from_u8_remove_flags(&mut b[output_byte_size - 1])
             .ok_or(SerializationError::UnexpectedFlags)?;
         let self_integer = u32::from_le_bytes(b[0..4].try_into().unwrap());
-        Ok((Self(self_integer), flags))
+        let value = Self(self_integer);
+        value.check()?;
+        Ok((value, flags))
     }
 }
 
 impl Valid for Fp {
     fn check(&self) -> Result<(), SerializationError> {
+        // `MODULUS` is the second encoding of zero
+        if self.0 > MODULUS {
+            return Err(SerializationError::InvalidData);
+        }
         Ok(())
     }
 }
@@ -885,8 +953,83 @@ impl<'a> DivAssign<&'a Self> for Fp {
 
 #[cfg(test)]
 mod tests {
-    use super::Fp as TestField;
+    use super::{Fp as TestField, MODULUS};
     use ark_algebra_test_templates::test_field;
+    use ark_serialize::{
+        CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
+        CanonicalSerializeWithFlags, Flags, SerializationError, Valid,
+    };
+    use ark_std::{test_rng, vec::Vec, UniformRand};
 
     test_field!(generated; TestField; prime);
+
+    /// Flag stored in the bit left free by the 31-bit canonical integer
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    struct TopBit(bool);
+
+    impl Flags for TopBit {
+        const BIT_SIZE: usize = 1;
+
+        fn u8_bitmask(&self) -> u8 {
+            u8::from(self.0) << 7
+        }
+
+        fn from_u8(value: u8) -> Option<Self> {
+            Some(Self(value >> 7 == 1))
+        }
+    }
+
+    #[test]
+    fn serialization_is_little_endian_canonical_integer() {
+        let mut bytes = Vec::new();
+        TestField(0x0102_0304)
+            .serialize_compressed(&mut bytes)
+            .unwrap();
+        assert_eq!(bytes, [4, 3, 2, 1]);
+
+        // both encodings of zero serialize to the canonical zero
+        let mut zero = Vec::new();
+        TestField(MODULUS).serialize_compressed(&mut zero).unwrap();
+        assert_eq!(zero, [0; 4]);
+        let decoded = TestField::deserialize_compressed(&zero[..]).unwrap();
+        assert!(decoded.is_zero());
+
+        let max = TestField(MODULUS - 1);
+        let mut bytes = Vec::new();
+        max.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes, [0xfe, 0xff, 0xff, 0x7f]);
+        assert_eq!(TestField::deserialize_compressed(&bytes[..]).unwrap(), max);
+    }
+
+    #[test]
+    fn deserialization_rejects_integers_above_modulus() {
+        for bytes in [[0xff; 4], [0, 0, 0, 0x80]] {
+            assert!(matches!(
+                TestField::deserialize_compressed(&bytes[..]),
+                Err(SerializationError::InvalidData)
+            ));
+        }
+        assert!(TestField(u32::MAX).check().is_err());
+        assert!(TestField(MODULUS).check().is_ok());
+    }
+
+    #[test]
+    fn serialization_packs_flag_into_top_bit() {
+        let mut rng = test_rng();
+        for _ in 0..100 {
+            let value = TestField::rand(&mut rng);
+            for flag in [TopBit(false), TopBit(true)] {
+                let mut bytes = Vec::new();
+                value.serialize_with_flags(&mut bytes, flag).unwrap();
+                assert_eq!(bytes.len(), value.serialized_size_with_flags::<TopBit>());
+                assert_eq!(bytes.len(), 4);
+                assert_eq!(bytes[3] >> 7, u8::from(flag.0));
+
+                let (decoded, decoded_flag) =
+                    TestField::deserialize_with_flags::<_, TopBit>(&bytes[..]).unwrap();
+                assert_eq!(decoded, value);
+                assert_eq!(decoded_flag, flag);
+            }
+        }
+    }
 }
@@ -0,0 +1,380 @@
+//! The Monolith permutation over [`Fp`] with width 16 and a 2-to-1
+//! compression function producing 8 element digests.
+//!
+//! Monolith (<https://eprint.iacr.org/2023/1025>) replaces the power map of
+//! Poseidon with a lookup based S-box that is cheap both natively and in
+//! arithmetic circuits. A round applies three layers:
+//!
+//! - Bars splits each of the first 8 elements into three bytes and a 7-bit
+//!   limb. Bytes are mapped with the chi-like S-box
+//!   `(y ^ (!(y <<< 1) & (y <<< 2) & (y <<< 3))) <<< 1` and the top limb with
+//!   the same construction on 7 bits. Both S-boxes fix the all zero and all one
+//!   limbs so `0` and `p` are fixed points and the result is always at most
+//!   `p`.
+//! - Bricks is the Feistel layer `x_i += x_(i-1)^2` for `i > 0`, computed
+//!   from the last element down so every square uses the old value.
+//! - Concrete multiplies by a circulant MDS matrix with 16-bit entries.
+//!
+//! The permutation applies Concrete, 5 rounds that add round constants after
+//! Concrete and a final round without constants. The round constants are
+//! sampled with SHAKE-128 as in the paper and agree with Plonky3's
+//! `MonolithMersenne31`.
+
+use super::Fp;
+use ark_ff::Field;
+
+/// Number of rounds with round constants. The permutation has one more round
+/// without constants.
+const NUM_FULL_ROUNDS: usize = 5;
+
+/// Number of elements passed through the Bars layer
+const NUM_BARS: usize = 8;
+
+/// Number of elements in a digest
+pub const DIGEST_LEN: usize = 8;
+
+/// A compression output
+pub type Digest = [Fp; DIGEST_LEN];
+
+/// The round constants and MDS matrix of a Monolith instance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Monolith<const WIDTH: usize> {
+    round_constants: [[Fp; WIDTH]; NUM_FULL_ROUNDS],
+    /// First row of the circulant Concrete matrix
+    mds_row: [u32; WIDTH],
+}
+
+impl Monolith<16> {
+    /// Returns the width 16 instance.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            round_constants: to_fp_rows(ROUND_CONSTANTS_16),
+            mds_row: [
+                61402, 17845, 26798, 59689, 12021, 40901, 41351, 27521, 56951, 12034, 53865, 43244,
+                7454, 33823, 28750, 1108,
+            ],
+        }
+    }
+}
+
+impl Default for Monolith<16> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WIDTH: usize> Monolith<WIDTH> {
+    /// Applies the permutation to `state` in place.
+    pub fn permute(&self, state: &mut [Fp; WIDTH]) {
+        self.concrete(state);
+        for constants in &self.round_constants {
+            bars(state);
+            bricks(state);
+            self.concrete(state);
+            for (value, constant) in state.iter_mut().zip(constants) {
+                *value += constant;
+            }
+        }
+        bars(state);
+        bricks(state);
+        self.concrete(state);
+    }
+
+    /// Compresses two digests by truncating the permutation of `left || right`
+    /// to [`DIGEST_LEN`] elements.
+    #[must_use]
+    pub fn compress(&self, left: &Digest, right: &Digest) -> Digest {
+        crate::compression::truncated_permutation(|state| self.permute(state), left, right)
+    }
+
+    /// Multiplies by the circulant matrix with first row `mds_row`, so
+    /// `x'_i = sum_j mds_row[(j - i) mod WIDTH] * x_j`.
+    ///
+    /// Elements are at most `p < 2^31` and the entries below `2^16`, so the 16
+    /// products sum to less than `2^51` and are reduced once with
+    /// [`Fp::reduce_u62`].
+    #[inline]
+    fn concrete(&self, state: &mut [Fp; WIDTH]) {
+        let input = *state;
+        for (i, value) in state.iter_mut().enumerate() {
+            let sum: u64 = input
+                .iter()
+                .enumerate()
+                .map(|(j, x)| u64::from(self.mds_row[(WIDTH + j - i) % WIDTH]) * u64::from(x.0))
+                .sum();
+            *value = Fp::reduce_u62(sum);
+        }
+    }
+}
+
+/// Applies [`bar`] to the first [`NUM_BARS`] elements.
+#[inline]
+fn bars<const WIDTH: usize>(state: &mut [Fp; WIDTH]) {
+    for value in &mut state[..NUM_BARS] {
+        *value = Fp(bar(value.0));
+    }
+}
+
+/// Maps the three low bytes of `x < 2^31` with [`s_box`] and the top 7 bits
+/// with [`final_s_box`], both looked up in precomputed tables.
+#[inline]
+const fn bar(x: u32) -> u32 {
+    let b0 = S_BOX[(x & 0xFF) as usize] as u32;
+    let b1 = S_BOX[((x >> 8) & 0xFF) as usize] as u32;
+    let b2 = S_BOX[((x >> 16) & 0xFF) as usize] as u32;
+    let b3 = FINAL_S_BOX[(x >> 24) as usize] as u32;
+    b0 | (b1 << 8) | (b2 << 16) | (b3 << 24)
+}
+
+const S_BOX: [u8; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        #[allow(clippy::cast_possible_truncation)]
+        let y = i as u8;
+        table[i] = s_box(y);
+        i += 1;
+    }
+    table
+};
+
+const FINAL_S_BOX: [u8; 128] = {
+    let mut table = [0; 128];
+    let mut i = 0;
+    while i < 128 {
+        #[allow(clippy::cast_possible_truncation)]
+        let y = i as u8;
+        table[i] = final_s_box(y);
+        i += 1;
+    }
+    table
+};
+
+/// Computes `(y ^ (!(y <<< 1) & (y <<< 2) & (y <<< 3))) <<< 1` on a byte.
+const fn s_box(y: u8) -> u8 {
+    (y ^ (!y.rotate_left(1) & y.rotate_left(2) & y.rotate_left(3))).rotate_left(1)
+}
+
+/// Computes `(y ^ (!(y <<< 1) & (y <<< 2))) <<< 1` with rotations on 7 bits.
+const fn final_s_box(y: u8) -> u8 {
+    let rot1 = (y >> 6) | (y << 1);
+    let rot2 = (y >> 5) | (y << 2);
+    let tmp = (y ^ (!rot1 & rot2)) & 0x7F;
+    ((tmp >> 6) | (tmp << 1)) & 0x7F
+}
+
+/// Computes `x_i += x_(i-1)^2` for `i > 0` using the values from before the
+/// layer.
+#[inline]
+fn bricks<const WIDTH: usize>(state: &mut [Fp; WIDTH]) {
+    for i in (1..WIDTH).rev() {
+        state[i] += state[i - 1].square();
+    }
+}
+
+const fn to_fp_rows<const N: usize>(
+    rows: [[u32; N]; NUM_FULL_ROUNDS],
+) -> [[Fp; N]; NUM_FULL_ROUNDS] {
+    let mut res = [[Fp::ZERO; N]; NUM_FULL_ROUNDS];
+    let mut i = 0;
+    while i < NUM_FULL_ROUNDS {
+        let mut j = 0;
+        while j < N {
+            res[i][j] = Fp(rows[i][j]);
+            j += 1;
+        }
+        i += 1;
+    }
+    res
+}
+
+const ROUND_CONSTANTS_16: [[u32; 16]; NUM_FULL_ROUNDS] = [
+    [
+        0x3d98_fe90,
+        0x14cb_3ccb,
+        0x7c0b_1f93,
+        0x3b4d_56cd,
+        0x03de_ae45,
+        0x285f_40b2,
+        0x676d_c2ac,
+        0x0196_224e,
+        0x5bac_69ca,
+        0x65ee_092f,
+        0x3b5d_6dfb,
+        0x4fa7_7b75,
+        0x7a4c_68ae,
+        0x1aaa_334f,
+        0x6d10_12dc,
+        0x2ad9_c57e,
+    ],
+    [
+        0x1c46_a94c,
+        0x20bb_face,
+        0x0862_4421,
+        0x2652_4ae0,
+        0x136e_2ee2,
+        0x7c6d_25ca,
+        0x5531_244d,
+        0x200b_ce3b,
+        0x7b8d_385f,
+        0x2a2c_e224,
+        0x1cc7_e6b9,
+        0x316e_341b,
+        0x3c97_cc8e,
+        0x08d4_5359,
+        0x17b5_76d8,
+        0x02fb_1e41,
+    ],
+    [
+        0x3577_8631,
+        0x23a2_9605,
+        0x173e_09e7,
+        0x41a5_2825,
+        0x4e98_962a,
+        0x7482_8a97,
+        0x6aa6_4557,
+        0x1f88_6887,
+        0x366e_5c49,
+        0x65c6_9913,
+        0x24c3_ebda,
+        0x0bbf_3f2c,
+        0x465a_f76d,
+        0x0e64_4935,
+        0x6558_4d01,
+        0x68aa_622d,
+    ],
+    [
+        0x724d_c1f9,
+        0x4aa5_8253,
+        0x4be9_c141,
+        0x0caa_81bf,
+        0x4b9c_f923,
+        0x53a8_ff1e,
+        0x2489_3eb2,
+        0x3d17_5171,
+        0x5c33_806c,
+        0x6870_6e51,
+        0x2d26_2d0a,
+        0x369c_c105,
+        0x67ab_d856,
+        0x3c56_2a5c,
+        0x4aca_8f2e,
+        0x6f36_eff3,
+    ],
+    [
+        0x1fe2_1035,
+        0x76e7_19ed,
+        0x5f44_064b,
+        0x1e74_c8c5,
+        0x6f5b_4896,
+        0x4738_677f,
+        0x1584_389a,
+        0x4db0_403a,
+        0x141d_c716,
+        0x4985_b4fd,
+        0x3f13_8de3,
+        0x5441_fa11,
+        0x2fa8_73d0,
+        0x02de_e437,
+        0x4fd1_6101,
+        0x4237_2f45,
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{bar, bricks, final_s_box, s_box, Monolith};
+    use crate::fp31::{Fp, MODULUS};
+    use ark_ff::Field;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn s_box_fixed_points() {
+        assert_eq!(s_box(0), 0);
+        assert_eq!(s_box(0xFF), 0xFF);
+        assert_eq!(final_s_box(0), 0);
+        assert_eq!(final_s_box(0x7F), 0x7F);
+    }
+
+    #[test]
+    fn final_s_box_is_a_permutation() {
+        let mut seen = [false; 128];
+        for y in 0..128 {
+            let z = final_s_box(y);
+            assert!(z < 128);
+            assert!(!seen[z as usize]);
+            seen[z as usize] = true;
+        }
+    }
+
+    #[test]
+    fn bar_maps_both_zero_encodings_to_zero() {
+        assert_eq!(bar(0), 0);
+        assert_eq!(bar(MODULUS), MODULUS);
+        let mut rng = test_rng();
+        for _ in 0..100 {
+            assert!(bar(Fp::rand(&mut rng).0) <= MODULUS);
+        }
+    }
+
+    #[test]
+    fn bricks_squares_previous_elements() {
+        let mut rng = test_rng();
+        let state: [Fp; 16] = core::array::from_fn(|_| Fp::rand(&mut rng));
+        let mut actual = state;
+        bricks(&mut actual);
+        assert_eq!(actual[0], state[0]);
+        for i in 1..16 {
+            assert_eq!(actual[i], state[i] + state[i - 1].square());
+        }
+    }
+
+    #[test]
+    fn concrete_matches_matrix() {
+        let monolith = Monolith::<16>::new();
+        let mut rng = test_rng();
+        for _ in 0..10 {
+            let state: [Fp; 16] = core::array::from_fn(|_| Fp::rand(&mut rng));
+            let expected: [Fp; 16] = core::array::from_fn(|i| {
+                (0..16)
+                    .map(|j| Fp::from(monolith.mds_row[(16 + j - i) % 16]) * state[j])
+                    .sum()
+            });
+            let mut actual = state;
+            monolith.concrete(&mut actual);
+            assert_eq!(actual, expected);
+        }
+        let mut state = [Fp(MODULUS); 16];
+        monolith.concrete(&mut state);
+        assert_eq!(state, [Fp::ZERO; 16]);
+    }
+
+    /// Output of the Horizen Labs reference implementation on
+    /// `[0, 1, ..., 15]`, which Plonky3's `MonolithMersenne31` also checks
+    #[test]
+    fn permute_width_16_kat() {
+        let mut state: [Fp; 16] = core::array::from_fn(|i| Fp::from(i as u64));
+        Monolith::<16>::new().permute(&mut state);
+        let expected = [
+            609_156_607,
+            290_107_110,
+            1_900_746_598,
+            1_734_707_571,
+            2_050_994_835,
+            1_648_553_244,
+            1_307_647_296,
+            1_941_164_548,
+            1_707_113_065,
+            1_477_714_255,
+            1_170_160_793,
+            93_800_695,
+            769_879_348,
+            375_548_503,
+            1_989_726_444,
+            1_349_325_635,
+        ]
+        .map(Fp);
+        assert_eq!(state, expected);
+    }
+}
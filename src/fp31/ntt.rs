@@ -0,0 +1,515 @@
+//! Number theoretic transforms over [`Fp`].
+//!
+//! The multiplicative group has order `p - 1 = 2 * 3^2 * 7 * 11 * 31 * 151 * 331`
+//! so there are no large power-of-two subgroups. Instead the transforms support
+//! every size `n` dividing `p - 1`. Evaluations are in natural order at the
+//! powers of [`root_of_unity(n)`](root_of_unity), which matches
+//! `ark_poly::MixedRadixEvaluationDomain` for the sizes `2^a * 3^b` it supports.
+//!
+//! A size is decomposed recursively:
+//! * coprime factors `n = n1 * n2` use the Good-Thomas prime-factor algorithm,
+//!   which needs no twiddle factors,
+//! * the prime power `9` takes a radix-3 Cooley-Tukey step,
+//! * primes up to 151 are evaluated directly as a product with the DFT matrix,
+//! * the prime 331 uses Rader's algorithm, which turns the transform into a
+//!   cyclic convolution of length `q - 1`. The convolution is computed with
+//!   transforms of length `q - 1` when it divides `p - 1` and otherwise with
+//!   zero padded transforms of a length made up of primes smaller than `q`.
+//!
+//! Every step transforms all subsequences of the same length at once so that
+//! roots and Rader kernels are computed once per step.
+
+use super::{Fp, MODULUS};
+use ark_ff::{FftField, Field};
+use ark_std::{vec, vec::Vec};
+
+/// Smallest prime for which Rader's algorithm is faster than evaluating the
+/// transform directly. The padded convolutions make it more than twice as
+/// expensive as an FFT of the same length.
+const MIN_RADER_PRIME: usize = 200;
+
+/// Returns the primitive `n`-th root of unity or `None` if `n` does not divide
+/// `p - 1`.
+///
+/// The root is `g^((p - 1) / n)` for the generator `g` of the multiplicative
+/// group. This agrees with `Fp::get_root_of_unity(n)` wherever the latter is
+/// defined.
+#[must_use]
+pub fn root_of_unity(n: u64) -> Option<Fp> {
+    let order = u64::from(MODULUS - 1);
+    (n != 0 && order.is_multiple_of(n)).then(|| Fp::GENERATOR.pow([order / n]))
+}
+
+/// Evaluates the polynomial with coefficients `values` at the powers of the
+/// `n`-th root of unity in place.
+///
+/// # Panics
+///
+/// Panics if the length does not divide `p - 1`.
+pub fn ntt(values: &mut [Fp]) {
+    transform(values, false);
+}
+
+/// Interpolates the coefficients of a polynomial from its evaluations at the
+/// powers of the `n`-th root of unity in place.
+///
+/// # Panics
+///
+/// Panics if the length does not divide `p - 1`.
+pub fn intt(values: &mut [Fp]) {
+    transform(values, true);
+    let n_inv = Fp::from(values.len() as u64).inverse().unwrap();
+    for value in values {
+        *value *= n_inv;
+    }
+}
+
+/// Evaluates the polynomial with coefficients `values` on the coset
+/// `offset * <ω>` in place.
+///
+/// # Panics
+///
+/// Panics if the length does not divide `p - 1`.
+pub fn coset_ntt(values: &mut [Fp], offset: Fp) {
+    scale_by_powers(values, offset);
+    ntt(values);
+}
+
+/// Interpolates the coefficients of a polynomial from its evaluations on the
+/// coset `offset * <ω>` in place.
+///
+/// # Panics
+///
+/// Panics if `offset` is zero or the length does not divide `p - 1`.
+pub fn coset_intt(values: &mut [Fp], offset: Fp) {
+    intt(values);
+    scale_by_powers(values, offset.inverse().expect("coset offset is zero"));
+}
+
+fn transform(values: &mut [Fp], inverse: bool) {
+    let n = values.len();
+    assert!(
+        n != 0 && (MODULUS as usize - 1).is_multiple_of(n),
+        "length must divide p - 1"
+    );
+    transform_batch(values, n, inverse);
+}
+
+/// Transforms each of the consecutive chunks of length `n` in `data`.
+fn transform_batch(data: &mut [Fp], n: usize, inverse: bool) {
+    if n == 1 {
+        return;
+    }
+    let q = smallest_prime_factor(n);
+    let mut prime_power = q;
+    while (n / prime_power).is_multiple_of(q) {
+        prime_power *= q;
+    }
+    if prime_power != n {
+        prime_factor(data, prime_power, n / prime_power, inverse);
+    } else if q != n {
+        cooley_tukey(data, q, n / q, inverse);
+    } else if n < MIN_RADER_PRIME {
+        naive(data, n, inverse);
+    } else {
+        rader(data, n, inverse);
+    }
+}
+
+/// Evaluates transforms of a prime length `n` directly as a product with the
+/// DFT matrix. The reduced products are summed in a `u64`, which is reduced
+/// once per evaluation.
+fn naive(data: &mut [Fp], n: usize, inverse: bool) {
+    let roots = powers(root(n, inverse), n);
+    let matrix: Vec<Fp> = (0..n * n).map(|i| roots[(i / n) * (i % n) % n]).collect();
+    let mut inputs = vec![Fp::ZERO; n];
+    for chunk in data.chunks_exact_mut(n) {
+        inputs.copy_from_slice(chunk);
+        for (output, row) in chunk.iter_mut().zip(matrix.chunks_exact(n)) {
+            let sum: u64 = inputs
+                .iter()
+                .zip(row)
+                .map(|(x, root)| u64::from((*x * root).0))
+                .sum();
+            *output = Fp::from(sum);
+        }
+    }
+}
+
+/// Takes a decimation-in-frequency step for `n = radix * m`. The elements
+/// `x[i + m*s]` are combined with a radix-`radix` DFT and twiddled, the blocks
+/// of length `m` are transformed recursively and the result is transposed so
+/// that block `k` holds the evaluations `X[radix*j + k]`.
+fn cooley_tukey(data: &mut [Fp], radix: usize, m: usize, inverse: bool) {
+    let n = radix * m;
+    let root = root(n, inverse);
+    let radix_roots = powers(root.pow([m as u64]), radix);
+    let twiddles = powers(root, n);
+    let mut inputs = vec![Fp::ZERO; radix];
+    for chunk in data.chunks_exact_mut(n) {
+        for i in 0..m {
+            for (s, input) in inputs.iter_mut().enumerate() {
+                *input = chunk[i + m * s];
+            }
+            for k in 0..radix {
+                let sum: Fp = inputs
+                    .iter()
+                    .enumerate()
+                    .map(|(s, x)| *x * radix_roots[s * k % radix])
+                    .sum();
+                chunk[i + m * k] = sum * twiddles[i * k];
+            }
+        }
+    }
+
+    transform_batch(data, m, inverse);
+
+    let mut scratch = vec![Fp::ZERO; n];
+    for chunk in data.chunks_exact_mut(n) {
+        scratch.copy_from_slice(chunk);
+        for (k, block) in scratch.chunks_exact(m).enumerate() {
+            for (j, value) in block.iter().enumerate() {
+                chunk[radix * j + k] = *value;
+            }
+        }
+    }
+}
+
+/// Good-Thomas prime-factor step for coprime `n = n1 * n2`.
+///
+/// The input `x[(i1*n2 + i2*n1) mod n]` is viewed as an `n2 x n1` matrix with
+/// rows indexed by `i2`. Since `ω_n^(n2) = ω_n1` and `ω_n^(n1) = ω_n2` the
+/// transform is a length `n1` transform of every row followed by a length `n2`
+/// transform of every column. The evaluation for row `k1` and column `k2` is
+/// `X[k]` with `k = k1 mod n1` and `k = k2 mod n2`.
+fn prime_factor(data: &mut [Fp], n1: usize, n2: usize, inverse: bool) {
+    let n = n1 * n2;
+    let input_index: Vec<usize> = (0..n)
+        .map(|r| ((r % n1) * n2 + (r / n1) * n1) % n)
+        .collect();
+    // CRT basis `e1 = 1 mod n1, e1 = 0 mod n2` and `e2 = 0 mod n1, e2 = 1 mod n2`
+    let e1 = n2 * inverse_mod(n2 % n1, n1);
+    let e2 = n1 * inverse_mod(n1 % n2, n2);
+    let output_index: Vec<usize> = (0..n)
+        .map(|r| ((r / n2) * e1 + (r % n2) * e2) % n)
+        .collect();
+
+    let mut scratch = vec![Fp::ZERO; data.len()];
+    for (chunk, rows) in data.chunks_exact(n).zip(scratch.chunks_exact_mut(n)) {
+        for (value, &index) in rows.iter_mut().zip(&input_index) {
+            *value = chunk[index];
+        }
+    }
+
+    transform_batch(&mut scratch, n1, inverse);
+
+    for (chunk, rows) in data.chunks_exact_mut(n).zip(scratch.chunks_exact(n)) {
+        for (i2, row) in rows.chunks_exact(n1).enumerate() {
+            for (k1, value) in row.iter().enumerate() {
+                chunk[k1 * n2 + i2] = *value;
+            }
+        }
+    }
+
+    transform_batch(data, n2, inverse);
+
+    for (chunk, columns) in data.chunks_exact_mut(n).zip(scratch.chunks_exact_mut(n)) {
+        columns.copy_from_slice(chunk);
+        for (value, &index) in columns.iter().zip(&output_index) {
+            chunk[index] = *value;
+        }
+    }
+}
+
+/// Rader's algorithm for a prime length `q`.
+///
+/// With a generator `g` of the multiplicative group mod `q` the evaluations
+/// `X[g^(-m)] - x[0]` are the cyclic convolution of `a_j = x[g^j]` with the
+/// kernel `b_j = ω^(g^(-j))`, both of length `q - 1`. The convolution is
+/// computed by transforms of length `len`, which is either `q - 1` or large
+/// enough to hold the linear convolution whose upper half is folded back.
+fn rader(data: &mut [Fp], q: usize, inverse: bool) {
+    let conv_len = q - 1;
+    let len = convolution_length(q);
+    let g = primitive_root_mod(q);
+    let mut gather = Vec::with_capacity(conv_len);
+    let mut index = 1;
+    for _ in 0..conv_len {
+        gather.push(index);
+        index = index * g % q;
+    }
+    // `g^(-m) = g^(q - 1 - m)`
+    let scatter: Vec<usize> = (0..conv_len)
+        .map(|m| gather[(conv_len - m) % conv_len])
+        .collect();
+
+    // the kernel is transformed once and absorbs the `1/len` scaling
+    let roots = powers(root(q, inverse), q);
+    let mut kernel = vec![Fp::ZERO; len];
+    for (b, &index) in kernel.iter_mut().zip(&scatter) {
+        *b = roots[index];
+    }
+    transform_batch(&mut kernel, len, false);
+    let len_inv = Fp::from(len as u64).inverse().unwrap();
+    for b in &mut kernel {
+        *b *= len_inv;
+    }
+
+    let num_chunks = data.len() / q;
+    let mut scratch = vec![Fp::ZERO; num_chunks * len];
+    for (chunk, a) in data.chunks_exact(q).zip(scratch.chunks_exact_mut(len)) {
+        for (value, &index) in a.iter_mut().zip(&gather) {
+            *value = chunk[index];
+        }
+    }
+
+    transform_batch(&mut scratch, len, false);
+    for a in scratch.chunks_exact_mut(len) {
+        for (value, b) in a.iter_mut().zip(&kernel) {
+            *value *= b;
+        }
+    }
+    transform_batch(&mut scratch, len, true);
+
+    let mut conv = vec![Fp::ZERO; conv_len];
+    for (chunk, c) in data.chunks_exact_mut(q).zip(scratch.chunks_exact(len)) {
+        // fold the linear convolution, its length is less than `2 * (q - 1)`
+        let (low, high) = c.split_at(conv_len);
+        conv.copy_from_slice(low);
+        for (value, folded) in conv.iter_mut().zip(high) {
+            *value += folded;
+        }
+        let x0 = chunk[0];
+        chunk[0] = chunk.iter().sum();
+        for (value, &index) in conv.iter().zip(&scatter) {
+            chunk[index] = x0 + value;
+        }
+    }
+}
+
+/// Returns `q - 1` if it divides `p - 1` and otherwise the smallest divisor of
+/// `p - 1` that fits the linear convolution of two length `q - 1` sequences and
+/// only has prime factors smaller than `q`.
+fn convolution_length(q: usize) -> usize {
+    let order = MODULUS as usize - 1;
+    if order.is_multiple_of(q - 1) {
+        return q - 1;
+    }
+    (2 * q - 3..=order)
+        .find(|&len| order.is_multiple_of(len) && largest_prime_factor(len) < q)
+        .unwrap()
+}
+
+/// Returns the primitive `n`-th root of unity or its inverse.
+fn root(n: usize, inverse: bool) -> Fp {
+    let root = root_of_unity(n as u64).unwrap();
+    if inverse {
+        root.inverse().unwrap()
+    } else {
+        root
+    }
+}
+
+fn smallest_prime_factor(n: usize) -> usize {
+    (2..=n).find(|d| n.is_multiple_of(*d)).unwrap()
+}
+
+fn largest_prime_factor(mut n: usize) -> usize {
+    let mut largest = 1;
+    while n > 1 {
+        largest = smallest_prime_factor(n);
+        n /= largest;
+    }
+    largest
+}
+
+/// Returns the smallest generator of the multiplicative group mod a prime `q`.
+fn primitive_root_mod(q: usize) -> usize {
+    let mut factors = Vec::new();
+    let mut rest = q - 1;
+    while rest > 1 {
+        let factor = smallest_prime_factor(rest);
+        factors.push(factor);
+        while rest.is_multiple_of(factor) {
+            rest /= factor;
+        }
+    }
+    (2..q)
+        .find(|&g| factors.iter().all(|f| pow_mod(g, (q - 1) / f, q) != 1))
+        .unwrap()
+}
+
+/// Returns the inverse of `a` mod `m` for small coprime `a` and `m`.
+fn inverse_mod(a: usize, m: usize) -> usize {
+    (1..m).find(|x| a * x % m == 1).unwrap_or(0)
+}
+
+fn pow_mod(base: usize, exp: usize, m: usize) -> usize {
+    (0..exp).fold(1 % m, |acc, _| acc * base % m)
+}
+
+/// Returns `[1, x, x^2, ..., x^(n-1)]`.
+fn powers(x: Fp, n: usize) -> Vec<Fp> {
+    let mut res = Vec::with_capacity(n);
+    let mut acc = Fp::ONE;
+    for _ in 0..n {
+        res.push(acc);
+        acc *= x;
+    }
+    res
+}
+
+/// Multiplies the `i`-th element of `values` by `x^i`.
+fn scale_by_powers(values: &mut [Fp], x: Fp) {
+    let mut acc = Fp::ONE;
+    for value in values {
+        *value *= acc;
+        acc *= x;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{coset_intt, coset_ntt, intt, naive, ntt, rader, root_of_unity, MODULUS};
+    use crate::fp31::Fp;
+    use ark_ff::{FftField, Field};
+    use ark_poly::{EvaluationDomain, MixedRadixEvaluationDomain};
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    /// Prime factors of `p - 1` and their multiplicities
+    const FACTORS: [(u64, u32); 7] = [(2, 1), (3, 2), (7, 1), (11, 1), (31, 1), (151, 1), (331, 1)];
+
+    fn random_values(n: usize) -> Vec<Fp> {
+        let mut rng = test_rng();
+        (0..n).map(|_| Fp::rand(&mut rng)).collect()
+    }
+
+    fn divisors_of_order() -> Vec<u64> {
+        let mut divisors = ark_std::vec![1u64];
+        for (q, multiplicity) in FACTORS {
+            let mut next = Vec::new();
+            for d in &divisors {
+                let mut power = 1;
+                for _ in 0..=multiplicity {
+                    next.push(d * power);
+                    power *= q;
+                }
+            }
+            divisors = next;
+        }
+        divisors.sort_unstable();
+        divisors
+    }
+
+    #[test]
+    fn root_of_unity_matches_fft_field() {
+        for n in [1, 2, 3, 6, 9, 18] {
+            assert_eq!(root_of_unity(n), Fp::get_root_of_unity(n), "n = {n}");
+        }
+        let order = u64::from(MODULUS - 1);
+        assert_eq!(divisors_of_order().len(), 192);
+        // the root has order exactly `n` if `root^(n/q) != 1` for every prime `q | n`
+        for n in divisors_of_order() {
+            let root = root_of_unity(n).unwrap();
+            assert_eq!(root.pow([n]), Fp::ONE, "n = {n}");
+            assert!(
+                FACTORS
+                    .iter()
+                    .all(|(q, _)| !n.is_multiple_of(*q) || root.pow([n / q]) != Fp::ONE),
+                "n = {n}"
+            );
+        }
+        assert_eq!(root_of_unity(order), Some(Fp::GENERATOR));
+        assert_eq!(root_of_unity(0), None);
+        assert_eq!(root_of_unity(4), None);
+        assert_eq!(root_of_unity(5), None);
+    }
+
+    #[test]
+    fn matches_mixed_radix_domain() {
+        let offset = Fp::GENERATOR;
+        for n in [1, 2, 3, 6, 9, 18] {
+            let domain = MixedRadixEvaluationDomain::<Fp>::new(n).unwrap();
+            assert_eq!(domain.size(), n);
+            let coset = domain.get_coset(offset).unwrap();
+            let coeffs = random_values(n);
+
+            let mut expected = coeffs.clone();
+            domain.fft_in_place(&mut expected);
+            let mut evals = coeffs.clone();
+            ntt(&mut evals);
+            assert_eq!(evals, expected, "n = {n}");
+
+            let mut expected = evals.clone();
+            domain.ifft_in_place(&mut expected);
+            intt(&mut evals);
+            assert_eq!(evals, expected, "n = {n}");
+            assert_eq!(evals, coeffs, "n = {n}");
+
+            let mut expected = coeffs.clone();
+            coset.fft_in_place(&mut expected);
+            let mut evals = coeffs.clone();
+            coset_ntt(&mut evals, offset);
+            assert_eq!(evals, expected, "n = {n}");
+
+            let mut expected = evals.clone();
+            coset.ifft_in_place(&mut expected);
+            coset_intt(&mut evals, offset);
+            assert_eq!(evals, expected, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn matches_naive_evaluation() {
+        for n in divisors_of_order().into_iter().filter(|n| *n <= 700) {
+            let root = root_of_unity(n).unwrap();
+            let n = usize::try_from(n).unwrap();
+            let coeffs = random_values(n);
+            let expected: Vec<Fp> = (0..n)
+                .map(|i| {
+                    let point = root.pow([i as u64]);
+                    coeffs.iter().rev().fold(Fp::ZERO, |acc, c| acc * point + c)
+                })
+                .collect();
+            let mut evals = coeffs.clone();
+            ntt(&mut evals);
+            assert_eq!(evals, expected, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn rader_matches_naive() {
+        for q in [7, 11, 31, 151, 331] {
+            let values = random_values(3 * q);
+            for inverse in [false, true] {
+                let mut expected = values.clone();
+                naive(&mut expected, q, inverse);
+                let mut evals = values.clone();
+                rader(&mut evals, q, inverse);
+                assert_eq!(evals, expected, "q = {q}");
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for n in divisors_of_order().into_iter().filter(|n| *n <= 1 << 15) {
+            let n = usize::try_from(n).unwrap();
+            let coeffs = random_values(n);
+            let mut values = coeffs.clone();
+            ntt(&mut values);
+            intt(&mut values);
+            assert_eq!(values, coeffs, "n = {n}");
+
+            coset_ntt(&mut values, Fp::GENERATOR);
+            coset_intt(&mut values, Fp::GENERATOR);
+            assert_eq!(values, coeffs, "n = {n}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "length must divide p - 1")]
+    fn rejects_unsupported_lengths() {
+        ntt(&mut random_values(4));
+    }
+}
@@ -0,0 +1,485 @@
+//! Packed arithmetic on 8 elements of the 31-bit Mersenne field using AVX2.
+//!
+//! Every lane runs the same 32-bit additions, masks and shifts as the scalar
+//! implementation so the results are identical, including which of the two
+//! encodings of zero is produced. AVX2 multiplies the even and the odd lanes
+//! separately into 64-bit products which are then folded with the same
+//! Mersenne reduction as `Fp::mul`.
+//!
+//! AVX2 support is detected at runtime with `cpuid` and the scalar
+//! implementation is used without it. The detected routines can't be inlined
+//! so each operation pays for a call. The slice operations such as
+//! [`PackedFp31x8::mul_assign_slice`] check once and inline the routines into
+//! their loop. Compiling with AVX2 enabled, for example with
+//! `-C target-cpu=native`, removes the check everywhere.
+//!
+//! [`Fp`] also implements [`SimdField`] with the same routines written with
+//! `core::simd` for the portable [`Packed`](crate::packed::Packed) type.
+
+use super::{Fp, MODULUS};
+use crate::packed::{dispatch, dispatch_slice, PackedField, SimdField};
+use ark_ff::Field;
+use core::{
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    simd::{num::SimdUint, Simd},
+};
+
+/// Number of field elements in a [`PackedFp31x8`]
+pub const WIDTH: usize = 8;
+
+/// Eight elements of the 31-bit Mersenne field operated on lane-wise
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PackedFp31x8(pub [Fp; WIDTH]);
+
+impl PackedFp31x8 {
+    pub const ZERO: Self = Self([Fp::ZERO; WIDTH]);
+
+    pub const ONE: Self = Self([Fp::ONE; WIDTH]);
+
+    /// Returns a packed value with `value` in every lane.
+    #[must_use]
+    pub const fn broadcast(value: Fp) -> Self {
+        Self([value; WIDTH])
+    }
+
+    /// Packs the first [`WIDTH`] elements of `slice`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slice` has fewer than [`WIDTH`] elements.
+    #[must_use]
+    pub fn from_slice(slice: &[Fp]) -> Self {
+        Self(slice[..WIDTH].try_into().unwrap())
+    }
+
+    /// Writes the lanes to the first [`WIDTH`] elements of `slice`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slice` has fewer than [`WIDTH`] elements.
+    pub fn to_slice(&self, slice: &mut [Fp]) {
+        slice[..WIDTH].copy_from_slice(&self.0);
+    }
+
+    #[must_use]
+    pub fn double(&self) -> Self {
+        dispatch!(PackedFp31x8, double, scalar::double(*self), *self)
+    }
+
+    #[must_use]
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// Sets `lhs[i] += rhs[i]` for every `i`, checking for AVX2 once rather
+    /// than for every element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lhs` and `rhs` have different lengths.
+    pub fn add_assign_slice(lhs: &mut [Self], rhs: &[Self]) {
+        dispatch_slice!(PackedFp31x8, add, lhs, rhs);
+    }
+
+    /// Sets `lhs[i] *= rhs[i]` for every `i`, checking for AVX2 once rather
+    /// than for every element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lhs` and `rhs` have different lengths.
+    pub fn mul_assign_slice(lhs: &mut [Self], rhs: &[Self]) {
+        dispatch_slice!(PackedFp31x8, mul, lhs, rhs);
+    }
+
+    #[cfg(test)]
+    const fn to_raw(self) -> [u32; WIDTH] {
+        let mut raw = [0; WIDTH];
+        let mut i = 0;
+        while i < WIDTH {
+            raw[i] = self.0[i].0;
+            i += 1;
+        }
+        raw
+    }
+
+    #[inline]
+    fn from_raw(raw: [u32; WIDTH]) -> Self {
+        Self(raw.map(Fp))
+    }
+
+    /// Views the lanes as integers in place so the AVX2 routines load them
+    /// with a single read of the original memory.
+    #[cfg(target_arch = "x86_64")]
+    #[inline]
+    const fn as_raw(&self) -> &[u32; WIDTH] {
+        const _: () = assert!(core::mem::size_of::<Fp>() == core::mem::size_of::<u32>());
+        // SAFETY: `Fp` has the size of its only field which is therefore at
+        // offset 0.
+        unsafe { &*core::ptr::from_ref(self).cast() }
+    }
+}
+
+impl Add for PackedFp31x8 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        dispatch!(PackedFp31x8, add, scalar::add(self, rhs), self, rhs)
+    }
+}
+
+impl Sub for PackedFp31x8 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        dispatch!(PackedFp31x8, sub, scalar::sub(self, rhs), self, rhs)
+    }
+}
+
+impl Mul for PackedFp31x8 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        dispatch!(PackedFp31x8, mul, scalar::mul(self, rhs), self, rhs)
+    }
+}
+
+impl Neg for PackedFp31x8 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        dispatch!(PackedFp31x8, neg, scalar::neg(self), self)
+    }
+}
+
+impl AddAssign for PackedFp31x8 {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for PackedFp31x8 {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for PackedFp31x8 {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl From<Fp> for PackedFp31x8 {
+    fn from(value: Fp) -> Self {
+        Self::broadcast(value)
+    }
+}
+
+impl PackedField for PackedFp31x8 {
+    type Scalar = Fp;
+
+    const WIDTH: usize = WIDTH;
+
+    #[inline]
+    fn broadcast(value: Fp) -> Self {
+        Self::broadcast(value)
+    }
+
+    #[inline]
+    fn from_fn<G: FnMut(usize) -> Fp>(f: G) -> Self {
+        Self(core::array::from_fn(f))
+    }
+
+    #[inline]
+    fn lane(&self, i: usize) -> Fp {
+        self.0[i]
+    }
+
+    #[inline]
+    fn double(&self) -> Self {
+        Self::double(self)
+    }
+}
+
+/// Computes `(x & p) + (x >> 31)` in every lane.
+#[inline]
+fn simd_reduce<const N: usize>(x: Simd<u32, N>) -> Simd<u32, N> {
+    (x & Simd::splat(MODULUS)) + (x >> 31)
+}
+
+/// Lanes hold the same representation as [`Fp`] and follow the scalar
+/// routines in `fp31.rs`.
+impl SimdField for Fp {
+    type Lane = u32;
+
+    #[inline]
+    fn to_lane(self) -> u32 {
+        self.0
+    }
+
+    #[inline]
+    fn from_lane(lane: u32) -> Self {
+        Self(lane)
+    }
+
+    #[inline]
+    fn simd_add<const N: usize>(a: Simd<u32, N>, b: Simd<u32, N>) -> Simd<u32, N> {
+        simd_reduce(a + b)
+    }
+
+    #[inline]
+    fn simd_sub<const N: usize>(a: Simd<u32, N>, b: Simd<u32, N>) -> Simd<u32, N> {
+        simd_reduce(a + (Simd::splat(MODULUS) - b))
+    }
+
+    #[inline]
+    fn simd_mul<const N: usize>(a: Simd<u32, N>, b: Simd<u32, N>) -> Simd<u32, N> {
+        let t = a.cast::<u64>() * (b << 1).cast::<u64>();
+        let t0 = t.cast::<u32>() >> 1;
+        let t1 = (t >> 32).cast::<u32>();
+        simd_reduce(t0 + t1)
+    }
+
+    #[inline]
+    fn simd_neg<const N: usize>(a: Simd<u32, N>) -> Simd<u32, N> {
+        Simd::splat(MODULUS) - a
+    }
+
+    #[inline]
+    fn simd_double<const N: usize>(a: Simd<u32, N>) -> Simd<u32, N> {
+        ((a << 1) & Simd::splat(MODULUS)) + (a >> 30)
+    }
+}
+
+/// Lane-wise fallback using the scalar field arithmetic
+mod scalar {
+    use super::{PackedFp31x8, WIDTH};
+    use ark_ff::Field;
+
+    pub fn add(a: PackedFp31x8, b: PackedFp31x8) -> PackedFp31x8 {
+        PackedFp31x8(core::array::from_fn::<_, WIDTH, _>(|i| a.0[i] + b.0[i]))
+    }
+
+    pub fn sub(a: PackedFp31x8, b: PackedFp31x8) -> PackedFp31x8 {
+        PackedFp31x8(core::array::from_fn::<_, WIDTH, _>(|i| a.0[i] - b.0[i]))
+    }
+
+    pub fn mul(a: PackedFp31x8, b: PackedFp31x8) -> PackedFp31x8 {
+        PackedFp31x8(core::array::from_fn::<_, WIDTH, _>(|i| a.0[i] * b.0[i]))
+    }
+
+    pub fn neg(a: PackedFp31x8) -> PackedFp31x8 {
+        PackedFp31x8(a.0.map(|element| -element))
+    }
+
+    pub fn double(a: PackedFp31x8) -> PackedFp31x8 {
+        PackedFp31x8(a.0.map(|element| element.double()))
+    }
+}
+
+/// AVX2 versions of the scalar routines in `fp31.rs` operating on eight
+/// 32-bit lanes
+#[cfg(target_arch = "x86_64")]
+#[allow(clippy::cast_possible_wrap)]
+mod avx2 {
+    use super::{MODULUS, WIDTH};
+    use core::arch::x86_64::{
+        __m256i, _mm256_add_epi32, _mm256_and_si256, _mm256_blend_epi32, _mm256_loadu_si256,
+        _mm256_mul_epu32, _mm256_set1_epi32, _mm256_slli_epi32, _mm256_slli_epi64,
+        _mm256_srli_epi32, _mm256_srli_epi64, _mm256_storeu_si256, _mm256_sub_epi32,
+    };
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn load(x: &[u32; WIDTH]) -> __m256i {
+        _mm256_loadu_si256(x.as_ptr().cast())
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn store(x: __m256i) -> [u32; WIDTH] {
+        let mut res = [0; WIDTH];
+        _mm256_storeu_si256(res.as_mut_ptr().cast(), x);
+        res
+    }
+
+    /// Computes `(x & p) + (x >> 31)` in every lane.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn reduce(x: __m256i) -> __m256i {
+        let p = _mm256_set1_epi32(MODULUS as i32);
+        _mm256_add_epi32(_mm256_and_si256(x, p), _mm256_srli_epi32::<31>(x))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn add(a: &[u32; WIDTH], b: &[u32; WIDTH]) -> [u32; WIDTH] {
+        store(reduce(_mm256_add_epi32(load(a), load(b))))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sub(a: &[u32; WIDTH], b: &[u32; WIDTH]) -> [u32; WIDTH] {
+        // a - b = a + (p - b)
+        let p = _mm256_set1_epi32(MODULUS as i32);
+        store(reduce(_mm256_add_epi32(
+            load(a),
+            _mm256_sub_epi32(p, load(b)),
+        )))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn neg(a: &[u32; WIDTH]) -> [u32; WIDTH] {
+        let p = _mm256_set1_epi32(MODULUS as i32);
+        store(_mm256_sub_epi32(p, load(a)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn double(a: &[u32; WIDTH]) -> [u32; WIDTH] {
+        let a = load(a);
+        let p = _mm256_set1_epi32(MODULUS as i32);
+        let x = _mm256_slli_epi32::<1>(a);
+        store(_mm256_add_epi32(
+            _mm256_and_si256(x, p),
+            _mm256_srli_epi32::<30>(a),
+        ))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mul(a: &[u32; WIDTH], b: &[u32; WIDTH]) -> [u32; WIDTH] {
+        let a = load(a);
+        let b = _mm256_slli_epi32::<1>(load(b));
+        // 64-bit products `t = a * 2b` of the even and the odd lanes
+        let even = _mm256_mul_epu32(a, b);
+        let odd = _mm256_mul_epu32(_mm256_srli_epi64::<32>(a), _mm256_srli_epi64::<32>(b));
+        // `x = (t as u32 >> 1) + (t >> 32)` lands in the even 32-bit lanes of
+        // `x_even` and in the odd 32-bit lanes of `x_odd`
+        let x_even = _mm256_add_epi32(_mm256_srli_epi32::<1>(even), _mm256_srli_epi64::<32>(even));
+        let x_odd = _mm256_add_epi32(_mm256_srli_epi32::<1>(_mm256_slli_epi64::<32>(odd)), odd);
+        store(reduce(_mm256_blend_epi32::<0b1010_1010>(x_even, x_odd)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scalar, PackedFp31x8, WIDTH};
+    use crate::fp31::{Fp, MODULUS};
+    use ark_ff::Field;
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    /// Random elements together with both encodings of zero and values close
+    /// to the modulus.
+    fn test_vectors() -> Vec<PackedFp31x8> {
+        let mut rng = test_rng();
+        let edge = [0, 1, 2, MODULUS - 1, MODULUS, 1 << 30, (1 << 30) - 1];
+        let mut vectors = Vec::new();
+        for a in edge {
+            for b in edge {
+                vectors.push(PackedFp31x8(
+                    [a, b, a, b, b, a, MODULUS - a, MODULUS - b].map(Fp),
+                ));
+            }
+        }
+        for _ in 0..1000 {
+            vectors.push(PackedFp31x8(core::array::from_fn(|_| Fp::rand(&mut rng))));
+        }
+        vectors
+    }
+
+    #[test]
+    fn matches_scalar_arithmetic() {
+        let vectors = test_vectors();
+        for (a, b) in vectors.iter().zip(vectors.iter().rev()) {
+            let (a, b) = (*a, *b);
+            let sum = (a + b).to_raw();
+            let difference = (a - b).to_raw();
+            let product = (a * b).to_raw();
+            let negation = (-a).to_raw();
+            let double = a.double().to_raw();
+            let square = a.square().to_raw();
+            for i in 0..WIDTH {
+                assert_eq!(sum[i], (a.0[i] + b.0[i]).0);
+                assert_eq!(difference[i], (a.0[i] - b.0[i]).0);
+                assert_eq!(product[i], (a.0[i] * b.0[i]).0);
+                assert_eq!(negation[i], (-a.0[i]).0);
+                assert_eq!(double[i], a.0[i].double().0);
+                assert_eq!(square[i], a.0[i].square().0);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn avx2_matches_fallback() {
+        use super::avx2;
+
+        // the scalar fallback is covered by `matches_scalar_arithmetic`
+        if !crate::packed::has_avx2() {
+            return;
+        }
+        let vectors = test_vectors();
+        for (a, b) in vectors.iter().zip(vectors.iter().rev()) {
+            let (a, b) = (*a, *b);
+            let (x, y) = (a.as_raw(), b.as_raw());
+            // SAFETY: AVX2 support has been checked above.
+            unsafe {
+                assert_eq!(avx2::add(x, y), scalar::add(a, b).to_raw());
+                assert_eq!(avx2::sub(x, y), scalar::sub(a, b).to_raw());
+                assert_eq!(avx2::mul(x, y), scalar::mul(a, b).to_raw());
+                assert_eq!(avx2::neg(x), scalar::neg(a).to_raw());
+                assert_eq!(avx2::double(x), scalar::double(a).to_raw());
+            }
+        }
+    }
+
+    #[test]
+    fn slice_ops_match_elementwise() {
+        let vectors = test_vectors();
+        let rhs: Vec<PackedFp31x8> = vectors.iter().rev().copied().collect();
+        let mut sums = vectors.clone();
+        PackedFp31x8::add_assign_slice(&mut sums, &rhs);
+        let mut products = vectors.clone();
+        PackedFp31x8::mul_assign_slice(&mut products, &rhs);
+        for (i, (a, b)) in vectors.iter().zip(&rhs).enumerate() {
+            assert_eq!(sums[i], *a + *b);
+            assert_eq!(products[i], *a * *b);
+        }
+    }
+
+    #[test]
+    fn portable_matches_scalar_representation() {
+        use crate::packed::{Packed, PackedField};
+
+        let vectors = test_vectors();
+        for (a, b) in vectors.iter().zip(vectors.iter().rev()) {
+            let (x, y) = (Packed::from_array(a.0), Packed::from_array(b.0));
+            let raw = |packed: Packed<Fp, WIDTH>| packed.to_array().map(|element| element.0);
+            assert_eq!(raw(x + y), scalar::add(*a, *b).to_raw());
+            assert_eq!(raw(x - y), scalar::sub(*a, *b).to_raw());
+            assert_eq!(raw(x * y), scalar::mul(*a, *b).to_raw());
+            assert_eq!(raw(-x), scalar::neg(*a).to_raw());
+            assert_eq!(raw(x.double()), scalar::double(*a).to_raw());
+        }
+    }
+
+    #[test]
+    fn slice_round_trip() {
+        let mut rng = test_rng();
+        let values: Vec<Fp> = (0..=WIDTH).map(|_| Fp::rand(&mut rng)).collect();
+        let packed = PackedFp31x8::from_slice(&values);
+        let mut out = [Fp(0); WIDTH];
+        packed.to_slice(&mut out);
+        assert_eq!(out, values[..WIDTH]);
+        assert_eq!(PackedFp31x8::broadcast(values[0]).0, [values[0]; WIDTH]);
+    }
+}
@@ -0,0 +1,558 @@
+//! The Poseidon2 permutation over [`Fp`] with widths 16 and 24, a sponge and a
+//! 2-to-1 compression function producing 8 element digests.
+//!
+//! Poseidon2 (<https://eprint.iacr.org/2023/323>) runs 4 full rounds, the
+//! partial rounds and another 4 full rounds with the S-box `x^5`, the smallest
+//! exponent coprime to `p - 1 = 2 * 3^2 * 7 * 11 * 31 * 151 * 331`. Width 16
+//! has 14 partial rounds and width 24 has 22. Before the first round and after
+//! every full round the external layer `circ(2*M4, M4, ..., M4)` is applied,
+//! built from the 4x4 MDS matrix
+//!
+//! ```text
+//! [2 3 1 1]
+//! [1 2 3 1]
+//! [1 1 2 3]
+//! [3 1 1 2]
+//! ```
+//!
+//! Partial rounds use the internal layer `1 + diag(d)` where `1` is the all
+//! ones matrix and `d = [-2, 2^s_1, ..., 2^s_(t-1)]`. Multiplying by a power of
+//! two only shifts the 31-bit value, so the internal layer sums the state and
+//! the shifted elements in 64 bits and defers the reduction to a single
+//! [`Fp::reduce_u62`] per element.
+//!
+//! The round constants are generated at compile time with the Grain LFSR of
+//! the Poseidon reference implementation. Plonky3 samples the constants of its
+//! Mersenne-31 instances with a seeded RNG instead, so the permutations
+//! differ.
+
+use super::{Fp, MODULUS};
+use ark_ff::Field;
+
+/// Number of full rounds before and after the partial rounds
+const HALF_FULL_ROUNDS: usize = 4;
+
+const PARTIAL_ROUNDS_16: usize = 14;
+
+const PARTIAL_ROUNDS_24: usize = 22;
+
+/// Number of elements in a digest, which is also the capacity of the sponge
+pub const DIGEST_LEN: usize = 8;
+
+/// A sponge or compression output
+pub type Digest = [Fp; DIGEST_LEN];
+
+/// The round constants and internal diagonal of a Poseidon2 instance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Poseidon2<const WIDTH: usize> {
+    initial_constants: [[Fp; WIDTH]; HALF_FULL_ROUNDS],
+    internal_constants: &'static [Fp],
+    terminal_constants: [[Fp; WIDTH]; HALF_FULL_ROUNDS],
+    /// `d_i = 2^internal_shifts[i]` for `i > 0`, the first entry is unused
+    /// since `d_0 = -2`
+    internal_shifts: [u32; WIDTH],
+}
+
+const CONSTANTS_16: [Fp; 2 * HALF_FULL_ROUNDS * 16 + PARTIAL_ROUNDS_16] =
+    Grain::new(16, PARTIAL_ROUNDS_16).round_constants();
+
+const CONSTANTS_24: [Fp; 2 * HALF_FULL_ROUNDS * 24 + PARTIAL_ROUNDS_24] =
+    Grain::new(24, PARTIAL_ROUNDS_24).round_constants();
+
+impl Poseidon2<16> {
+    /// Returns the width 16 instance.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::from_constants(
+            &CONSTANTS_16,
+            [0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 12, 13, 14, 15, 16],
+        )
+    }
+}
+
+impl Default for Poseidon2<16> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Poseidon2<24> {
+    /// Returns the width 24 instance.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::from_constants(
+            &CONSTANTS_24,
+            [
+                0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22,
+            ],
+        )
+    }
+}
+
+impl Default for Poseidon2<24> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WIDTH: usize> Poseidon2<WIDTH> {
+    /// Splits the constants in the order generated by [`Grain`]: the initial
+    /// full rounds, the partial rounds and the terminal full rounds.
+    const fn from_constants(constants: &'static [Fp], internal_shifts: [u32; WIDTH]) -> Self {
+        let (initial, rest) = constants.split_at(HALF_FULL_ROUNDS * WIDTH);
+        let (internal, terminal) = rest.split_at(rest.len() - HALF_FULL_ROUNDS * WIDTH);
+        Self {
+            initial_constants: to_rows(initial),
+            internal_constants: internal,
+            terminal_constants: to_rows(terminal),
+            internal_shifts,
+        }
+    }
+
+    /// Applies the permutation to `state` in place.
+    pub fn permute(&self, state: &mut [Fp; WIDTH]) {
+        external_linear_layer(state);
+        for constants in &self.initial_constants {
+            full_round(state, constants);
+        }
+        for constant in self.internal_constants {
+            state[0] = sbox(state[0] + constant);
+            internal_linear_layer(state, &self.internal_shifts);
+        }
+        for constants in &self.terminal_constants {
+            full_round(state, constants);
+        }
+    }
+
+    /// Hashes `elements` with a [`Poseidon2Sponge`] using this permutation.
+    #[must_use]
+    pub fn hash(&self, elements: &[Fp]) -> Digest {
+        let mut sponge = Poseidon2Sponge::new(self.clone());
+        sponge.absorb(elements);
+        sponge.finalize()
+    }
+
+    /// Compresses two digests into the first [`DIGEST_LEN`] elements of the
+    /// permuted `left || right`, padded with zeros for width 24.
+    #[must_use]
+    pub fn compress(&self, left: &Digest, right: &Digest) -> Digest {
+        crate::compression::truncated_permutation(|state| self.permute(state), left, right)
+    }
+}
+
+/// A sponge over [`Poseidon2`] with capacity [`DIGEST_LEN`] and rate
+/// `WIDTH - DIGEST_LEN`.
+///
+/// Elements are added to the rate portion at the start of the state, which is
+/// permuted whenever it is full. The input is padded with a single one
+/// followed by zeros so inputs of different lengths are absorbed differently,
+/// even if one of them ends in zeros. The digest is the first [`DIGEST_LEN`]
+/// elements of the final state.
+#[derive(Clone, Debug)]
+pub struct Poseidon2Sponge<const WIDTH: usize> {
+    permutation: Poseidon2<WIDTH>,
+    state: [Fp; WIDTH],
+    /// Number of elements absorbed into the rate since the last permutation
+    position: usize,
+}
+
+impl<const WIDTH: usize> Poseidon2Sponge<WIDTH> {
+    const RATE: usize = WIDTH - DIGEST_LEN;
+
+    /// Creates a sponge with an all zero state.
+    #[must_use]
+    pub const fn new(permutation: Poseidon2<WIDTH>) -> Self {
+        Self {
+            permutation,
+            state: [Fp::ZERO; WIDTH],
+            position: 0,
+        }
+    }
+
+    /// Absorbs `elements`. Absorbing in several calls is the same as
+    /// absorbing the concatenation at once.
+    pub fn absorb(&mut self, elements: &[Fp]) {
+        for element in elements {
+            if self.position == Self::RATE {
+                self.permutation.permute(&mut self.state);
+                self.position = 0;
+            }
+            self.state[self.position] += element;
+            self.position += 1;
+        }
+    }
+
+    /// Pads the input and returns the digest.
+    #[must_use]
+    pub fn finalize(mut self) -> Digest {
+        self.absorb(&[Fp::ONE]);
+        self.permutation.permute(&mut self.state);
+        let mut digest = [Fp::ZERO; DIGEST_LEN];
+        digest.copy_from_slice(&self.state[..DIGEST_LEN]);
+        digest
+    }
+}
+
+/// Adds the round constants, applies the S-box to every element and then the
+/// external linear layer.
+#[inline]
+fn full_round<const WIDTH: usize>(state: &mut [Fp; WIDTH], constants: &[Fp; WIDTH]) {
+    for (value, constant) in state.iter_mut().zip(constants) {
+        *value = sbox(*value + constant);
+    }
+    external_linear_layer(state);
+}
+
+/// Computes `x^5 = x^4 * x`.
+#[inline]
+fn sbox(x: Fp) -> Fp {
+    x.square().square() * x
+}
+
+/// Multiplies by the 4x4 MDS matrix with 9 additions and 2 doublings.
+#[inline]
+#[allow(clippy::similar_names)]
+fn mds_4(x: &mut [Fp]) {
+    let t01 = x[0] + x[1];
+    let t23 = x[2] + x[3];
+    let t0123 = t01 + t23;
+    let t01123 = t0123 + x[1];
+    let t01233 = t0123 + x[3];
+    // `x[1]` and `x[3]` are overwritten before `x[0]` and `x[2]`
+    x[3] = t01233 + x[0].double();
+    x[1] = t01123 + x[2].double();
+    x[0] = t01123 + t01;
+    x[2] = t01233 + t23;
+}
+
+/// Multiplies by `circ(2*M4, M4, ..., M4)`. After applying `M4` to every
+/// chunk of 4, element `i` is doubled and the elements of the other chunks at
+/// the same offset are added, so the column sums are shared.
+#[inline]
+fn external_linear_layer<const WIDTH: usize>(state: &mut [Fp; WIDTH]) {
+    for chunk in state.chunks_exact_mut(4) {
+        mds_4(chunk);
+    }
+    let mut sums = [Fp::ZERO; 4];
+    for chunk in state.chunks_exact(4) {
+        for (sum, value) in sums.iter_mut().zip(chunk) {
+            *sum += value;
+        }
+    }
+    for chunk in state.chunks_exact_mut(4) {
+        for (value, sum) in chunk.iter_mut().zip(&sums) {
+            *value += sum;
+        }
+    }
+}
+
+/// Multiplies by `1 + diag(d)` as `x_i * d_i + sum(x)`.
+///
+/// Elements are at most `p < 2^31` so the sum of at most 24 elements is below
+/// `2^36`, `-2 * x_0` is computed as `2 * (p - x_0) < 2^32` and the shifts are
+/// at most 22. Every result is below `2^54` before its reduction.
+#[inline]
+fn internal_linear_layer<const WIDTH: usize>(state: &mut [Fp; WIDTH], shifts: &[u32; WIDTH]) {
+    let sum: u64 = state.iter().map(|x| u64::from(x.0)).sum();
+    state[0] = Fp::reduce_u62(sum + (u64::from(MODULUS - state[0].0) << 1));
+    for (value, shift) in state.iter_mut().zip(shifts).skip(1) {
+        *value = Fp::reduce_u62(sum + (u64::from(value.0) << shift));
+    }
+}
+
+const fn to_rows<const WIDTH: usize>(constants: &[Fp]) -> [[Fp; WIDTH]; HALF_FULL_ROUNDS] {
+    let mut rows = [[Fp::ZERO; WIDTH]; HALF_FULL_ROUNDS];
+    let mut i = 0;
+    while i < HALF_FULL_ROUNDS * WIDTH {
+        rows[i / WIDTH][i % WIDTH] = constants[i];
+        i += 1;
+    }
+    rows
+}
+
+/// The Grain LFSR from the Poseidon reference implementation used to generate
+/// round constants.
+///
+/// The 80-bit state is initialised with the parameters and 30 ones, and the
+/// first 160 output bits are discarded. Output bits are taken in pairs and the
+/// second bit is kept only if the first one is set. Field elements are read as
+/// 31-bit big-endian integers and rejected if they are not below `p`.
+struct Grain {
+    /// Bit `i` is the `i`-th oldest bit of the register
+    state: u128,
+}
+
+impl Grain {
+    const fn new(width: usize, partial_rounds: usize) -> Self {
+        // field type 1 (prime field), S-box type 0 (x^alpha), field size,
+        // width, full rounds and partial rounds
+        let parameters = [
+            (1, 2),
+            (0, 4),
+            (31, 12),
+            (width, 12),
+            (2 * HALF_FULL_ROUNDS, 10),
+            (partial_rounds, 10),
+        ];
+        let mut grain = Self { state: 0 };
+        let mut position = 0;
+        let mut i = 0;
+        while i < parameters.len() {
+            let (value, num_bits) = parameters[i];
+            let mut bit = num_bits;
+            while bit > 0 {
+                bit -= 1;
+                grain.state |= ((value >> bit) as u128 & 1) << position;
+                position += 1;
+            }
+            i += 1;
+        }
+        grain.state |= ((1 << 30) - 1) << position;
+        let mut i = 0;
+        while i < 160 {
+            grain.step();
+            i += 1;
+        }
+        grain
+    }
+
+    /// Shifts in the XOR of the taps and returns it.
+    const fn step(&mut self) -> u32 {
+        let s = self.state;
+        let bit = ((s >> 62) ^ (s >> 51) ^ (s >> 38) ^ (s >> 23) ^ (s >> 13) ^ s) & 1;
+        self.state = (s >> 1) | (bit << 79);
+        bit as u32
+    }
+
+    const fn next_bit(&mut self) -> u32 {
+        loop {
+            if self.step() == 1 {
+                return self.step();
+            }
+            self.step();
+        }
+    }
+
+    const fn next_element(&mut self) -> Fp {
+        loop {
+            let mut value = 0;
+            let mut i = 0;
+            while i < 31 {
+                value = (value << 1) | self.next_bit();
+                i += 1;
+            }
+            if value < MODULUS {
+                return Fp(value);
+            }
+        }
+    }
+
+    const fn round_constants<const N: usize>(mut self) -> [Fp; N] {
+        let mut constants = [Fp::ZERO; N];
+        let mut i = 0;
+        while i < N {
+            constants[i] = self.next_element();
+            i += 1;
+        }
+        constants
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        external_linear_layer, internal_linear_layer, Digest, Poseidon2, Poseidon2Sponge,
+        DIGEST_LEN, MODULUS,
+    };
+    use crate::fp31::Fp;
+    use ark_ff::{Field, PrimeField};
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    fn to_u32s(values: &[Fp]) -> Vec<u32> {
+        values
+            .iter()
+            .map(|v| u32::try_from(v.into_bigint().0[0]).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn generated_constants_match_published() {
+        let permutation = Poseidon2::<16>::new();
+        assert_eq!(
+            to_u32s(&permutation.initial_constants[0][..4]),
+            [0x768b_ab52, 0x70e0_ab7d, 0x3d26_6c8a, 0x6da4_2045]
+        );
+        assert_eq!(
+            to_u32s(permutation.internal_constants),
+            [
+                0x7f7e_c4bf,
+                0x0421_926f,
+                0x5198_e669,
+                0x34db_3148,
+                0x4368_bafd,
+                0x6668_5c7f,
+                0x78d3_249a,
+                0x6018_7881,
+                0x76da_d67a,
+                0x0690_b437,
+                0x1ea9_5311,
+                0x40e5_369a,
+                0x38f1_03fc,
+                0x1d22_6a21,
+            ]
+        );
+        assert_eq!(
+            to_u32s(&permutation.terminal_constants[3][12..]),
+            [0x4f5f_3c70, 0x0245_af6c, 0x2383_59d3, 0x4996_6a59]
+        );
+
+        let permutation = Poseidon2::<24>::new();
+        assert_eq!(
+            to_u32s(&permutation.initial_constants[0][..2]),
+            [0x1fea_ba61, 0x5322_4454]
+        );
+        assert_eq!(permutation.internal_constants.len(), 22);
+    }
+
+    /// Regression vector on `[0, 1, ..., 15]`
+    #[test]
+    fn width_16_known_answer() {
+        let mut state: [Fp; 16] = core::array::from_fn(|i| Fp::from(i as u64));
+        Poseidon2::<16>::new().permute(&mut state);
+        assert_eq!(
+            to_u32s(&state),
+            [
+                0x0b2c_803a,
+                0x5b1e_e4d1,
+                0x49c6_b1e3,
+                0x2cdc_280c,
+                0x310a_60c8,
+                0x530a_729e,
+                0x4e61_bcb4,
+                0x2e84_d3c3,
+                0x5870_9c08,
+                0x7e82_ac42,
+                0x2162_bcef,
+                0x6d15_3ab6,
+                0x742c_f0e3,
+                0x2f21_632d,
+                0x61ad_ce1e,
+                0x1973_d6f1,
+            ]
+        );
+    }
+
+    /// Regression vector on `[0, 1, ..., 23]`
+    #[test]
+    fn width_24_known_answer() {
+        let mut state: [Fp; 24] = core::array::from_fn(|i| Fp::from(i as u64));
+        Poseidon2::<24>::new().permute(&mut state);
+        assert_eq!(
+            to_u32s(&state),
+            [
+                0x2040_f051,
+                0x7261_dbfa,
+                0x4fbd_519e,
+                0x2320_ecaf,
+                0x039e_f27c,
+                0x48d6_0ad5,
+                0x73ca_17ff,
+                0x6023_111a,
+                0x6c5e_31e7,
+                0x373c_d90d,
+                0x75a3_ae11,
+                0x00ec_c878,
+                0x33a7_c097,
+                0x244c_2171,
+                0x7552_a38e,
+                0x58d2_0817,
+                0x00fe_ecb7,
+                0x47c4_3c88,
+                0x30d3_001c,
+                0x24d0_9ba6,
+                0x71f2_41d9,
+                0x1c72_ab2e,
+                0x4749_f79d,
+                0x61ff_7579,
+            ]
+        );
+    }
+
+    fn matrix_product<const WIDTH: usize>(
+        matrix: impl Fn(usize, usize) -> Fp,
+        state: &[Fp; WIDTH],
+    ) -> [Fp; WIDTH] {
+        core::array::from_fn(|i| (0..WIDTH).map(|j| matrix(i, j) * state[j]).sum())
+    }
+
+    #[test]
+    fn linear_layers_match_matrices() {
+        const M4: [[u64; 4]; 4] = [[2, 3, 1, 1], [1, 2, 3, 1], [1, 1, 2, 3], [3, 1, 1, 2]];
+        let mut rng = test_rng();
+        let mut state: [Fp; 24] = core::array::from_fn(|_| Fp::rand(&mut rng));
+        // both encodings of zero and the largest values
+        state[0] = Fp(MODULUS);
+        state[1] = Fp(0);
+        state[23] = Fp(MODULUS);
+        state[22] = Fp(MODULUS - 1);
+
+        let external = |i: usize, j: usize| {
+            let entry = Fp::from(M4[i % 4][j % 4]);
+            if i / 4 == j / 4 {
+                entry.double()
+            } else {
+                entry
+            }
+        };
+        let mut actual = state;
+        external_linear_layer(&mut actual);
+        assert_eq!(actual, matrix_product(external, &state));
+
+        let shifts = Poseidon2::<24>::new().internal_shifts;
+        let diag = |i: usize| {
+            if i == 0 {
+                -Fp::from(2u8)
+            } else {
+                Fp::from(2u8).pow([u64::from(shifts[i])])
+            }
+        };
+        let internal = |i: usize, j: usize| if i == j { Fp::ONE + diag(i) } else { Fp::ONE };
+        let mut actual = state;
+        internal_linear_layer(&mut actual, &shifts);
+        assert!(actual.iter().all(|x| x.0 <= MODULUS));
+        assert_eq!(actual, matrix_product(internal, &state));
+    }
+
+    #[test]
+    fn sponge_absorbs_incrementally() {
+        let mut rng = test_rng();
+        let elements: Vec<Fp> = (0..40).map(|_| Fp::rand(&mut rng)).collect();
+        for len in 0..elements.len() {
+            let expected = Poseidon2::<16>::new().hash(&elements[..len]);
+            for split in 0..=len {
+                let mut sponge = Poseidon2Sponge::new(Poseidon2::<16>::new());
+                sponge.absorb(&elements[..split]);
+                sponge.absorb(&elements[split..len]);
+                assert_eq!(sponge.finalize(), expected, "len = {len}, split = {split}");
+            }
+        }
+    }
+
+    #[test]
+    fn sponge_pads_input() {
+        let permutation = Poseidon2::<24>::new();
+        let mut state = [Fp::ZERO; 24];
+        state[0] = Fp::ONE;
+        permutation.permute(&mut state);
+        let expected: Digest = state[..DIGEST_LEN].try_into().unwrap();
+        assert_eq!(permutation.hash(&[]), expected);
+
+        // trailing zeros and block boundaries give distinct digests
+        let digests: Vec<Digest> = (0..34)
+            .map(|len| permutation.hash(&ark_std::vec![Fp::ZERO; len]))
+            .collect();
+        for (i, a) in digests.iter().enumerate() {
+            assert!(digests[i + 1..].iter().all(|b| a != b));
+        }
+    }
+}
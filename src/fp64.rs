@@ -13,6 +13,17 @@
 use ark_ff::{fields::Fp64, BigInt, PrimeField, SqrtPrecomputation, Zero};
 use core::marker::PhantomData;
 
+pub mod ext2;
+pub mod ext3;
+pub mod ext5;
+pub mod fri;
+pub mod monolith;
+pub mod ntt;
+pub mod packed;
+pub mod poseidon2;
+pub mod rpo;
+pub mod tip5;
+
 /// Field modulus `p = 2^64 - 2^32 + 1`
 const MODULUS: u64 = 18_446_744_069_414_584_321;
 
@@ -27,6 +38,12 @@ impl ark_ff::FpConfig<1> for FpParams {
     const ONE: Fp64<Self> = into_mont(1);
     const TWO_ADICITY: u32 = 32;
     const TWO_ADIC_ROOT_OF_UNITY: Fp64<Self> = into_mont(1_753_635_133_440_165_772);
+    // `p - 1 = 2^32 * 3 * 5 * 17 * 257 * 65537`. All roots of unity are powers of
+    // the generator so the cube of this root is `TWO_ADIC_ROOT_OF_UNITY`.
+    const SMALL_SUBGROUP_BASE: Option<u32> = Some(3);
+    const SMALL_SUBGROUP_BASE_ADICITY: Option<u32> = Some(1);
+    const LARGE_SUBGROUP_ROOT_OF_UNITY: Option<Fp64<Self>> =
+        Some(into_mont(14_159_254_819_154_955_796));
     const SQRT_PRECOMP: Option<ark_ff::SqrtPrecomputation<Fp64<Self>>> =
         Some(SqrtPrecomputation::TonelliShanks {
             two_adicity: Self::TWO_ADICITY,
@@ -108,9 +125,43 @@ impl ark_ff::FpConfig<1> for FpParams {
 /// 2^32 + 1`
 pub type Fp = Fp64<FpParams>;
 
+/// Replaces every non-zero element of `values` with its inverse using
+/// Montgomery's trick, which needs a single `exp_acc` inversion for the whole
+/// slice. Zeros are left unchanged.
+pub fn batch_inverse(values: &mut [Fp]) {
+    crate::inversion::batch_inverse(values);
+}
+
+/// Writes the inverse of every element of `values` to `out`, or zero for zero
+/// elements, using a single inversion.
+///
+/// # Panics
+///
+/// Panics if `values` and `out` have different lengths.
+pub fn batch_inverse_into(values: &[Fp], out: &mut [Fp]) {
+    crate::inversion::batch_inverse_into(values, out);
+}
+
+/// [`batch_inverse`] on chunks of `values` in parallel with rayon. Each chunk
+/// costs one inversion.
+#[cfg(feature = "parallel")]
+pub fn par_batch_inverse(values: &mut [Fp]) {
+    crate::inversion::par_batch_inverse(values);
+}
+
+/// [`batch_inverse_into`] on chunks of `values` in parallel with rayon.
+///
+/// # Panics
+///
+/// Panics if `values` and `out` have different lengths.
+#[cfg(feature = "parallel")]
+pub fn par_batch_inverse_into(values: &[Fp], out: &mut [Fp]) {
+    crate::inversion::par_batch_inverse_into(values, out);
+}
+
 /// Converts a value into Montgomery representation
 #[inline]
-const fn into_mont(value: u64) -> Fp {
+pub(crate) const fn into_mont(value: u64) -> Fp {
     ark_ff::Fp(BigInt([mont_red(value as u128 * R2 as u128)]), PhantomData)
 }
 
@@ -127,6 +178,21 @@ const fn mont_red(x: u128) -> u64 {
     r.wrapping_sub(0u32.wrapping_sub(underflow as u32) as u64)
 }
 
+/// Reduces `x < 2^96` modulo `p` using `2^64 = 2^32 - 1 mod p`.
+#[inline]
+const fn reduce_u96(x: u128) -> u64 {
+    #[allow(clippy::cast_possible_truncation)]
+    let (lo, hi) = (x as u64, (x >> 64) as u64);
+    let (res, overflow) = lo.overflowing_add((hi << 32) - hi);
+    // `res` is small after an overflow so adding `2^32 - 1` can't overflow
+    let res = res.wrapping_add(0u32.wrapping_sub(overflow as u32) as u64);
+    if res >= MODULUS {
+        res - MODULUS
+    } else {
+        res
+    }
+}
+
 /// Squares `base` N times and multiplies the result by the tail value.
 #[inline]
 const fn exp_acc<const N: usize>(base: u64, tail: u64) -> u64 {
@@ -0,0 +1,732 @@
+//! An implementation of the quadratic extension of the 64-bit STARK-friendly
+//! prime field `F_p[u]/(u^2 - 7)` where `p = 2^64 - 2^32 + 1`.
+//!
+//! This is the extension used for drawing challenges in Plonky2-style
+//! recursion. Since `7` is a quadratic non-residue modulo `p`:
+//! * Multiplication by the non-residue is a handful of additions.
+//! * The Frobenius map `x -> x^p` is the conjugation `c0 + c1*u -> c0 - c1*u`.
+//! * `p^2 - 1` is divisible by `2^33` i.e. the extension has one more bit of
+//!   2-adicity than the base field.
+
+use super::{into_mont, Fp};
+use ark_ff::{FftField, Field, LegendreSymbol, One, SqrtPrecomputation, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
+    CanonicalSerializeWithFlags, Compress, EmptyFlags, Flags, SerializationError, Valid, Validate,
+};
+use core::{
+    fmt::{Display, Formatter},
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// `1/2 = (p + 1)/2`
+const TWO_INV: Fp = into_mont(9_223_372_034_707_292_161);
+
+/// `1/7` in the base field
+const SEVEN_INV: Fp = into_mont(2_635_249_152_773_512_046);
+
+/// Multiplies a base field element by the non-residue `7` as `8a - a`.
+#[inline]
+fn mul_by_nonresidue(a: Fp) -> Fp {
+    a.double().double().double() - a
+}
+
+/// An element `c0 + c1 * u` of the quadratic extension of the 64-bit field
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+pub struct Fp2 {
+    pub c0: Fp,
+    pub c1: Fp,
+}
+
+impl Fp2 {
+    /// Creates the element `c0 + c1 * u`.
+    #[must_use]
+    pub const fn new(c0: Fp, c1: Fp) -> Self {
+        Self { c0, c1 }
+    }
+
+    /// Returns the conjugate `c0 - c1 * u`.
+    #[must_use]
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0, -self.c1)
+    }
+
+    /// Replaces `self` with its conjugate `c0 - c1 * u`.
+    pub fn conjugate_in_place(&mut self) -> &mut Self {
+        self.c1.neg_in_place();
+        self
+    }
+
+    /// Returns the norm `c0^2 - 7 * c1^2` which is the product of an element
+    /// and its conjugate.
+    #[must_use]
+    pub fn norm(&self) -> Fp {
+        self.c0.square() - mul_by_nonresidue(self.c1.square())
+    }
+
+    /// Multiplies `self` by an element of the base field.
+    pub fn mul_assign_by_base_field(&mut self, element: &Fp) {
+        self.c0 *= element;
+        self.c1 *= element;
+    }
+}
+
+impl Field for Fp2 {
+    type BasePrimeField = Fp;
+    type BasePrimeFieldIter = core::array::IntoIter<Fp, 2>;
+
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<Self>> = None;
+
+    const ZERO: Self = Self::new(Fp::ZERO, Fp::ZERO);
+
+    const ONE: Self = Self::new(Fp::ONE, Fp::ZERO);
+
+    fn extension_degree() -> u64 {
+        2
+    }
+
+    fn from_base_prime_field(elem: Self::BasePrimeField) -> Self {
+        Self::new(elem, Fp::ZERO)
+    }
+
+    fn to_base_prime_field_elements(&self) -> Self::BasePrimeFieldIter {
+        [self.c0, self.c1].into_iter()
+    }
+
+    fn from_base_prime_field_elems(elems: &[Self::BasePrimeField]) -> Option<Self> {
+        if elems.len() != usize::try_from(Self::extension_degree()).unwrap() {
+            return None;
+        }
+        Some(Self::new(elems[0], elems[1]))
+    }
+
+    #[inline]
+    fn double(&self) -> Self {
+        let mut temp = *self;
+        temp.double_in_place();
+        temp
+    }
+
+    #[inline]
+    fn double_in_place(&mut self) -> &mut Self {
+        self.c0.double_in_place();
+        self.c1.double_in_place();
+        self
+    }
+
+    #[inline]
+    fn neg_in_place(&mut self) -> &mut Self {
+        self.c0.neg_in_place();
+        self.c1.neg_in_place();
+        self
+    }
+
+    #[inline]
+    fn from_random_bytes_with_flags<F: Flags>(bytes: &[u8]) -> Option<(Self, F)> {
+        let split_at = bytes.len() / 2;
+        let c0 = Fp::from_random_bytes(&bytes[..split_at])?;
+        let (c1, flags) = Fp::from_random_bytes_with_flags(&bytes[split_at..])?;
+        Some((Self::new(c0, c1), flags))
+    }
+
+    #[inline]
+    fn square(&self) -> Self {
+        let mut temp = *self;
+        temp.square_in_place();
+        temp
+    }
+
+    /// Complex squaring: with `v0 = c0 * c1` we have `(c0 + c1*u)^2 =
+    /// ((c0 + c1)(c0 + 7*c1) - 8*v0) + 2*v0*u`.
+    #[inline]
+    fn square_in_place(&mut self) -> &mut Self {
+        let v0 = self.c0 * self.c1;
+        self.c0 = (self.c0 + self.c1) * (self.c0 + mul_by_nonresidue(self.c1))
+            - v0.double().double().double();
+        self.c1 = v0.double();
+        self
+    }
+
+    #[inline]
+    fn inverse(&self) -> Option<Self> {
+        // 1/(c0 + c1*u) = (c0 - c1*u)/(c0^2 - 7*c1^2)
+        self.norm().inverse().map(|norm_inv| {
+            let mut res = self.conjugate();
+            res.mul_assign_by_base_field(&norm_inv);
+            res
+        })
+    }
+
+    fn inverse_in_place(&mut self) -> Option<&mut Self> {
+        self.inverse().map(|inverse| {
+            *self = inverse;
+            self
+        })
+    }
+
+    /// Since `u^p = 7^((p-1)/2) * u = -u` the Frobenius map is the identity for
+    /// even powers and conjugation for odd powers.
+    #[inline]
+    fn frobenius_map_in_place(&mut self, power: usize) {
+        if power % 2 == 1 {
+            self.conjugate_in_place();
+        }
+    }
+
+    /// An element is a square in the extension iff its norm is a square in
+    /// the base field.
+    #[inline]
+    fn legendre(&self) -> LegendreSymbol {
+        self.norm().legendre()
+    }
+
+    /// Square root based on the complex method. See Algorithm 8 in
+    /// <https://eprint.iacr.org/2012/685.pdf>.
+    fn sqrt(&self) -> Option<Self> {
+        if self.c1.is_zero() {
+            // `7` is a non-residue so either `c0` or `c0/7` has a root in `Fp`.
+            return self.c0.sqrt().map_or_else(
+                || {
+                    (self.c0 * SEVEN_INV)
+                        .sqrt()
+                        .map(|c1| Self::new(Fp::ZERO, c1))
+                },
+                |c0| Some(Self::new(c0, Fp::ZERO)),
+            );
+        }
+        let alpha = self.norm().sqrt()?;
+        let mut delta = (alpha + self.c0) * TWO_INV;
+        if delta.legendre().is_qnr() {
+            delta -= &alpha;
+        }
+        let c0 = delta.sqrt()?;
+        let c1 = self.c1 * c0.double().inverse()?;
+        Some(Self::new(c0, c1))
+    }
+}
+
+impl FftField for Fp2 {
+    const GENERATOR: Self = Self::new(into_mont(11), into_mont(1));
+    const TWO_ADICITY: u32 = 33;
+    const TWO_ADIC_ROOT_OF_UNITY: Self =
+        Self::new(into_mont(0), into_mont(7_324_632_090_278_461_921));
+    const SMALL_SUBGROUP_BASE: Option<u32> = None;
+    const SMALL_SUBGROUP_BASE_ADICITY: Option<u32> = None;
+    const LARGE_SUBGROUP_ROOT_OF_UNITY: Option<Self> = None;
+}
+
+impl zeroize::Zeroize for Fp2 {
+    fn zeroize(&mut self) {
+        self.c0.zeroize();
+        self.c1.zeroize();
+    }
+}
+
+impl Zero for Fp2 {
+    #[inline]
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+}
+
+impl One for Fp2 {
+    #[inline]
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    #[inline]
+    fn is_one(&self) -> bool {
+        self.c0.is_one() && self.c1.is_zero()
+    }
+}
+
+/// `Fp2` elements are ordered lexicographically.
+impl Ord for Fp2 {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.c1.cmp(&other.c1).then_with(|| self.c0.cmp(&other.c0))
+    }
+}
+
+impl PartialOrd for Fp2 {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<Fp> for Fp2 {
+    fn from(other: Fp) -> Self {
+        Self::from_base_prime_field(other)
+    }
+}
+
+impl From<u128> for Fp2 {
+    fn from(other: u128) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i128> for Fp2 {
+    fn from(other: i128) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<bool> for Fp2 {
+    fn from(other: bool) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u64> for Fp2 {
+    fn from(other: u64) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i64> for Fp2 {
+    fn from(other: i64) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u32> for Fp2 {
+    fn from(other: u32) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i32> for Fp2 {
+    fn from(other: i32) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u16> for Fp2 {
+    fn from(other: u16) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i16> for Fp2 {
+    fn from(other: i16) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u8> for Fp2 {
+    fn from(other: u8) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i8> for Fp2 {
+    fn from(other: i8) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl ark_std::rand::distributions::Distribution<Fp2> for ark_std::rand::distributions::Standard {
+    #[inline]
+    fn sample<R: ark_std::rand::Rng + ?Sized>(&self, rng: &mut R) -> Fp2 {
+        Fp2::new(rng.gen(), rng.gen())
+    }
+}
+
+impl CanonicalSerializeWithFlags for Fp2 {
+    fn serialize_with_flags<W: ark_std::io::Write, F: Flags>(
+        &self,
+        mut writer: W,
+        flags: F,
+    ) -> Result<(), SerializationError> {
+        self.c0.serialize_compressed(&mut writer)?;
+        self.c1.serialize_with_flags(&mut writer, flags)?;
+        Ok(())
+    }
+
+    fn serialized_size_with_flags<F: Flags>(&self) -> usize {
+        self.c0.compressed_size() + self.c1.serialized_size_with_flags::<F>()
+    }
+}
+
+impl CanonicalSerialize for Fp2 {
+    #[inline]
+    fn serialize_with_mode<W: ark_std::io::Write>(
+        &self,
+        writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.serialize_with_flags(writer, EmptyFlags)
+    }
+
+    #[inline]
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        self.serialized_size_with_flags::<EmptyFlags>()
+    }
+}
+
+impl CanonicalDeserializeWithFlags for Fp2 {
+    fn deserialize_with_flags<R: ark_std::io::Read, F: Flags>(
+        mut reader: R,
+    ) -> Result<(Self, F), SerializationError> {
+        let c0 = Fp::deserialize_compressed(&mut reader)?;
+        let (c1, flags) = Fp::deserialize_with_flags(&mut reader)?;
+        Ok((Self::new(c0, c1), flags))
+    }
+}
+
+impl Valid for Fp2 {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.c0.check()?;
+        self.c1.check()
+    }
+}
+
+impl CanonicalDeserialize for Fp2 {
+    fn deserialize_with_mode<R: ark_std::io::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let c0 = Fp::deserialize_with_mode(&mut reader, compress, validate)?;
+        let c1 = Fp::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self::new(c0, c1))
+    }
+}
+
+/// Outputs a string of the form `c0 + c1 * u`.
+impl Display for Fp2 {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} + {} * u", self.c0, self.c1)
+    }
+}
+
+impl Neg for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(mut self) -> Self {
+        self.neg_in_place();
+        self
+    }
+}
+
+impl AddAssign<&Self> for Fp2 {
+    #[inline]
+    fn add_assign(&mut self, other: &Self) {
+        self.c0 += other.c0;
+        self.c1 += other.c1;
+    }
+}
+
+impl SubAssign<&Self> for Fp2 {
+    #[inline]
+    fn sub_assign(&mut self, other: &Self) {
+        self.c0 -= other.c0;
+        self.c1 -= other.c1;
+    }
+}
+
+impl MulAssign<&Self> for Fp2 {
+    /// Karatsuba multiplication using the fact that `u^2 = 7`.
+    #[inline]
+    fn mul_assign(&mut self, other: &Self) {
+        let v0 = self.c0 * other.c0;
+        let v1 = self.c1 * other.c1;
+        self.c1 = (self.c0 + self.c1) * (other.c0 + other.c1) - v0 - v1;
+        self.c0 = v0 + mul_by_nonresidue(v1);
+    }
+}
+
+/// Computes `self *= other.inverse()` if `other.inverse()` is `Some`, and
+/// panics otherwise.
+impl DivAssign<&Self> for Fp2 {
+    #[inline]
+    fn div_assign(&mut self, other: &Self) {
+        self.mul_assign(&other.inverse().unwrap());
+    }
+}
+
+impl Add<&Self> for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: &Self) -> Self {
+        self.add_assign(other);
+        self
+    }
+}
+
+impl Sub<&Self> for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: &Self) -> Self {
+        self.sub_assign(other);
+        self
+    }
+}
+
+impl Mul<&Self> for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: &Self) -> Self {
+        self.mul_assign(other);
+        self
+    }
+}
+
+impl Div<&Self> for Fp2 {
+    type Output = Self;
+
+    /// Returns `self * other.inverse()` if `other.inverse()` is `Some`, and
+    /// panics otherwise.
+    #[inline]
+    fn div(mut self, other: &Self) -> Self {
+        self.div_assign(other);
+        self
+    }
+}
+
+impl Add<&Fp2> for &Fp2 {
+    type Output = Fp2;
+
+    #[inline]
+    fn add(self, other: &Fp2) -> Fp2 {
+        *self + other
+    }
+}
+
+impl Sub<&Fp2> for &Fp2 {
+    type Output = Fp2;
+
+    #[inline]
+    fn sub(self, other: &Fp2) -> Fp2 {
+        *self - other
+    }
+}
+
+impl Mul<&Fp2> for &Fp2 {
+    type Output = Fp2;
+
+    #[inline]
+    fn mul(self, other: &Fp2) -> Fp2 {
+        *self * other
+    }
+}
+
+impl Div<&Fp2> for &Fp2 {
+    type Output = Fp2;
+
+    #[inline]
+    fn div(self, other: &Fp2) -> Fp2 {
+        *self / other
+    }
+}
+
+impl AddAssign<&mut Self> for Fp2 {
+    #[inline]
+    fn add_assign(&mut self, other: &mut Self) {
+        self.add_assign(&*other);
+    }
+}
+
+impl SubAssign<&mut Self> for Fp2 {
+    #[inline]
+    fn sub_assign(&mut self, other: &mut Self) {
+        self.sub_assign(&*other);
+    }
+}
+
+impl MulAssign<&mut Self> for Fp2 {
+    #[inline]
+    fn mul_assign(&mut self, other: &mut Self) {
+        self.mul_assign(&*other);
+    }
+}
+
+impl DivAssign<&mut Self> for Fp2 {
+    #[inline]
+    fn div_assign(&mut self, other: &mut Self) {
+        self.div_assign(&*other);
+    }
+}
+
+impl AddAssign<Self> for Fp2 {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.add_assign(&other);
+    }
+}
+
+impl SubAssign<Self> for Fp2 {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.sub_assign(&other);
+    }
+}
+
+impl MulAssign<Self> for Fp2 {
+    #[inline]
+    fn mul_assign(&mut self, other: Self) {
+        self.mul_assign(&other);
+    }
+}
+
+impl DivAssign<Self> for Fp2 {
+    #[inline]
+    fn div_assign(&mut self, other: Self) {
+        self.div_assign(&other);
+    }
+}
+
+impl Add<Self> for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: Self) -> Self {
+        self.add_assign(&other);
+        self
+    }
+}
+
+impl Sub<Self> for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: Self) -> Self {
+        self.sub_assign(&other);
+        self
+    }
+}
+
+impl Mul<Self> for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: Self) -> Self {
+        self.mul_assign(&other);
+        self
+    }
+}
+
+impl Div<Self> for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn div(mut self, other: Self) -> Self {
+        self.div_assign(&other);
+        self
+    }
+}
+
+impl Add<&mut Self> for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: &mut Self) -> Self {
+        self.add_assign(&*other);
+        self
+    }
+}
+
+impl Sub<&mut Self> for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: &mut Self) -> Self {
+        self.sub_assign(&*other);
+        self
+    }
+}
+
+impl Mul<&mut Self> for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: &mut Self) -> Self {
+        self.mul_assign(&*other);
+        self
+    }
+}
+
+impl Div<&mut Self> for Fp2 {
+    type Output = Self;
+
+    #[inline]
+    fn div(mut self, other: &mut Self) -> Self {
+        self.div_assign(&*other);
+        self
+    }
+}
+
+impl Product<Self> for Fp2 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), core::ops::Mul::mul)
+    }
+}
+
+impl<'a> Product<&'a Self> for Fp2 {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+impl Sum<Self> for Fp2 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), core::ops::Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Self> for Fp2 {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), core::ops::Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Fp, Fp2 as TestField};
+    use ark_algebra_test_templates::test_field;
+    use ark_ff::{FftField, Field, One};
+    use ark_std::{test_rng, UniformRand};
+
+    test_field!(generated; TestField; fft);
+
+    #[test]
+    fn norm_is_product_with_conjugate() {
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            let a = TestField::rand(&mut rng);
+            let norm = a * a.conjugate();
+            assert_eq!(norm, TestField::from(a.norm()));
+        }
+    }
+
+    #[test]
+    fn has_root_of_unity_of_order_two_pow_33() {
+        let root = TestField::get_root_of_unity(1 << 33).unwrap();
+        assert_eq!(root.pow([1 << 33]), TestField::one());
+        assert_ne!(root.pow([1 << 32]), TestField::one());
+    }
+
+    #[test]
+    fn sqrt_of_base_field_elements() {
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            let a = TestField::from(Fp::rand(&mut rng));
+            let root = a.sqrt().unwrap();
+            assert_eq!(root.square(), a);
+        }
+    }
+}
@@ -0,0 +1,776 @@
+//! An implementation of the cubic extension of the 64-bit STARK-friendly prime
+//! field `F_p[x]/(x^3 - x - 1)` where `p = 2^64 - 2^32 + 1`.
+//!
+//! This is the extension used by Miden VM to reach ~128 bits of soundness so
+//! elements can be shared with Miden proofs. Notable properties:
+//! * Reduction by `x^3 = x + 1` only takes additions.
+//! * The Frobenius map permutes the roots of `x^3 - x - 1` so it can be applied
+//!   with a few multiplications by precomputed constants.
+//! * Being an odd degree extension, an element is a square iff its norm is a
+//!   square in the base field.
+
+use super::{into_mont, Fp};
+use ark_ff::{FftField, Field, LegendreSymbol, One, SqrtPrecomputation, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
+    CanonicalSerializeWithFlags, Compress, EmptyFlags, Flags, SerializationError, Valid, Validate,
+};
+use core::{
+    fmt::{Display, Formatter},
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// `x^(p^i)` for `i = 0, 1, 2`
+const FROBENIUS_COEFF_C1: [Fp3; 3] = [
+    Fp3::new(into_mont(0), into_mont(1), into_mont(0)),
+    Fp3::new(
+        into_mont(10_615_703_402_128_488_253),
+        into_mont(10_050_274_602_728_160_328),
+        into_mont(11_746_561_000_929_144_102),
+    ),
+    Fp3::new(
+        into_mont(7_831_040_667_286_096_068),
+        into_mont(8_396_469_466_686_423_992),
+        into_mont(6_700_183_068_485_440_219),
+    ),
+];
+
+/// `x^(2 * p^i)` for `i = 0, 1, 2`
+const FROBENIUS_COEFF_C2: [Fp3; 3] = [
+    Fp3::new(into_mont(0), into_mont(0), into_mont(1)),
+    Fp3::new(
+        into_mont(6_700_183_068_485_440_220),
+        into_mont(14_531_223_735_771_536_287),
+        into_mont(8_396_469_466_686_423_992),
+    ),
+    Fp3::new(
+        into_mont(11_746_561_000_929_144_103),
+        into_mont(3_915_520_333_643_048_034),
+        into_mont(10_050_274_602_728_160_328),
+    ),
+];
+
+/// `(t - 1)/2` where `p^3 - 1 = 2^32 * t`
+const TRACE_MINUS_ONE_DIV_TWO: [u64; 3] = [
+    9_223_372_049_739_677_694,
+    9_223_372_049_739_677_692,
+    2_147_483_646,
+];
+
+/// An element `c0 + c1 * x + c2 * x^2` of the cubic extension of the 64-bit
+/// field
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+pub struct Fp3 {
+    pub c0: Fp,
+    pub c1: Fp,
+    pub c2: Fp,
+}
+
+impl Fp3 {
+    /// Creates the element `c0 + c1 * x + c2 * x^2`.
+    #[must_use]
+    pub const fn new(c0: Fp, c1: Fp, c2: Fp) -> Self {
+        Self { c0, c1, c2 }
+    }
+
+    /// Returns the norm `a * a^p * a^(p^2)` which lies in the base field.
+    #[must_use]
+    pub fn norm(&self) -> Fp {
+        let conjugates = self.frobenius_map(1) * self.frobenius_map(2);
+        self.norm_with_conjugates(&conjugates)
+    }
+
+    /// Returns the constant coefficient of `self * conjugates`. The other
+    /// coefficients vanish when `conjugates = a^p * a^(p^2)`.
+    #[inline]
+    #[allow(clippy::suspicious_operation_groupings)]
+    fn norm_with_conjugates(&self, conjugates: &Self) -> Fp {
+        self.c0 * conjugates.c0 + self.c1 * conjugates.c2 + self.c2 * conjugates.c1
+    }
+
+    /// Multiplies `self` by an element of the base field.
+    pub fn mul_assign_by_base_field(&mut self, element: &Fp) {
+        self.c0 *= element;
+        self.c1 *= element;
+        self.c2 *= element;
+    }
+}
+
+impl Field for Fp3 {
+    type BasePrimeField = Fp;
+    type BasePrimeFieldIter = core::array::IntoIter<Fp, 3>;
+
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<Self>> =
+        Some(SqrtPrecomputation::TonelliShanks {
+            two_adicity: Self::TWO_ADICITY,
+            quadratic_nonresidue_to_trace: Self::TWO_ADIC_ROOT_OF_UNITY,
+            trace_of_modulus_minus_one_div_two: &TRACE_MINUS_ONE_DIV_TWO,
+        });
+
+    const ZERO: Self = Self::new(Fp::ZERO, Fp::ZERO, Fp::ZERO);
+
+    const ONE: Self = Self::new(Fp::ONE, Fp::ZERO, Fp::ZERO);
+
+    fn extension_degree() -> u64 {
+        3
+    }
+
+    fn from_base_prime_field(elem: Self::BasePrimeField) -> Self {
+        Self::new(elem, Fp::ZERO, Fp::ZERO)
+    }
+
+    fn to_base_prime_field_elements(&self) -> Self::BasePrimeFieldIter {
+        [self.c0, self.c1, self.c2].into_iter()
+    }
+
+    fn from_base_prime_field_elems(elems: &[Self::BasePrimeField]) -> Option<Self> {
+        if elems.len() != usize::try_from(Self::extension_degree()).unwrap() {
+            return None;
+        }
+        Some(Self::new(elems[0], elems[1], elems[2]))
+    }
+
+    #[inline]
+    fn double(&self) -> Self {
+        let mut temp = *self;
+        temp.double_in_place();
+        temp
+    }
+
+    #[inline]
+    fn double_in_place(&mut self) -> &mut Self {
+        self.c0.double_in_place();
+        self.c1.double_in_place();
+        self.c2.double_in_place();
+        self
+    }
+
+    #[inline]
+    fn neg_in_place(&mut self) -> &mut Self {
+        self.c0.neg_in_place();
+        self.c1.neg_in_place();
+        self.c2.neg_in_place();
+        self
+    }
+
+    #[inline]
+    fn from_random_bytes_with_flags<F: Flags>(bytes: &[u8]) -> Option<(Self, F)> {
+        let split_at = bytes.len() / 3;
+        let c0 = Fp::from_random_bytes(&bytes[..split_at])?;
+        let c1 = Fp::from_random_bytes(&bytes[split_at..2 * split_at])?;
+        let (c2, flags) = Fp::from_random_bytes_with_flags(&bytes[2 * split_at..])?;
+        Some((Self::new(c0, c1, c2), flags))
+    }
+
+    #[inline]
+    fn square(&self) -> Self {
+        let mut temp = *self;
+        temp.square_in_place();
+        temp
+    }
+
+    /// Chung-Hasan squaring (SQR2) with 3 squarings and 2 multiplications in
+    /// the base field. See <https://cacr.uwaterloo.ca/techreports/2006/cacr2006-24.pdf>.
+    #[inline]
+    fn square_in_place(&mut self) -> &mut Self {
+        let s0 = self.c0.square();
+        let s1 = (self.c0 * self.c1).double();
+        let s2 = (self.c0 - self.c1 + self.c2).square();
+        let s3 = (self.c1 * self.c2).double();
+        let s4 = self.c2.square();
+        let d2 = s1 + s2 + s3 - s0 - s4;
+        self.c0 = s0 + s3;
+        self.c1 = s1 + s3 + s4;
+        self.c2 = d2 + s4;
+        self
+    }
+
+    #[inline]
+    fn inverse(&self) -> Option<Self> {
+        // 1/a = a^p * a^(p^2) / N(a)
+        let mut conjugates = self.frobenius_map(1) * self.frobenius_map(2);
+        self.norm_with_conjugates(&conjugates)
+            .inverse()
+            .map(|norm_inv| {
+                conjugates.mul_assign_by_base_field(&norm_inv);
+                conjugates
+            })
+    }
+
+    fn inverse_in_place(&mut self) -> Option<&mut Self> {
+        self.inverse().map(|inverse| {
+            *self = inverse;
+            self
+        })
+    }
+
+    /// Computes `c0 + c1 * x^(p^i) + c2 * x^(2 * p^i)` where `i = power % 3`.
+    #[inline]
+    fn frobenius_map_in_place(&mut self, power: usize) {
+        let i = power % 3;
+        if i != 0 {
+            let mut c1 = FROBENIUS_COEFF_C1[i];
+            c1.mul_assign_by_base_field(&self.c1);
+            let mut c2 = FROBENIUS_COEFF_C2[i];
+            c2.mul_assign_by_base_field(&self.c2);
+            *self = Self::from_base_prime_field(self.c0) + c1 + c2;
+        }
+    }
+
+    /// Since `(p^3 - 1)/2 = (p - 1)/2 * (p^2 + p + 1)` the legendre symbol of
+    /// an element is the legendre symbol of its norm.
+    #[inline]
+    fn legendre(&self) -> LegendreSymbol {
+        self.norm().legendre()
+    }
+}
+
+/// `p^3 - 1 = (p - 1)(p^2 + p + 1)` where `p^2 + p + 1` is odd so the two-adic
+/// subgroup is the one of the base field.
+impl FftField for Fp3 {
+    const GENERATOR: Self = Self::new(into_mont(2), into_mont(1), into_mont(0));
+    const TWO_ADICITY: u32 = 32;
+    const TWO_ADIC_ROOT_OF_UNITY: Self = Self::new(
+        into_mont(1_753_635_133_440_165_772),
+        into_mont(0),
+        into_mont(0),
+    );
+    const SMALL_SUBGROUP_BASE: Option<u32> = None;
+    const SMALL_SUBGROUP_BASE_ADICITY: Option<u32> = None;
+    const LARGE_SUBGROUP_ROOT_OF_UNITY: Option<Self> = None;
+}
+
+impl zeroize::Zeroize for Fp3 {
+    fn zeroize(&mut self) {
+        self.c0.zeroize();
+        self.c1.zeroize();
+        self.c2.zeroize();
+    }
+}
+
+impl Zero for Fp3 {
+    #[inline]
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+}
+
+impl One for Fp3 {
+    #[inline]
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    #[inline]
+    fn is_one(&self) -> bool {
+        self.c0.is_one() && self.c1.is_zero() && self.c2.is_zero()
+    }
+}
+
+/// `Fp3` elements are ordered lexicographically.
+impl Ord for Fp3 {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.c2
+            .cmp(&other.c2)
+            .then_with(|| self.c1.cmp(&other.c1))
+            .then_with(|| self.c0.cmp(&other.c0))
+    }
+}
+
+impl PartialOrd for Fp3 {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<Fp> for Fp3 {
+    fn from(other: Fp) -> Self {
+        Self::from_base_prime_field(other)
+    }
+}
+
+impl From<u128> for Fp3 {
+    fn from(other: u128) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i128> for Fp3 {
+    fn from(other: i128) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<bool> for Fp3 {
+    fn from(other: bool) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u64> for Fp3 {
+    fn from(other: u64) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i64> for Fp3 {
+    fn from(other: i64) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u32> for Fp3 {
+    fn from(other: u32) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i32> for Fp3 {
+    fn from(other: i32) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u16> for Fp3 {
+    fn from(other: u16) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i16> for Fp3 {
+    fn from(other: i16) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u8> for Fp3 {
+    fn from(other: u8) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i8> for Fp3 {
+    fn from(other: i8) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl ark_std::rand::distributions::Distribution<Fp3> for ark_std::rand::distributions::Standard {
+    #[inline]
+    fn sample<R: ark_std::rand::Rng + ?Sized>(&self, rng: &mut R) -> Fp3 {
+        Fp3::new(rng.gen(), rng.gen(), rng.gen())
+    }
+}
+
+impl CanonicalSerializeWithFlags for Fp3 {
+    fn serialize_with_flags<W: ark_std::io::Write, F: Flags>(
+        &self,
+        mut writer: W,
+        flags: F,
+    ) -> Result<(), SerializationError> {
+        self.c0.serialize_compressed(&mut writer)?;
+        self.c1.serialize_compressed(&mut writer)?;
+        self.c2.serialize_with_flags(&mut writer, flags)?;
+        Ok(())
+    }
+
+    fn serialized_size_with_flags<F: Flags>(&self) -> usize {
+        self.c0.compressed_size()
+            + self.c1.compressed_size()
+            + self.c2.serialized_size_with_flags::<F>()
+    }
+}
+
+impl CanonicalSerialize for Fp3 {
+    #[inline]
+    fn serialize_with_mode<W: ark_std::io::Write>(
+        &self,
+        writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.serialize_with_flags(writer, EmptyFlags)
+    }
+
+    #[inline]
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        self.serialized_size_with_flags::<EmptyFlags>()
+    }
+}
+
+impl CanonicalDeserializeWithFlags for Fp3 {
+    fn deserialize_with_flags<R: ark_std::io::Read, F: Flags>(
+        mut reader: R,
+    ) -> Result<(Self, F), SerializationError> {
+        let c0 = Fp::deserialize_compressed(&mut reader)?;
+        let c1 = Fp::deserialize_compressed(&mut reader)?;
+        let (c2, flags) = Fp::deserialize_with_flags(&mut reader)?;
+        Ok((Self::new(c0, c1, c2), flags))
+    }
+}
+
+impl Valid for Fp3 {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.c0.check()?;
+        self.c1.check()?;
+        self.c2.check()
+    }
+}
+
+impl CanonicalDeserialize for Fp3 {
+    fn deserialize_with_mode<R: ark_std::io::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let c0 = Fp::deserialize_with_mode(&mut reader, compress, validate)?;
+        let c1 = Fp::deserialize_with_mode(&mut reader, compress, validate)?;
+        let c2 = Fp::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self::new(c0, c1, c2))
+    }
+}
+
+/// Outputs a string of the form `c0 + c1 * x + c2 * x^2`.
+impl Display for Fp3 {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} + {} * x + {} * x^2", self.c0, self.c1, self.c2)
+    }
+}
+
+impl Neg for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(mut self) -> Self {
+        self.neg_in_place();
+        self
+    }
+}
+
+impl AddAssign<&Self> for Fp3 {
+    #[inline]
+    fn add_assign(&mut self, other: &Self) {
+        self.c0 += other.c0;
+        self.c1 += other.c1;
+        self.c2 += other.c2;
+    }
+}
+
+impl SubAssign<&Self> for Fp3 {
+    #[inline]
+    fn sub_assign(&mut self, other: &Self) {
+        self.c0 -= other.c0;
+        self.c1 -= other.c1;
+        self.c2 -= other.c2;
+    }
+}
+
+impl MulAssign<&Self> for Fp3 {
+    /// Karatsuba multiplication with 6 base field multiplications followed
+    /// by a reduction using `x^3 = x + 1` and `x^4 = x^2 + x`.
+    #[inline]
+    fn mul_assign(&mut self, other: &Self) {
+        let v0 = self.c0 * other.c0;
+        let v1 = self.c1 * other.c1;
+        let v2 = self.c2 * other.c2;
+        let d1 = (self.c0 + self.c1) * (other.c0 + other.c1) - v0 - v1;
+        let d2 = (self.c0 + self.c2) * (other.c0 + other.c2) - v0 - v2 + v1;
+        let d3 = (self.c1 + self.c2) * (other.c1 + other.c2) - v1 - v2;
+        self.c0 = v0 + d3;
+        self.c1 = d1 + d3 + v2;
+        self.c2 = d2 + v2;
+    }
+}
+
+/// Computes `self *= other.inverse()` if `other.inverse()` is `Some`, and
+/// panics otherwise.
+impl DivAssign<&Self> for Fp3 {
+    #[inline]
+    fn div_assign(&mut self, other: &Self) {
+        self.mul_assign(&other.inverse().unwrap());
+    }
+}
+
+impl Add<&Self> for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: &Self) -> Self {
+        self.add_assign(other);
+        self
+    }
+}
+
+impl Sub<&Self> for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: &Self) -> Self {
+        self.sub_assign(other);
+        self
+    }
+}
+
+impl Mul<&Self> for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: &Self) -> Self {
+        self.mul_assign(other);
+        self
+    }
+}
+
+impl Div<&Self> for Fp3 {
+    type Output = Self;
+
+    /// Returns `self * other.inverse()` if `other.inverse()` is `Some`, and
+    /// panics otherwise.
+    #[inline]
+    fn div(mut self, other: &Self) -> Self {
+        self.div_assign(other);
+        self
+    }
+}
+
+impl Add<&Fp3> for &Fp3 {
+    type Output = Fp3;
+
+    #[inline]
+    fn add(self, other: &Fp3) -> Fp3 {
+        *self + other
+    }
+}
+
+impl Sub<&Fp3> for &Fp3 {
+    type Output = Fp3;
+
+    #[inline]
+    fn sub(self, other: &Fp3) -> Fp3 {
+        *self - other
+    }
+}
+
+impl Mul<&Fp3> for &Fp3 {
+    type Output = Fp3;
+
+    #[inline]
+    fn mul(self, other: &Fp3) -> Fp3 {
+        *self * other
+    }
+}
+
+impl Div<&Fp3> for &Fp3 {
+    type Output = Fp3;
+
+    #[inline]
+    fn div(self, other: &Fp3) -> Fp3 {
+        *self / other
+    }
+}
+
+impl AddAssign<&mut Self> for Fp3 {
+    #[inline]
+    fn add_assign(&mut self, other: &mut Self) {
+        self.add_assign(&*other);
+    }
+}
+
+impl SubAssign<&mut Self> for Fp3 {
+    #[inline]
+    fn sub_assign(&mut self, other: &mut Self) {
+        self.sub_assign(&*other);
+    }
+}
+
+impl MulAssign<&mut Self> for Fp3 {
+    #[inline]
+    fn mul_assign(&mut self, other: &mut Self) {
+        self.mul_assign(&*other);
+    }
+}
+
+impl DivAssign<&mut Self> for Fp3 {
+    #[inline]
+    fn div_assign(&mut self, other: &mut Self) {
+        self.div_assign(&*other);
+    }
+}
+
+impl AddAssign<Self> for Fp3 {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.add_assign(&other);
+    }
+}
+
+impl SubAssign<Self> for Fp3 {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.sub_assign(&other);
+    }
+}
+
+impl MulAssign<Self> for Fp3 {
+    #[inline]
+    fn mul_assign(&mut self, other: Self) {
+        self.mul_assign(&other);
+    }
+}
+
+impl DivAssign<Self> for Fp3 {
+    #[inline]
+    fn div_assign(&mut self, other: Self) {
+        self.div_assign(&other);
+    }
+}
+
+impl Add<Self> for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: Self) -> Self {
+        self.add_assign(&other);
+        self
+    }
+}
+
+impl Sub<Self> for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: Self) -> Self {
+        self.sub_assign(&other);
+        self
+    }
+}
+
+impl Mul<Self> for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: Self) -> Self {
+        self.mul_assign(&other);
+        self
+    }
+}
+
+impl Div<Self> for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn div(mut self, other: Self) -> Self {
+        self.div_assign(&other);
+        self
+    }
+}
+
+impl Add<&mut Self> for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: &mut Self) -> Self {
+        self.add_assign(&*other);
+        self
+    }
+}
+
+impl Sub<&mut Self> for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: &mut Self) -> Self {
+        self.sub_assign(&*other);
+        self
+    }
+}
+
+impl Mul<&mut Self> for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: &mut Self) -> Self {
+        self.mul_assign(&*other);
+        self
+    }
+}
+
+impl Div<&mut Self> for Fp3 {
+    type Output = Self;
+
+    #[inline]
+    fn div(mut self, other: &mut Self) -> Self {
+        self.div_assign(&*other);
+        self
+    }
+}
+
+impl Product<Self> for Fp3 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), core::ops::Mul::mul)
+    }
+}
+
+impl<'a> Product<&'a Self> for Fp3 {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+impl Sum<Self> for Fp3 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), core::ops::Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Self> for Fp3 {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), core::ops::Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Fp, Fp3 as TestField};
+    use ark_algebra_test_templates::test_field;
+    use ark_ff::{Field, One, Zero};
+    use ark_std::{test_rng, UniformRand};
+
+    test_field!(generated; TestField; fft);
+
+    #[test]
+    fn generator_is_root_of_modulus() {
+        let x = TestField::new(Fp::zero(), Fp::one(), Fp::zero());
+        assert_eq!(x.square() * x, x + TestField::one());
+    }
+
+    #[test]
+    fn norm_is_product_of_conjugates() {
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            let a = TestField::rand(&mut rng);
+            let norm = a * a.frobenius_map(1) * a.frobenius_map(2);
+            assert_eq!(norm, TestField::from(a.norm()));
+        }
+    }
+
+    #[test]
+    fn mul_by_base_field() {
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            let a = TestField::rand(&mut rng);
+            let b = Fp::rand(&mut rng);
+            let mut c = a;
+            c.mul_assign_by_base_field(&b);
+            assert_eq!(c, a * TestField::from(b));
+        }
+    }
+}
@@ -0,0 +1,912 @@
+//! An implementation of the quintic extension of the 64-bit STARK-friendly
+//! prime field `F_p[z]/(z^5 - 3)` where `p = 2^64 - 2^32 + 1`.
+//!
+//! This is the field `GF(p^5)` over which the `EcGFp5` curve is defined. The
+//! implementation follows <https://eprint.iacr.org/2022/274.pdf>:
+//! * Multiplication and squaring accumulate the 128-bit products of each
+//!   coefficient before a single Montgomery reduction.
+//! * Since `p = 1 mod 5` the Frobenius map `z -> z^p` is the multiplication of
+//!   each coefficient by a power of a 5th root of unity. Inversion and square
+//!   roots are computed with a few Frobenius maps and one inversion or square
+//!   root in the base field.
+//! * Being an odd degree extension, an element is a square iff its norm is a
+//!   square in the base field.
+
+use super::{into_mont, mont_red, Fp, MODULUS};
+use ark_ff::{BigInt, FftField, Field, LegendreSymbol, One, SqrtPrecomputation, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
+    CanonicalSerializeWithFlags, Compress, EmptyFlags, Flags, SerializationError, Valid, Validate,
+};
+use core::{
+    fmt::{Display, Formatter},
+    iter::{Product, Sum},
+    marker::PhantomData,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// Powers of the 5th root of unity `w = 3^((p-1)/5)` such that `z^p = w * z`
+const FROBENIUS_COEFF: [Fp; 5] = [
+    into_mont(1),
+    into_mont(1_041_288_259_238_279_555),
+    into_mont(15_820_824_984_080_659_046),
+    into_mont(211_587_555_138_949_697),
+    into_mont(1_373_043_270_956_696_022),
+];
+
+/// Multiplies a base field element by `3` as `2a + a`.
+#[inline]
+fn mul_by_three(a: Fp) -> Fp {
+    a.double() + a
+}
+
+/// Computes `a[0] * b[0] + ... + a[N-1] * b[N-1]` with a single Montgomery
+/// reduction. `N` must be at most 5 so the carries fit in a few bits.
+#[inline]
+fn sum_of_products<const N: usize>(a: [Fp; N], b: [Fp; N]) -> Fp {
+    let mut lo = 0u128;
+    let mut hi = 0u64;
+    for (a, b) in a.iter().zip(&b) {
+        let (sum, carry) = lo.overflowing_add(u128::from((a.0).0[0]) * u128::from((b.0).0[0]));
+        lo = sum;
+        hi += u64::from(carry);
+    }
+    // `mont_red` needs the high limb below `p`. Subtracting `p * 2^64` keeps
+    // the value modulo `p`. A mask rather than a branch keeps the
+    // multiplication constant time.
+    let mask = 0u128.wrapping_sub(u128::from((lo >> 64) as u64 >= MODULUS));
+    lo -= (u128::from(MODULUS) << 64) & mask;
+    // The reduction of `hi * 2^128` is `hi * 2^64 = hi * (2^32 - 1) mod p`.
+    let lo = ark_ff::Fp(BigInt([mont_red(lo)]), PhantomData);
+    let hi = ark_ff::Fp(BigInt([hi * 0xFFFF_FFFF]), PhantomData);
+    lo + hi
+}
+
+/// An element `c0 + c1 * z + c2 * z^2 + c3 * z^3 + c4 * z^4` of the quintic
+/// extension of the 64-bit field
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+pub struct Fp5 {
+    pub c0: Fp,
+    pub c1: Fp,
+    pub c2: Fp,
+    pub c3: Fp,
+    pub c4: Fp,
+}
+
+impl Fp5 {
+    /// Creates the element `c0 + c1 * z + c2 * z^2 + c3 * z^3 + c4 * z^4`.
+    #[must_use]
+    pub const fn new(c0: Fp, c1: Fp, c2: Fp, c3: Fp, c4: Fp) -> Self {
+        Self { c0, c1, c2, c3, c4 }
+    }
+
+    /// Returns the norm `a * a^p * a^(p^2) * a^(p^3) * a^(p^4)` which lies in
+    /// the base field.
+    #[must_use]
+    pub fn norm(&self) -> Fp {
+        self.norm_with_conjugates(&self.conjugates())
+    }
+
+    /// Returns `a^(p + p^2 + p^3 + p^4)` with three multiplications.
+    #[inline]
+    fn conjugates(&self) -> Self {
+        let t0 = self.frobenius_map(1) * self.frobenius_map(2);
+        t0 * t0.frobenius_map(2)
+    }
+
+    /// Returns the constant coefficient of `self * conjugates`. The other
+    /// coefficients vanish when `conjugates = a^(p + p^2 + p^3 + p^4)`.
+    #[inline]
+    fn norm_with_conjugates(&self, conjugates: &Self) -> Fp {
+        sum_of_products(
+            [self.c0, self.c1, self.c2, self.c3, self.c4],
+            [
+                conjugates.c0,
+                mul_by_three(conjugates.c4),
+                mul_by_three(conjugates.c3),
+                mul_by_three(conjugates.c2),
+                mul_by_three(conjugates.c1),
+            ],
+        )
+    }
+
+    /// Multiplies `self` by an element of the base field.
+    pub fn mul_assign_by_base_field(&mut self, element: &Fp) {
+        self.c0 *= element;
+        self.c1 *= element;
+        self.c2 *= element;
+        self.c3 *= element;
+        self.c4 *= element;
+    }
+
+    /// Multiplies `self` by `z`.
+    #[must_use]
+    pub fn mul_by_z(&self) -> Self {
+        Self::new(mul_by_three(self.c4), self.c0, self.c1, self.c2, self.c3)
+    }
+
+    /// Squares `self` `n` times.
+    #[must_use]
+    fn square_n(mut self, n: usize) -> Self {
+        for _ in 0..n {
+            self.square_in_place();
+        }
+        self
+    }
+}
+
+impl Field for Fp5 {
+    type BasePrimeField = Fp;
+    type BasePrimeFieldIter = core::array::IntoIter<Fp, 5>;
+
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<Self>> = None;
+
+    const ZERO: Self = Self::new(Fp::ZERO, Fp::ZERO, Fp::ZERO, Fp::ZERO, Fp::ZERO);
+
+    const ONE: Self = Self::new(Fp::ONE, Fp::ZERO, Fp::ZERO, Fp::ZERO, Fp::ZERO);
+
+    fn extension_degree() -> u64 {
+        5
+    }
+
+    fn from_base_prime_field(elem: Self::BasePrimeField) -> Self {
+        Self::new(elem, Fp::ZERO, Fp::ZERO, Fp::ZERO, Fp::ZERO)
+    }
+
+    fn to_base_prime_field_elements(&self) -> Self::BasePrimeFieldIter {
+        [self.c0, self.c1, self.c2, self.c3, self.c4].into_iter()
+    }
+
+    fn from_base_prime_field_elems(elems: &[Self::BasePrimeField]) -> Option<Self> {
+        if elems.len() != usize::try_from(Self::extension_degree()).unwrap() {
+            return None;
+        }
+        Some(Self::new(elems[0], elems[1], elems[2], elems[3], elems[4]))
+    }
+
+    #[inline]
+    fn double(&self) -> Self {
+        let mut temp = *self;
+        temp.double_in_place();
+        temp
+    }
+
+    #[inline]
+    fn double_in_place(&mut self) -> &mut Self {
+        self.c0.double_in_place();
+        self.c1.double_in_place();
+        self.c2.double_in_place();
+        self.c3.double_in_place();
+        self.c4.double_in_place();
+        self
+    }
+
+    #[inline]
+    fn neg_in_place(&mut self) -> &mut Self {
+        self.c0.neg_in_place();
+        self.c1.neg_in_place();
+        self.c2.neg_in_place();
+        self.c3.neg_in_place();
+        self.c4.neg_in_place();
+        self
+    }
+
+    #[inline]
+    fn from_random_bytes_with_flags<F: Flags>(bytes: &[u8]) -> Option<(Self, F)> {
+        let split_at = bytes.len() / 5;
+        let c0 = Fp::from_random_bytes(&bytes[..split_at])?;
+        let c1 = Fp::from_random_bytes(&bytes[split_at..2 * split_at])?;
+        let c2 = Fp::from_random_bytes(&bytes[2 * split_at..3 * split_at])?;
+        let c3 = Fp::from_random_bytes(&bytes[3 * split_at..4 * split_at])?;
+        let (c4, flags) = Fp::from_random_bytes_with_flags(&bytes[4 * split_at..])?;
+        Some((Self::new(c0, c1, c2, c3, c4), flags))
+    }
+
+    #[inline]
+    fn square(&self) -> Self {
+        let mut temp = *self;
+        temp.square_in_place();
+        temp
+    }
+
+    /// Squaring with 15 products in the base field by doubling the cross
+    /// terms up front.
+    #[inline]
+    fn square_in_place(&mut self) -> &mut Self {
+        let [a0, a1, a2, a3, a4] = [self.c0, self.c1, self.c2, self.c3, self.c4];
+        let a0_2 = a0.double();
+        let a1_2 = a1.double();
+        let a3_3 = mul_by_three(a3);
+        let a4_3 = mul_by_three(a4);
+        let a3_6 = a3_3.double();
+        let a4_6 = a4_3.double();
+        self.c0 = sum_of_products([a0, a1, a2], [a0, a4_6, a3_6]);
+        self.c1 = sum_of_products([a0_2, a2, a3], [a1, a4_6, a3_3]);
+        self.c2 = sum_of_products([a0_2, a1, a3], [a2, a1, a4_6]);
+        self.c3 = sum_of_products([a0_2, a1_2, a4], [a3, a2, a4_3]);
+        self.c4 = sum_of_products([a0_2, a1_2, a2], [a4, a3, a2]);
+        self
+    }
+
+    #[inline]
+    fn inverse(&self) -> Option<Self> {
+        // 1/a = a^(p + p^2 + p^3 + p^4) / N(a)
+        let mut conjugates = self.conjugates();
+        self.norm_with_conjugates(&conjugates)
+            .inverse()
+            .map(|norm_inv| {
+                conjugates.mul_assign_by_base_field(&norm_inv);
+                conjugates
+            })
+    }
+
+    fn inverse_in_place(&mut self) -> Option<&mut Self> {
+        self.inverse().map(|inverse| {
+            *self = inverse;
+            self
+        })
+    }
+
+    /// Since `z^(p^i) = w^i * z` the `i`-th coefficient is multiplied by
+    /// `w^(i * power)`.
+    #[inline]
+    fn frobenius_map_in_place(&mut self, power: usize) {
+        let k = power % 5;
+        if k != 0 {
+            self.c1 *= FROBENIUS_COEFF[k];
+            self.c2 *= FROBENIUS_COEFF[(2 * k) % 5];
+            self.c3 *= FROBENIUS_COEFF[(3 * k) % 5];
+            self.c4 *= FROBENIUS_COEFF[(4 * k) % 5];
+        }
+    }
+
+    /// Since `(p^5 - 1)/2 = (p - 1)/2 * (1 + p + p^2 + p^3 + p^4)` the
+    /// legendre symbol of an element is the legendre symbol of its norm.
+    #[inline]
+    fn legendre(&self) -> LegendreSymbol {
+        self.norm().legendre()
+    }
+
+    /// With `r = 1 + p + p^2 + p^3 + p^4` we have `y = a^((r + 1)/2)` satisfies
+    /// `y^2 = a * N(a)` so `sqrt(a) = y / sqrt(N(a))`. Computing `y` only
+    /// requires `a^((p - 1)/2)` and a few Frobenius maps.
+    fn sqrt(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(Self::ZERO);
+        }
+        // g = a^((p - 1)/2) = a^((2^32 - 1) * 2^31)
+        let t2 = self.square() * self;
+        let t4 = t2.square_n(2) * t2;
+        let t8 = t4.square_n(4) * t4;
+        let t16 = t8.square_n(8) * t8;
+        let t32 = t16.square_n(16) * t16;
+        let g = t32.square_n(31);
+        // u = a^(p(p + 1)/2) and y = a * u * u^(p^2)
+        let u = *self * g.square() * g.frobenius_map(1);
+        let y = *self * u * u.frobenius_map(2);
+        let norm_sqrt = self.norm().sqrt()?;
+        let mut res = y;
+        res.mul_assign_by_base_field(&norm_sqrt.inverse()?);
+        Some(res)
+    }
+}
+
+/// `p^5 - 1 = (p - 1)(1 + p + p^2 + p^3 + p^4)` where the second factor is odd
+/// so the two-adic subgroup is the one of the base field.
+impl FftField for Fp5 {
+    const GENERATOR: Self = Self::new(
+        into_mont(2),
+        into_mont(1),
+        into_mont(0),
+        into_mont(0),
+        into_mont(0),
+    );
+    const TWO_ADICITY: u32 = 32;
+    const TWO_ADIC_ROOT_OF_UNITY: Self = Self::new(
+        into_mont(1_753_635_133_440_165_772),
+        into_mont(0),
+        into_mont(0),
+        into_mont(0),
+        into_mont(0),
+    );
+    const SMALL_SUBGROUP_BASE: Option<u32> = None;
+    const SMALL_SUBGROUP_BASE_ADICITY: Option<u32> = None;
+    const LARGE_SUBGROUP_ROOT_OF_UNITY: Option<Self> = None;
+}
+
+impl zeroize::Zeroize for Fp5 {
+    fn zeroize(&mut self) {
+        self.c0.zeroize();
+        self.c1.zeroize();
+        self.c2.zeroize();
+        self.c3.zeroize();
+        self.c4.zeroize();
+    }
+}
+
+impl Zero for Fp5 {
+    #[inline]
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero()
+            && self.c1.is_zero()
+            && self.c2.is_zero()
+            && self.c3.is_zero()
+            && self.c4.is_zero()
+    }
+}
+
+impl One for Fp5 {
+    #[inline]
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    #[inline]
+    fn is_one(&self) -> bool {
+        self.c0.is_one()
+            && self.c1.is_zero()
+            && self.c2.is_zero()
+            && self.c3.is_zero()
+            && self.c4.is_zero()
+    }
+}
+
+/// `Fp5` elements are ordered lexicographically.
+impl Ord for Fp5 {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.c4
+            .cmp(&other.c4)
+            .then_with(|| self.c3.cmp(&other.c3))
+            .then_with(|| self.c2.cmp(&other.c2))
+            .then_with(|| self.c1.cmp(&other.c1))
+            .then_with(|| self.c0.cmp(&other.c0))
+    }
+}
+
+impl PartialOrd for Fp5 {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<Fp> for Fp5 {
+    fn from(other: Fp) -> Self {
+        Self::from_base_prime_field(other)
+    }
+}
+
+impl From<u128> for Fp5 {
+    fn from(other: u128) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i128> for Fp5 {
+    fn from(other: i128) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<bool> for Fp5 {
+    fn from(other: bool) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u64> for Fp5 {
+    fn from(other: u64) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i64> for Fp5 {
+    fn from(other: i64) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u32> for Fp5 {
+    fn from(other: u32) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i32> for Fp5 {
+    fn from(other: i32) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u16> for Fp5 {
+    fn from(other: u16) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i16> for Fp5 {
+    fn from(other: i16) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u8> for Fp5 {
+    fn from(other: u8) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i8> for Fp5 {
+    fn from(other: i8) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl ark_std::rand::distributions::Distribution<Fp5> for ark_std::rand::distributions::Standard {
+    #[inline]
+    fn sample<R: ark_std::rand::Rng + ?Sized>(&self, rng: &mut R) -> Fp5 {
+        Fp5::new(rng.gen(), rng.gen(), rng.gen(), rng.gen(), rng.gen())
+    }
+}
+
+impl CanonicalSerializeWithFlags for Fp5 {
+    fn serialize_with_flags<W: ark_std::io::Write, F: Flags>(
+        &self,
+        mut writer: W,
+        flags: F,
+    ) -> Result<(), SerializationError> {
+        self.c0.serialize_compressed(&mut writer)?;
+        self.c1.serialize_compressed(&mut writer)?;
+        self.c2.serialize_compressed(&mut writer)?;
+        self.c3.serialize_compressed(&mut writer)?;
+        self.c4.serialize_with_flags(&mut writer, flags)?;
+        Ok(())
+    }
+
+    fn serialized_size_with_flags<F: Flags>(&self) -> usize {
+        self.c0.compressed_size()
+            + self.c1.compressed_size()
+            + self.c2.compressed_size()
+            + self.c3.compressed_size()
+            + self.c4.serialized_size_with_flags::<F>()
+    }
+}
+
+impl CanonicalSerialize for Fp5 {
+    #[inline]
+    fn serialize_with_mode<W: ark_std::io::Write>(
+        &self,
+        writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.serialize_with_flags(writer, EmptyFlags)
+    }
+
+    #[inline]
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        self.serialized_size_with_flags::<EmptyFlags>()
+    }
+}
+
+impl CanonicalDeserializeWithFlags for Fp5 {
+    fn deserialize_with_flags<R: ark_std::io::Read, F: Flags>(
+        mut reader: R,
+    ) -> Result<(Self, F), SerializationError> {
+        let c0 = Fp::deserialize_compressed(&mut reader)?;
+        let c1 = Fp::deserialize_compressed(&mut reader)?;
+        let c2 = Fp::deserialize_compressed(&mut reader)?;
+        let c3 = Fp::deserialize_compressed(&mut reader)?;
+        let (c4, flags) = Fp::deserialize_with_flags(&mut reader)?;
+        Ok((Self::new(c0, c1, c2, c3, c4), flags))
+    }
+}
+
+impl Valid for Fp5 {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.c0.check()?;
+        self.c1.check()?;
+        self.c2.check()?;
+        self.c3.check()?;
+        self.c4.check()
+    }
+}
+
+impl CanonicalDeserialize for Fp5 {
+    fn deserialize_with_mode<R: ark_std::io::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let c0 = Fp::deserialize_with_mode(&mut reader, compress, validate)?;
+        let c1 = Fp::deserialize_with_mode(&mut reader, compress, validate)?;
+        let c2 = Fp::deserialize_with_mode(&mut reader, compress, validate)?;
+        let c3 = Fp::deserialize_with_mode(&mut reader, compress, validate)?;
+        let c4 = Fp::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self::new(c0, c1, c2, c3, c4))
+    }
+}
+
+/// Outputs a string of the form `c0 + c1 * z + ... + c4 * z^4`.
+impl Display for Fp5 {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} + {} * z + {} * z^2 + {} * z^3 + {} * z^4",
+            self.c0, self.c1, self.c2, self.c3, self.c4
+        )
+    }
+}
+
+impl Neg for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(mut self) -> Self {
+        self.neg_in_place();
+        self
+    }
+}
+
+impl AddAssign<&Self> for Fp5 {
+    #[inline]
+    fn add_assign(&mut self, other: &Self) {
+        self.c0 += other.c0;
+        self.c1 += other.c1;
+        self.c2 += other.c2;
+        self.c3 += other.c3;
+        self.c4 += other.c4;
+    }
+}
+
+impl SubAssign<&Self> for Fp5 {
+    #[inline]
+    fn sub_assign(&mut self, other: &Self) {
+        self.c0 -= other.c0;
+        self.c1 -= other.c1;
+        self.c2 -= other.c2;
+        self.c3 -= other.c3;
+        self.c4 -= other.c4;
+    }
+}
+
+impl MulAssign<&Self> for Fp5 {
+    /// Schoolbook multiplication where each coefficient is a sum of five
+    /// products which is reduced only once. The wrap-around terms are scaled
+    /// by `z^5 = 3` beforehand.
+    #[inline]
+    fn mul_assign(&mut self, other: &Self) {
+        let [a0, a1, a2, a3, a4] = [self.c0, self.c1, self.c2, self.c3, self.c4];
+        let [b0, b1, b2, b3, b4] = [other.c0, other.c1, other.c2, other.c3, other.c4];
+        let [b1_3, b2_3, b3_3, b4_3] = [b1, b2, b3, b4].map(mul_by_three);
+        self.c0 = sum_of_products([a0, a1, a2, a3, a4], [b0, b4_3, b3_3, b2_3, b1_3]);
+        self.c1 = sum_of_products([a0, a1, a2, a3, a4], [b1, b0, b4_3, b3_3, b2_3]);
+        self.c2 = sum_of_products([a0, a1, a2, a3, a4], [b2, b1, b0, b4_3, b3_3]);
+        self.c3 = sum_of_products([a0, a1, a2, a3, a4], [b3, b2, b1, b0, b4_3]);
+        self.c4 = sum_of_products([a0, a1, a2, a3, a4], [b4, b3, b2, b1, b0]);
+    }
+}
+
+/// Computes `self *= other.inverse()` if `other.inverse()` is `Some`, and
+/// panics otherwise.
+impl DivAssign<&Self> for Fp5 {
+    #[inline]
+    fn div_assign(&mut self, other: &Self) {
+        self.mul_assign(&other.inverse().unwrap());
+    }
+}
+
+impl Add<&Self> for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: &Self) -> Self {
+        self.add_assign(other);
+        self
+    }
+}
+
+impl Sub<&Self> for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: &Self) -> Self {
+        self.sub_assign(other);
+        self
+    }
+}
+
+impl Mul<&Self> for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: &Self) -> Self {
+        self.mul_assign(other);
+        self
+    }
+}
+
+impl Div<&Self> for Fp5 {
+    type Output = Self;
+
+    /// Returns `self * other.inverse()` if `other.inverse()` is `Some`, and
+    /// panics otherwise.
+    #[inline]
+    fn div(mut self, other: &Self) -> Self {
+        self.div_assign(other);
+        self
+    }
+}
+
+impl Add<&Fp5> for &Fp5 {
+    type Output = Fp5;
+
+    #[inline]
+    fn add(self, other: &Fp5) -> Fp5 {
+        *self + other
+    }
+}
+
+impl Sub<&Fp5> for &Fp5 {
+    type Output = Fp5;
+
+    #[inline]
+    fn sub(self, other: &Fp5) -> Fp5 {
+        *self - other
+    }
+}
+
+impl Mul<&Fp5> for &Fp5 {
+    type Output = Fp5;
+
+    #[inline]
+    fn mul(self, other: &Fp5) -> Fp5 {
+        *self * other
+    }
+}
+
+impl Div<&Fp5> for &Fp5 {
+    type Output = Fp5;
+
+    #[inline]
+    fn div(self, other: &Fp5) -> Fp5 {
+        *self / other
+    }
+}
+
+impl AddAssign<&mut Self> for Fp5 {
+    #[inline]
+    fn add_assign(&mut self, other: &mut Self) {
+        self.add_assign(&*other);
+    }
+}
+
+impl SubAssign<&mut Self> for Fp5 {
+    #[inline]
+    fn sub_assign(&mut self, other: &mut Self) {
+        self.sub_assign(&*other);
+    }
+}
+
+impl MulAssign<&mut Self> for Fp5 {
+    #[inline]
+    fn mul_assign(&mut self, other: &mut Self) {
+        self.mul_assign(&*other);
+    }
+}
+
+impl DivAssign<&mut Self> for Fp5 {
+    #[inline]
+    fn div_assign(&mut self, other: &mut Self) {
+        self.div_assign(&*other);
+    }
+}
+
+impl AddAssign<Self> for Fp5 {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.add_assign(&other);
+    }
+}
+
+impl SubAssign<Self> for Fp5 {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.sub_assign(&other);
+    }
+}
+
+impl MulAssign<Self> for Fp5 {
+    #[inline]
+    fn mul_assign(&mut self, other: Self) {
+        self.mul_assign(&other);
+    }
+}
+
+impl DivAssign<Self> for Fp5 {
+    #[inline]
+    fn div_assign(&mut self, other: Self) {
+        self.div_assign(&other);
+    }
+}
+
+impl Add<Self> for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: Self) -> Self {
+        self.add_assign(&other);
+        self
+    }
+}
+
+impl Sub<Self> for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: Self) -> Self {
+        self.sub_assign(&other);
+        self
+    }
+}
+
+impl Mul<Self> for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: Self) -> Self {
+        self.mul_assign(&other);
+        self
+    }
+}
+
+impl Div<Self> for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn div(mut self, other: Self) -> Self {
+        self.div_assign(&other);
+        self
+    }
+}
+
+impl Add<&mut Self> for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: &mut Self) -> Self {
+        self.add_assign(&*other);
+        self
+    }
+}
+
+impl Sub<&mut Self> for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: &mut Self) -> Self {
+        self.sub_assign(&*other);
+        self
+    }
+}
+
+impl Mul<&mut Self> for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: &mut Self) -> Self {
+        self.mul_assign(&*other);
+        self
+    }
+}
+
+impl Div<&mut Self> for Fp5 {
+    type Output = Self;
+
+    #[inline]
+    fn div(mut self, other: &mut Self) -> Self {
+        self.div_assign(&*other);
+        self
+    }
+}
+
+impl Product<Self> for Fp5 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), core::ops::Mul::mul)
+    }
+}
+
+impl<'a> Product<&'a Self> for Fp5 {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+impl Sum<Self> for Fp5 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), core::ops::Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Self> for Fp5 {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), core::ops::Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sum_of_products, Fp, Fp5 as TestField, MODULUS};
+    use ark_algebra_test_templates::test_field;
+    use ark_ff::{Field, One, Zero};
+    use ark_std::{test_rng, UniformRand};
+    use num_bigint::BigUint;
+
+    test_field!(generated; TestField; fft);
+
+    fn from_raw(value: u64) -> Fp {
+        ark_ff::Fp(ark_ff::BigInt([value]), core::marker::PhantomData)
+    }
+
+    #[test]
+    fn sum_of_products_with_large_operands() {
+        let a = [18_446_744_069_414_369_935, 18_446_744_069_413_918_622].map(from_raw);
+        let b = [18_446_744_022_038_561_060, 18_446_744_049_909_366_242].map(from_raw);
+        assert_eq!(sum_of_products(a, b), a[0] * b[0] + a[1] * b[1]);
+
+        // a product close to `p^2` plus one close to `2^97` lands just below
+        // `2^128` where the high limb is at least `p`
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            let near_p = |rng: &mut _| from_raw(MODULUS - 1 - u64::rand(rng) % (1 << 20));
+            let near_2_48 = |rng: &mut _| from_raw((1 << 48) + u64::rand(rng) % (1 << 48));
+            let a = [near_p(&mut rng), near_2_48(&mut rng)];
+            let b = [near_p(&mut rng), near_2_48(&mut rng)];
+            let expected: BigUint = a
+                .iter()
+                .zip(&b)
+                .map(|(a, b)| BigUint::from(*a) * BigUint::from(*b))
+                .sum();
+            assert_eq!(sum_of_products(a, b), Fp::from(expected));
+        }
+    }
+
+    #[test]
+    fn z_is_root_of_modulus() {
+        let z = TestField::new(Fp::zero(), Fp::one(), Fp::zero(), Fp::zero(), Fp::zero());
+        assert_eq!(z.pow([5]), TestField::from(Fp::from(3u8)));
+        assert_eq!(z.mul_by_z(), z.square());
+    }
+
+    #[test]
+    fn norm_is_product_of_conjugates() {
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            let a = TestField::rand(&mut rng);
+            let norm = (0..5).map(|i| a.frobenius_map(i)).product::<TestField>();
+            assert_eq!(norm, TestField::from(a.norm()));
+        }
+    }
+
+    #[test]
+    fn sqrt() {
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            let a = TestField::rand(&mut rng);
+            let b = a.square();
+            assert!(b.legendre().is_qr());
+            let root = b.sqrt().unwrap();
+            assert!(root == a || root == -a);
+            if a.legendre().is_qnr() {
+                assert!(a.sqrt().is_none());
+            }
+        }
+        assert_eq!(TestField::zero().sqrt(), Some(TestField::zero()));
+    }
+}
@@ -0,0 +1,644 @@
+//! The FRI low degree test over [`Fp`] with challenges in an extension field.
+//!
+//! The prover commits to the evaluations of a polynomial `f` of degree below
+//! `d` on the coset `g * <ω>` of size `n = d * 2^log_blowup`, where `g` is the
+//! multiplicative generator. [`lde`] computes such evaluations with a coset
+//! NTT. Writing `f(x) = sum_k x^k f_k(x^a)` for the folding arity `a`, each
+//! round folds the current layer into the evaluations of
+//! `sum_k β^k f_k(y)` on the domain `{x^a}`, which is `a` times smaller, for a
+//! challenge `β` drawn from the transcript. The `a` values of a layer that fold
+//! into the same value are the points `x * ζ^j` of a coset of the `a`-th roots
+//! of unity `ζ^j`. They are at indices `i + j * n / a` in natural order and
+//! form leaf `i` of the layer's Merkle tree, so a query opens a single leaf per
+//! layer.
+//!
+//! Folding stops once the degree bound is at most `max_remainder_degree + 1`
+//! or below the arity. The prover then sends the coefficients of the final
+//! layer instead of committing to it. After an optional proof of work the
+//! verifier draws query positions, recomputes the folded values from the
+//! opened cosets and checks them against the next layer and finally against
+//! the remainder polynomial.
+//!
+//! Challenges live in any field `E` with base prime field [`Fp`], such as
+//! [`Fp`] itself or the quadratic, cubic and quintic extensions. The input
+//! layer is committed as [`Fp`] values and folded layers as the coefficients
+//! of their `E` values. The commitments, the transcript and the openings are
+//! handled by [`crate::fri`].
+
+use super::{ntt, Fp};
+pub use crate::fri::{FriError, FriQueries};
+use crate::{
+    fri::{
+        commit, finish_commit_phase, open, open_layers, opened_cosets, replay_commit_phase,
+        verify_layers,
+    },
+    merkle::{MerkleHasher, MerkleTree},
+    transcript::{DuplexPermutation, Transcript},
+};
+use ark_ff::{FftField, Field};
+use ark_std::vec::Vec;
+
+/// Parameters of the FRI protocol shared by the prover and verifier
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FriOptions {
+    log_blowup: u32,
+    folding_arity: usize,
+    num_queries: usize,
+    max_remainder_degree: usize,
+    proof_of_work_bits: u32,
+}
+
+impl FriOptions {
+    /// Returns `None` unless `log_blowup` is positive, the folding arity is 2,
+    /// 4 or 8, there is at least one query and the proof of work has fewer
+    /// than 32 bits.
+    #[must_use]
+    pub const fn new(
+        log_blowup: u32,
+        folding_arity: usize,
+        num_queries: usize,
+        max_remainder_degree: usize,
+        proof_of_work_bits: u32,
+    ) -> Option<Self> {
+        if log_blowup == 0
+            || !matches!(folding_arity, 2 | 4 | 8)
+            || num_queries == 0
+            || proof_of_work_bits >= 32
+        {
+            return None;
+        }
+        Some(Self {
+            log_blowup,
+            folding_arity,
+            num_queries,
+            max_remainder_degree,
+            proof_of_work_bits,
+        })
+    }
+
+    #[must_use]
+    pub const fn log_blowup(&self) -> u32 {
+        self.log_blowup
+    }
+
+    #[must_use]
+    pub const fn folding_arity(&self) -> usize {
+        self.folding_arity
+    }
+
+    #[must_use]
+    pub const fn num_queries(&self) -> usize {
+        self.num_queries
+    }
+
+    /// Returns the number of committed layers for an input of `domain_size`
+    /// evaluations, or `None` if the domain is not a power of two between
+    /// `2^log_blowup` and `2^32` or no folding round would take place.
+    #[must_use]
+    pub const fn num_layers(&self, domain_size: usize) -> Option<usize> {
+        if !domain_size.is_power_of_two()
+            || domain_size.trailing_zeros() > Fp::TWO_ADICITY
+            || domain_size.trailing_zeros() < self.log_blowup
+        {
+            return None;
+        }
+        let mut degree_bound = domain_size >> self.log_blowup;
+        let mut num_layers = 0;
+        while degree_bound > self.max_remainder_degree + 1 && degree_bound >= self.folding_arity {
+            degree_bound /= self.folding_arity;
+            num_layers += 1;
+        }
+        if num_layers == 0 {
+            None
+        } else {
+            Some(num_layers)
+        }
+    }
+}
+
+/// A FRI proof with challenges in `E` and Merkle digests `D`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FriProof<E, D> {
+    /// Merkle roots of the input followed by the folded layers
+    pub commitments: Vec<D>,
+    pub input_queries: FriQueries<Fp, D>,
+    pub layer_queries: Vec<FriQueries<E, D>>,
+    /// Coefficients of the final layer, lowest degree first
+    pub remainder: Vec<E>,
+    pub proof_of_work: Fp,
+}
+
+/// Evaluates the polynomial with `coefficients` on the coset
+/// `g * <ω>` of size `coefficients.len() * 2^log_blowup`.
+///
+/// # Panics
+///
+/// Panics if the domain size is not a power of two of at most `2^32`.
+#[must_use]
+pub fn lde(coefficients: &[Fp], log_blowup: u32) -> Vec<Fp> {
+    let mut values = coefficients.to_vec();
+    values.resize(coefficients.len() << log_blowup, Fp::ZERO);
+    assert!(
+        values.len().is_power_of_two(),
+        "size must be a power of two"
+    );
+    ntt::coset_ntt(&mut values, Fp::GENERATOR);
+    values
+}
+
+/// Proves that `evaluations` on the coset `g * <ω>` are close to a polynomial
+/// of degree below `evaluations.len() / 2^log_blowup`.
+///
+/// # Panics
+///
+/// Panics if [`FriOptions::num_layers`] rejects the number of evaluations.
+pub fn prove<E, H, P, const WIDTH: usize>(
+    options: &FriOptions,
+    hasher: &H,
+    transcript: &mut Transcript<P, WIDTH>,
+    evaluations: &[Fp],
+) -> FriProof<E, H::Digest>
+where
+    E: Field<BasePrimeField = Fp>,
+    H: MerkleHasher<Field = Fp> + Clone,
+    H::Digest: AsRef<[Fp]>,
+    P: DuplexPermutation<WIDTH, Field = Fp> + Clone,
+{
+    let domain_size = evaluations.len();
+    let num_layers = options
+        .num_layers(domain_size)
+        .expect("unsupported domain size");
+    let arity = options.folding_arity;
+
+    let input_tree = commit(hasher, transcript, evaluations, arity);
+    let beta: E = transcript.squeeze_extension();
+    let mut offset = Fp::GENERATOR;
+    let lifted: Vec<E> = evaluations
+        .iter()
+        .map(|&value| E::from_base_prime_field(value))
+        .collect();
+    let mut layer = fold_layer(&lifted, offset, arity, beta);
+    offset = offset.pow([arity as u64]);
+    let mut layer_trees = Vec::with_capacity(num_layers - 1);
+    for _ in 1..num_layers {
+        let tree = commit(hasher, transcript, &layer, arity);
+        let beta: E = transcript.squeeze_extension();
+        layer = fold_layer(&layer, offset, arity, beta);
+        offset = offset.pow([arity as u64]);
+        layer_trees.push(tree);
+    }
+
+    let degree_bound = layer.len() >> options.log_blowup;
+    let mut remainder = interpolate(&layer, offset);
+    remainder.truncate(degree_bound);
+    let num_leaves = domain_size / arity;
+    let (proof_of_work, positions) = finish_commit_phase(
+        transcript,
+        &remainder,
+        options.proof_of_work_bits,
+        options.num_queries,
+        num_leaves,
+    );
+
+    let commitments = core::iter::once(&input_tree)
+        .chain(&layer_trees)
+        .map(MerkleTree::root)
+        .collect();
+    let input_queries = open(&input_tree, &positions, arity);
+    let layer_queries = open_layers(&layer_trees, positions, num_leaves, arity);
+
+    FriProof {
+        commitments,
+        input_queries,
+        layer_queries,
+        remainder,
+        proof_of_work,
+    }
+}
+
+/// Verifies a proof that the committed evaluations on a coset of size
+/// `domain_size` are close to a polynomial of degree below
+/// `domain_size / 2^log_blowup`.
+///
+/// # Errors
+///
+/// Returns a [`FriError`] describing the first check that fails.
+pub fn verify<E, H, P, const WIDTH: usize>(
+    options: &FriOptions,
+    hasher: &H,
+    transcript: &mut Transcript<P, WIDTH>,
+    domain_size: usize,
+    proof: &FriProof<E, H::Digest>,
+) -> Result<(), FriError>
+where
+    E: Field<BasePrimeField = Fp>,
+    H: MerkleHasher<Field = Fp>,
+    H::Digest: AsRef<[Fp]>,
+    P: DuplexPermutation<WIDTH, Field = Fp> + Clone,
+{
+    let num_layers = options
+        .num_layers(domain_size)
+        .ok_or(FriError::InvalidDomainSize(domain_size))?;
+    let arity = options.folding_arity;
+    let final_size = (0..num_layers).fold(domain_size, |size, _| size / arity);
+    if proof.commitments.len() != num_layers
+        || proof.layer_queries.len() != num_layers - 1
+        || proof.remainder.len() != final_size >> options.log_blowup
+    {
+        return Err(FriError::InvalidProofShape);
+    }
+
+    let num_leaves = domain_size / arity;
+    let (betas, mut positions) = replay_commit_phase(
+        transcript,
+        &proof.commitments,
+        &proof.remainder,
+        options.proof_of_work_bits,
+        proof.proof_of_work,
+        options.num_queries,
+        num_leaves,
+    )?;
+
+    // the coset offset of every layer and of the remainder
+    let offsets: Vec<Fp> = core::iter::successors(Some(Fp::GENERATOR), |offset| {
+        Some(offset.pow([arity as u64]))
+    })
+    .take(num_layers + 1)
+    .collect();
+    let cosets = opened_cosets(
+        0,
+        hasher,
+        &proof.commitments[0],
+        &proof.input_queries,
+        &positions,
+        num_leaves,
+        arity,
+    )?;
+    let mut folded: Vec<E> = positions
+        .iter()
+        .zip(cosets)
+        .map(|(&position, coset)| {
+            let coset: Vec<E> = coset
+                .iter()
+                .map(|&value| E::from_base_prime_field(value))
+                .collect();
+            fold_coset(&coset, offsets[0], position, num_leaves, betas[0])
+        })
+        .collect();
+    verify_layers(
+        hasher,
+        &proof.commitments[1..],
+        &proof.layer_queries,
+        &mut positions,
+        &mut folded,
+        num_leaves,
+        arity,
+        |layer, coset, position, num_leaves| {
+            fold_coset(coset, offsets[layer], position, num_leaves, betas[layer])
+        },
+    )?;
+
+    for (&position, value) in positions.iter().zip(&folded) {
+        let x = domain_point(offsets[num_layers], final_size, position);
+        let expected = proof.remainder.iter().rfold(E::ZERO, |acc, coefficient| {
+            acc * E::from_base_prime_field(x) + coefficient
+        });
+        if *value != expected {
+            return Err(FriError::RemainderMismatch { position });
+        }
+    }
+    Ok(())
+}
+
+/// Folds the evaluations on the coset `offset * <ω>` into evaluations on
+/// `offset^a * <ω^a>`.
+fn fold_layer<E>(values: &[E], offset: Fp, arity: usize, beta: E) -> Vec<E>
+where
+    E: Field<BasePrimeField = Fp>,
+{
+    let num_leaves = values.len() / arity;
+    let generator_inv = ntt::root_of_unity(values.len() as u64)
+        .unwrap()
+        .inverse()
+        .unwrap();
+    let mut x_inv = offset.inverse().unwrap();
+    let inv_roots = inverse_roots(arity);
+    let mut coset = [E::ZERO; 8];
+    (0..num_leaves)
+        .map(|i| {
+            for (j, value) in coset[..arity].iter_mut().enumerate() {
+                *value = values[i + j * num_leaves];
+            }
+            let folded = fold(&coset[..arity], x_inv, beta, &inv_roots);
+            x_inv *= generator_inv;
+            folded
+        })
+        .collect()
+}
+
+/// Folds the opened coset of leaf `position` in a layer on `offset * <ω>` with
+/// `num_leaves` leaves.
+fn fold_coset<E>(coset: &[E], offset: Fp, position: usize, num_leaves: usize, beta: E) -> E
+where
+    E: Field<BasePrimeField = Fp>,
+{
+    let arity = coset.len();
+    let x = domain_point(offset, num_leaves * arity, position);
+    fold(coset, x.inverse().unwrap(), beta, &inverse_roots(arity))
+}
+
+/// Returns the point `offset * ω^i` of the coset of size `size`.
+fn domain_point(offset: Fp, size: usize, i: usize) -> Fp {
+    offset * ntt::root_of_unity(size as u64).unwrap().pow([i as u64])
+}
+
+/// Returns `ζ^-m` for `m < arity` where `ζ` is a primitive `arity`-th root of
+/// unity.
+fn inverse_roots(arity: usize) -> [Fp; 8] {
+    let zeta_inv = ntt::root_of_unity(arity as u64).unwrap().inverse().unwrap();
+    let mut roots = [Fp::ONE; 8];
+    for m in 1..arity {
+        roots[m] = roots[m - 1] * zeta_inv;
+    }
+    roots
+}
+
+/// Computes `sum_k β^k f_k(x^a)` from the values `f(x * ζ^j)`.
+///
+/// Since `sum_j f(x * ζ^j) ζ^(-jk) = a * x^k * f_k(x^a)`, the result is
+/// `1/a * sum_k c_k (β / x)^k` for the inverse DFT `c` of the coset values.
+fn fold<E>(coset: &[E], x_inv: Fp, beta: E, inv_roots: &[Fp; 8]) -> E
+where
+    E: Field<BasePrimeField = Fp>,
+{
+    let arity = coset.len();
+    let beta_x_inv = beta * E::from_base_prime_field(x_inv);
+    let mut result = E::ZERO;
+    for k in (0..arity).rev() {
+        let c_k: E = coset
+            .iter()
+            .enumerate()
+            .map(|(j, value)| *value * E::from_base_prime_field(inv_roots[j * k % arity]))
+            .sum();
+        result = result * beta_x_inv + c_k;
+    }
+    let arity_inv = Fp::from(arity as u64).inverse().unwrap();
+    result * E::from_base_prime_field(arity_inv)
+}
+
+/// Interpolates the coefficients of evaluations on `offset * <ω>` by
+/// transforming every base field coordinate.
+fn interpolate<E>(values: &[E], offset: Fp) -> Vec<E>
+where
+    E: Field<BasePrimeField = Fp>,
+{
+    let degree = usize::try_from(E::extension_degree()).unwrap();
+    let mut coordinates: Vec<Vec<Fp>> = (0..degree)
+        .map(|_| Vec::with_capacity(values.len()))
+        .collect();
+    for value in values {
+        for (coordinate, c) in coordinates
+            .iter_mut()
+            .zip(value.to_base_prime_field_elements())
+        {
+            coordinate.push(c);
+        }
+    }
+    for coordinate in &mut coordinates {
+        ntt::coset_intt(coordinate, offset);
+    }
+    (0..values.len())
+        .map(|i| {
+            let coefficients: Vec<Fp> =
+                coordinates.iter().map(|coordinate| coordinate[i]).collect();
+            E::from_base_prime_field_elems(&coefficients).unwrap()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fold_layer, interpolate, lde, prove, verify, FriError, FriOptions, FriProof};
+    use crate::{
+        fp64::{ext2::Fp2, ext3::Fp3, ntt, poseidon2::Poseidon2, Fp},
+        merkle::MerkleError,
+        transcript::Transcript,
+    };
+    use ark_ff::{FftField, Field};
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    type Digest = crate::fp64::poseidon2::Digest;
+
+    fn transcript() -> Transcript<Poseidon2<12>, 12> {
+        Transcript::new(Poseidon2::<12>::new(), b"fri")
+    }
+
+    fn random_coefficients(degree_bound: usize) -> Vec<Fp> {
+        let mut rng = test_rng();
+        (0..degree_bound).map(|_| Fp::rand(&mut rng)).collect()
+    }
+
+    fn prove_and_verify<E: Field<BasePrimeField = Fp>>(
+        options: &FriOptions,
+        evaluations: &[Fp],
+    ) -> Result<(), FriError> {
+        let hasher = Poseidon2::<8>::new();
+        let proof: FriProof<E, Digest> = prove(options, &hasher, &mut transcript(), evaluations);
+        verify(
+            options,
+            &hasher,
+            &mut transcript(),
+            evaluations.len(),
+            &proof,
+        )
+    }
+
+    #[test]
+    fn lde_evaluates_on_coset() {
+        let coefficients = random_coefficients(8);
+        let evaluations = lde(&coefficients, 2);
+        let generator = ntt::root_of_unity(32).unwrap();
+        for (i, value) in evaluations.iter().enumerate() {
+            let x = Fp::GENERATOR * generator.pow([i as u64]);
+            let expected = coefficients
+                .iter()
+                .rfold(Fp::ZERO, |acc, coefficient| acc * x + coefficient);
+            assert_eq!(*value, expected);
+        }
+    }
+
+    #[test]
+    fn folding_divides_degree() {
+        let mut rng = test_rng();
+        let coefficients = random_coefficients(64);
+        let values: Vec<Fp2> = lde(&coefficients, 1).into_iter().map(Fp2::from).collect();
+        for arity in [2, 4, 8] {
+            let beta = Fp2::rand(&mut rng);
+            let folded = fold_layer(&values, Fp::GENERATOR, arity, beta);
+            assert_eq!(folded.len(), 128 / arity);
+            let offset = Fp::GENERATOR.pow([arity as u64]);
+            let folded_coefficients = interpolate(&folded, offset);
+            // `sum_k β^k f_k` has the coefficients `sum_k β^k c_(ai + k)`
+            for (i, coefficient) in folded_coefficients.iter().enumerate() {
+                let expected: Fp2 = (0..arity)
+                    .map(|k| {
+                        let c = coefficients.get(arity * i + k).copied().unwrap_or_default();
+                        beta.pow([k as u64]) * Fp2::from(c)
+                    })
+                    .sum();
+                assert_eq!(*coefficient, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn options_validate_parameters() {
+        assert!(FriOptions::new(0, 2, 10, 0, 0).is_none());
+        assert!(FriOptions::new(1, 3, 10, 0, 0).is_none());
+        assert!(FriOptions::new(1, 16, 10, 0, 0).is_none());
+        assert!(FriOptions::new(1, 2, 0, 0, 0).is_none());
+        assert!(FriOptions::new(1, 2, 10, 0, 32).is_none());
+
+        let options = FriOptions::new(2, 4, 10, 3, 0).unwrap();
+        // degree bound 256 is folded to 64, 16 and 4
+        assert_eq!(options.num_layers(1024), Some(3));
+        assert_eq!(options.num_layers(16), None);
+        assert_eq!(options.num_layers(1000), None);
+        assert_eq!(options.num_layers(2), None);
+        // degree bound 32 stops at 2 since 2 is below the arity
+        let options = FriOptions::new(1, 4, 10, 0, 0).unwrap();
+        assert_eq!(options.num_layers(64), Some(2));
+    }
+
+    #[test]
+    fn honest_proofs_verify() {
+        for arity in [2, 4, 8] {
+            for log_blowup in [1, 3] {
+                let options = FriOptions::new(log_blowup, arity, 20, 7, 4).unwrap();
+                let evaluations = lde(&random_coefficients(256), log_blowup);
+                assert_eq!(prove_and_verify::<Fp>(&options, &evaluations), Ok(()));
+                assert_eq!(prove_and_verify::<Fp2>(&options, &evaluations), Ok(()));
+                assert_eq!(prove_and_verify::<Fp3>(&options, &evaluations), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn lower_degree_proofs_verify() {
+        let options = FriOptions::new(2, 8, 20, 0, 0).unwrap();
+        let mut coefficients = random_coefficients(10);
+        coefficients.resize(512, Fp::ZERO);
+        let evaluations = lde(&coefficients, 2);
+        assert_eq!(prove_and_verify::<Fp2>(&options, &evaluations), Ok(()));
+    }
+
+    #[test]
+    fn rejects_high_degree() {
+        let options = FriOptions::new(2, 2, 30, 0, 0).unwrap();
+        // adding `x^256` exceeds the degree bound 256
+        let mut evaluations = lde(&random_coefficients(256), 2);
+        let x = Fp::GENERATOR;
+        let generator = ntt::root_of_unity(1024).unwrap();
+        for (i, value) in evaluations.iter_mut().enumerate() {
+            *value += (x * generator.pow([i as u64])).pow([256]);
+        }
+        assert!(prove_and_verify::<Fp2>(&options, &evaluations).is_err());
+
+        let mut rng = test_rng();
+        let random: Vec<Fp> = (0..1024).map(|_| Fp::rand(&mut rng)).collect();
+        assert!(prove_and_verify::<Fp2>(&options, &random).is_err());
+    }
+
+    fn honest_proof(options: &FriOptions, evaluations: &[Fp]) -> FriProof<Fp2, Digest> {
+        prove(
+            options,
+            &Poseidon2::<8>::new(),
+            &mut transcript(),
+            evaluations,
+        )
+    }
+
+    fn check(
+        options: &FriOptions,
+        domain_size: usize,
+        proof: &FriProof<Fp2, Digest>,
+    ) -> Result<(), FriError> {
+        verify(
+            options,
+            &Poseidon2::<8>::new(),
+            &mut transcript(),
+            domain_size,
+            proof,
+        )
+    }
+
+    #[test]
+    fn rejects_tampered_proofs() {
+        let options = FriOptions::new(2, 4, 16, 3, 6).unwrap();
+        let evaluations = lde(&random_coefficients(256), 2);
+        let proof = honest_proof(&options, &evaluations);
+        assert_eq!(check(&options, 1024, &proof), Ok(()));
+
+        assert_eq!(
+            check(&options, 512, &proof),
+            Err(FriError::InvalidProofShape)
+        );
+        assert_eq!(
+            check(&options, 1000, &proof),
+            Err(FriError::InvalidDomainSize(1000))
+        );
+
+        let mut tampered = proof.clone();
+        tampered.remainder[0] += Fp2::ONE;
+        assert!(check(&options, 1024, &tampered).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.remainder.push(Fp2::ZERO);
+        assert_eq!(
+            check(&options, 1024, &tampered),
+            Err(FriError::InvalidProofShape)
+        );
+
+        let mut tampered = proof.clone();
+        tampered.proof_of_work += Fp::ONE;
+        assert!(check(&options, 1024, &tampered).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.commitments[1][0] += Fp::ONE;
+        assert!(check(&options, 1024, &tampered).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.input_queries.cosets[0][1] += Fp::ONE;
+        assert_eq!(
+            check(&options, 1024, &tampered),
+            Err(FriError::InvalidOpening {
+                layer: 0,
+                error: MerkleError::RootMismatch
+            })
+        );
+
+        let mut tampered = proof.clone();
+        tampered.layer_queries[1].cosets[0][0] += Fp2::ONE;
+        assert_eq!(
+            check(&options, 1024, &tampered),
+            Err(FriError::InvalidOpening {
+                layer: 2,
+                error: MerkleError::RootMismatch
+            })
+        );
+
+        let mut tampered = proof.clone();
+        tampered.layer_queries[0].cosets[0].pop();
+        assert_eq!(
+            check(&options, 1024, &tampered),
+            Err(FriError::InvalidProofShape)
+        );
+
+        let mut tampered = proof;
+        tampered.layer_queries.pop();
+        assert_eq!(
+            check(&options, 1024, &tampered),
+            Err(FriError::InvalidProofShape)
+        );
+    }
+}
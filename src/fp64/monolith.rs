@@ -0,0 +1,419 @@
+//! The Monolith permutation over [`Fp`] with widths 8 and 12 and a 2-to-1
+//! compression function producing 4 element digests.
+//!
+//! Monolith (<https://eprint.iacr.org/2023/1025>) replaces the power map of
+//! Poseidon with a lookup based S-box that is cheap both natively and in
+//! arithmetic circuits. A round applies three layers:
+//!
+//! - Bars decomposes each of the first 4 elements into 8 bytes, maps every
+//!   byte with the chi-like S-box `y ^ (!y <<< 1 & y <<< 2 & y <<< 3)` followed
+//!   by a rotation by 1 and recombines the bytes. All 8 bytes are handled in
+//!   one `u64` with SIMD within a register.
+//! - Bricks is the Feistel layer `x_i += x_(i-1)^2` for `i > 0`, computed
+//!   from the last element down so every square uses the old value.
+//! - Concrete multiplies by a circulant MDS matrix with small entries.
+//!
+//! The permutation applies Concrete, 5 rounds that add round constants after
+//! Concrete and a final round without constants. The round constants are
+//! sampled with SHAKE-128 as in the paper, following the Horizen Labs
+//! reference implementation (<https://github.com/HorizenLabs/monolith>).
+
+use super::{into_mont, mont_red, reduce_u96, Fp};
+use ark_ff::Field;
+
+/// Number of rounds with round constants. The permutation has one more round
+/// without constants.
+const NUM_FULL_ROUNDS: usize = 5;
+
+/// Number of elements passed through the Bars layer
+const NUM_BARS: usize = 4;
+
+/// Number of elements in a digest
+pub const DIGEST_LEN: usize = 4;
+
+/// A compression output
+pub type Digest = [Fp; DIGEST_LEN];
+
+/// The round constants and MDS matrix of a Monolith instance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Monolith<const WIDTH: usize> {
+    round_constants: [[Fp; WIDTH]; NUM_FULL_ROUNDS],
+    /// First row of the circulant Concrete matrix
+    mds_row: [u64; WIDTH],
+}
+
+impl Monolith<8> {
+    /// Returns the width 8 instance.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            round_constants: to_fp_rows(ROUND_CONSTANTS_8),
+            mds_row: [23, 8, 13, 10, 7, 6, 21, 8],
+        }
+    }
+}
+
+impl Default for Monolith<8> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Monolith<12> {
+    /// Returns the width 12 instance.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            round_constants: to_fp_rows(ROUND_CONSTANTS_12),
+            mds_row: [7, 23, 8, 26, 13, 10, 9, 7, 6, 22, 21, 8],
+        }
+    }
+}
+
+impl Default for Monolith<12> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WIDTH: usize> Monolith<WIDTH> {
+    /// Applies the permutation to `state` in place.
+    pub fn permute(&self, state: &mut [Fp; WIDTH]) {
+        self.concrete(state);
+        for constants in &self.round_constants {
+            bars(state);
+            bricks(state);
+            self.concrete(state);
+            for (value, constant) in state.iter_mut().zip(constants) {
+                *value += constant;
+            }
+        }
+        bars(state);
+        bricks(state);
+        self.concrete(state);
+    }
+
+    /// Compresses two digests by truncating the permutation of `left || right`,
+    /// which width 12 pads with zeros.
+    #[must_use]
+    pub fn compress(&self, left: &Digest, right: &Digest) -> Digest {
+        crate::compression::truncated_permutation(|state| self.permute(state), left, right)
+    }
+
+    /// Multiplies by the circulant matrix with first row `mds_row`, so
+    /// `x'_i = sum_j mds_row[(j - i) mod WIDTH] * x_j`.
+    ///
+    /// The matrix has integer entries so it is applied to the Montgomery
+    /// representations directly. These are split into 32-bit halves whose
+    /// weighted sums stay below `2^41` as the entries are at most 26 and the
+    /// width at most 12. Recombining them gives a value below `2^96` that
+    /// [`reduce_u96`] reduces.
+    #[inline]
+    fn concrete(&self, state: &mut [Fp; WIDTH]) {
+        let mut lo = [0u64; WIDTH];
+        let mut hi = [0u64; WIDTH];
+        for (j, value) in state.iter().enumerate() {
+            let limb = (value.0).0[0];
+            lo[j] = limb & 0xFFFF_FFFF;
+            hi[j] = limb >> 32;
+        }
+        for (i, value) in state.iter_mut().enumerate() {
+            let mut sum_lo = 0;
+            let mut sum_hi = 0;
+            for j in 0..WIDTH {
+                let coefficient = self.mds_row[(WIDTH + j - i) % WIDTH];
+                sum_lo += coefficient * lo[j];
+                sum_hi += coefficient * hi[j];
+            }
+            let sum = u128::from(sum_lo) + (u128::from(sum_hi) << 32);
+            (value.0).0[0] = reduce_u96(sum);
+        }
+    }
+}
+
+/// Applies [`bar`] to the canonical value of the first [`NUM_BARS`]
+/// elements. The S-box fixes the bytes `0x00` and `0xFF`, so a value below `p`
+/// stays below `p` and only the Montgomery form is converted.
+#[inline]
+fn bars<const WIDTH: usize>(state: &mut [Fp; WIDTH]) {
+    for value in &mut state[..NUM_BARS] {
+        *value = into_mont(bar(mont_red(u128::from((value.0).0[0]))));
+    }
+}
+
+/// Applies the byte S-box `(y ^ (!(y <<< 1) & (y <<< 2) & (y <<< 3))) <<< 1`
+/// to the 8 bytes of `x` at once. The rotations are done per byte by shifting
+/// the masked high and low bits of every byte separately.
+#[inline]
+const fn bar(x: u64) -> u64 {
+    let rot1 = ((!x & 0x8080_8080_8080_8080) >> 7) | ((!x & 0x7F7F_7F7F_7F7F_7F7F) << 1);
+    let rot2 = ((x & 0xC0C0_C0C0_C0C0_C0C0) >> 6) | ((x & 0x3F3F_3F3F_3F3F_3F3F) << 2);
+    let rot3 = ((x & 0xE0E0_E0E0_E0E0_E0E0) >> 5) | ((x & 0x1F1F_1F1F_1F1F_1F1F) << 3);
+    let tmp = x ^ (rot1 & rot2 & rot3);
+    ((tmp & 0x8080_8080_8080_8080) >> 7) | ((tmp & 0x7F7F_7F7F_7F7F_7F7F) << 1)
+}
+
+/// Computes `x_i += x_(i-1)^2` for `i > 0` using the values from before the
+/// layer.
+#[inline]
+fn bricks<const WIDTH: usize>(state: &mut [Fp; WIDTH]) {
+    for i in (1..WIDTH).rev() {
+        state[i] += state[i - 1].square();
+    }
+}
+
+const fn to_fp_rows<const N: usize>(
+    rows: [[u64; N]; NUM_FULL_ROUNDS],
+) -> [[Fp; N]; NUM_FULL_ROUNDS] {
+    let mut res = [[into_mont(0); N]; NUM_FULL_ROUNDS];
+    let mut i = 0;
+    while i < NUM_FULL_ROUNDS {
+        let mut j = 0;
+        while j < N {
+            res[i][j] = into_mont(rows[i][j]);
+            j += 1;
+        }
+        i += 1;
+    }
+    res
+}
+
+const ROUND_CONSTANTS_8: [[u64; 8]; NUM_FULL_ROUNDS] = [
+    [
+        0xe17b_45f7_a0e7_7938,
+        0x30ac_9482_1d25_5622,
+        0xb438_3b85_ff89_2048,
+        0xb7cb_af7a_8060_079c,
+        0xe48b_5c05_9aa3_cc13,
+        0x5701_c6c7_f5ad_517e,
+        0x5e22_216e_9149_af97,
+        0x731b_73ae_a805_b8cb,
+    ],
+    [
+        0x815a_9e5d_8281_cbff,
+        0xc5c7_301b_cfa3_3182,
+        0xf2be_533a_5d1a_cbb1,
+        0xb0c1_da06_c58d_8877,
+        0xa0ed_9bf9_4b90_03a2,
+        0xea15_28a4_6bb4_c87d,
+        0xcaeb_34e1_5151_110a,
+        0x49ae_33ef_b41f_12a9,
+    ],
+    [
+        0x5f09_e7a9_9af7_1157,
+        0xa018_dd62_2a98_21c5,
+        0xaf8d_8d2a_c0d2_c0b2,
+        0x4b7c_cd2e_47ec_5160,
+        0xe215_ddb1_e29c_0644,
+        0xe2c8_d8ca_25c0_3842,
+        0xe2e6_9739_6c9a_8e2c,
+        0xcf61_2209_95ce_459b,
+    ],
+    [
+        0xc87d_d0c7_49ea_bcf0,
+        0x33d7_ead7_27cf_0078,
+        0x1acb_c9df_3bae_9c86,
+        0xe2c3_fab2_4dee_5fbe,
+        0x1aa6_90f3_5ed9_86e4,
+        0xf7a5_3653_e473_223f,
+        0xef92_8a15_bbbf_ced8,
+        0xc9c0_b481_654b_fccc,
+    ],
+    [
+        0xd651_5be6_f439_bd40,
+        0x1549_799e_7433_cb20,
+        0x9bc2_1f66_850c_47b9,
+        0x9691_c6a6_63d9_421c,
+        0x2d34_317a_bda4_3681,
+        0x9a96_b70a_5727_e624,
+        0xe618_c6c7_67af_3abe,
+        0x17e6_33f2_7ba8_81b5,
+    ],
+];
+
+const ROUND_CONSTANTS_12: [[u64; 12]; NUM_FULL_ROUNDS] = [
+    [
+        0xbcaf_2516_e592_6dcf,
+        0x4ec5_a76b_ce1e_7676,
+        0x9d80_4725_bebb_56ab,
+        0x2ec0_5fca_215a_5be3,
+        0xe162_74e4_acab_86a0,
+        0x80b0_fddc_c3c4_380f,
+        0xc87c_769a_d77f_fece,
+        0x37f8_5ec9_117d_287c,
+        0x3b8d_825b_014c_458d,
+        0xb7a0_1d0c_b850_d75e,
+        0x1333_b751_bac7_04bd,
+        0x7b7e_f141_83d4_7b6f,
+    ],
+    [
+        0x2114_5176_43e3_b286,
+        0x542d_15ea_3cd1_2ade,
+        0xe847_d363_f17a_93e9,
+        0x24f0_421c_6ff4_1c56,
+        0x66e3_eda9_3e2c_a216,
+        0xfb88_d475_279c_b568,
+        0x7f42_1c62_6993_8a22,
+        0xdbb9_73ac_ce85_7401,
+        0xe172_409c_b156_3a6a,
+        0x996f_729f_6340_447d,
+        0x925c_5797_38b6_fa4a,
+        0x752e_9ec9_e0b3_4686,
+    ],
+    [
+        0xdb41_9e0b_d384_69bd,
+        0xba41_cee8_28bd_26d8,
+        0xd663_0f8f_0969_db39,
+        0x2340_e955_ae2f_0d94,
+        0x282f_553d_3587_2e2e,
+        0x77f7_c3ff_1ae4_96b3,
+        0xf5f2_efab_64bc_5eef,
+        0x47b2_3a00_8302_84f4,
+        0x0e18_a2d2_2424_86fa,
+        0x3d10_1838_a773_dab0,
+        0x47d6_86fd_1685_6524,
+        0x3eb2_d254_189b_3534,
+    ],
+    [
+        0xfe88_6e29_1ca8_c5bd,
+        0xb97e_c74d_f1e4_b0b6,
+        0x574f_def3_a600_e370,
+        0x8ad6_1c6f_132d_4feb,
+        0x41e6_9ca4_ecc7_e8c7,
+        0x151a_d562_e1f9_0ca4,
+        0x747c_0514_39a5_603c,
+        0x9901_51d3_e52d_502c,
+        0x532c_7f25_8282_ea12,
+        0x065e_62cb_3427_5dd5,
+        0x5288_0089_54f5_d0b2,
+        0xee7c_3407_cf3d_6e02,
+    ],
+    [
+        0xda07_0298_08ba_d5de,
+        0x7beb_df38_dcc7_a673,
+        0x20a3_f252_688c_312d,
+        0x9c52_48f7_bbf8_d188,
+        0xcf1c_f778_9943_82d4,
+        0x8c43_4b17_38b8_338c,
+        0xfe50_4398_813b_67a8,
+        0xe879_562f_def8_13b9,
+        0xd466_6793_b2a2_f191,
+        0xd909_6b87_de22_de01,
+        0xcaf4_cea5_f22a_bf34,
+        0x3128_d1e7_5d02_04fa,
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{bar, bricks, Monolith};
+    use crate::fp64::Fp;
+    use ark_ff::Field;
+    use ark_std::{test_rng, UniformRand};
+
+    /// Applies the S-box to every byte separately.
+    fn bar_bytewise(x: u64) -> u64 {
+        let bytes = x.to_le_bytes().map(|y| {
+            (y ^ (!y.rotate_left(1) & y.rotate_left(2) & y.rotate_left(3))).rotate_left(1)
+        });
+        u64::from_le_bytes(bytes)
+    }
+
+    #[test]
+    fn bar_fixed_points() {
+        assert_eq!(bar(0), 0);
+        assert_eq!(bar(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn bar_matches_bytewise() {
+        let mut rng = test_rng();
+        for x in [0x0102_0304_0506_0708, 0xDEAD_BEEF_CAFE_BABE, 1 << 63] {
+            assert_eq!(bar(x), bar_bytewise(x));
+        }
+        for _ in 0..100 {
+            let x = u64::rand(&mut rng);
+            assert_eq!(bar(x), bar_bytewise(x));
+        }
+    }
+
+    #[test]
+    fn bricks_squares_previous_elements() {
+        let mut rng = test_rng();
+        let state: [Fp; 12] = core::array::from_fn(|_| Fp::rand(&mut rng));
+        let mut actual = state;
+        bricks(&mut actual);
+        assert_eq!(actual[0], state[0]);
+        for i in 1..12 {
+            assert_eq!(actual[i], state[i] + state[i - 1].square());
+        }
+    }
+
+    fn check_concrete<const WIDTH: usize>(monolith: &Monolith<WIDTH>) {
+        let mut rng = test_rng();
+        for _ in 0..10 {
+            let state: [Fp; WIDTH] = core::array::from_fn(|_| Fp::rand(&mut rng));
+            let expected: [Fp; WIDTH] = core::array::from_fn(|i| {
+                (0..WIDTH)
+                    .map(|j| Fp::from(monolith.mds_row[(WIDTH + j - i) % WIDTH]) * state[j])
+                    .sum()
+            });
+            let mut actual = state;
+            monolith.concrete(&mut actual);
+            assert_eq!(actual, expected);
+        }
+        let mut state = [-Fp::ONE; WIDTH];
+        monolith.concrete(&mut state);
+        let row_sum: u64 = monolith.mds_row.iter().sum();
+        assert_eq!(state, [-Fp::from(row_sum); WIDTH]);
+    }
+
+    #[test]
+    fn concrete_matches_matrix() {
+        check_concrete(&Monolith::<8>::new());
+        check_concrete(&Monolith::<12>::new());
+    }
+
+    /// Regression vector on `[0, 1, ..., 7]`. The reference implementation
+    /// only publishes vectors for width 12, which are checked below.
+    #[test]
+    fn permute_width_8_kat() {
+        let mut state: [Fp; 8] = core::array::from_fn(|i| Fp::from(i as u64));
+        Monolith::<8>::new().permute(&mut state);
+        let expected = [
+            3_656_442_354_255_169_651u64,
+            1_088_199_316_401_146_975,
+            22_941_152_274_975_507,
+            14_434_181_924_633_355_796,
+            6_981_961_052_218_049_719,
+            16_492_720_827_407_246_378,
+            17_986_182_688_944_525_029,
+            9_161_400_698_613_172_623,
+        ]
+        .map(Fp::from);
+        assert_eq!(state, expected);
+    }
+
+    /// Output of the reference implementation on `[0, 1, ..., 11]`
+    #[test]
+    fn permute_width_12_kat() {
+        let mut state: [Fp; 12] = core::array::from_fn(|i| Fp::from(i as u64));
+        Monolith::<12>::new().permute(&mut state);
+        let expected = [
+            5_867_581_605_548_782_913u64,
+            588_867_029_099_903_233,
+            6_043_817_495_575_026_667,
+            805_786_589_926_590_032,
+            9_919_982_299_747_097_782,
+            6_718_641_691_835_914_685,
+            7_951_881_005_429_661_950,
+            15_453_177_927_755_089_358,
+            974_633_365_445_157_727,
+            9_654_662_171_963_364_206,
+            6_281_307_445_101_925_412,
+            13_745_376_999_934_453_119,
+        ]
+        .map(Fp::from);
+        assert_eq!(state, expected);
+    }
+}
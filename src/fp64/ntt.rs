@@ -0,0 +1,488 @@
+//! Number theoretic transforms over [`Fp`].
+//!
+//! Since `p - 1 = 2^32 * 3 * 5 * 17 * 257 * 65537` the transforms support sizes
+//! `n = 2^a * 3^b * 5^c` with `a <= 32` and `b, c <= 1`. Evaluations are in
+//! natural order at the powers of [`root_of_unity(n)`](root_of_unity), which
+//! matches `ark_poly::Radix2EvaluationDomain` and
+//! `ark_poly::MixedRadixEvaluationDomain` for the sizes they support.
+//!
+//! Power-of-two sizes use an iterative radix-2 Cooley-Tukey NTT on bit-reversed
+//! input. The 64th root of unity used here is `8^13 = 2^39`, so every twiddle
+//! in the first six levels is a power of two. These levels multiply with
+//! shifts and a Montgomery reduction instead of a full 64x64-bit
+//! multiplication. Scaling by `1/n` in the inverse transform is a power of two
+//! as well.
+//!
+//! Other sizes first take a radix-3 or radix-5 Cooley-Tukey step and then
+//! transform the interleaved subsequences recursively.
+
+use super::{mont_red, Fp, MODULUS};
+use ark_ff::{BigInt, FftField, Field};
+use ark_std::vec::Vec;
+use core::marker::PhantomData;
+
+/// `log2` of the 64th root of unity `get_root_of_unity(64) = 2^39`
+const LOG_ROOT_OF_UNITY_64: u32 = 39;
+
+/// The multiplicative order of 2 is 192 since `2^96 = -1`
+const ORDER_OF_TWO: u32 = 192;
+
+/// Largest block size whose twiddles are all powers of two
+const MAX_SHIFT_BLOCK_SIZE: usize = 64;
+
+/// Returns the primitive `n`-th root of unity or `None` if `n` does not divide
+/// `p - 1`.
+///
+/// The root is `g^((p - 1) / n)` for the generator `g` of the multiplicative
+/// group. This agrees with `Fp::get_root_of_unity(n)` wherever the latter is
+/// defined.
+#[must_use]
+pub fn root_of_unity(n: u64) -> Option<Fp> {
+    (n != 0 && (MODULUS - 1).is_multiple_of(n)).then(|| Fp::GENERATOR.pow([(MODULUS - 1) / n]))
+}
+
+/// Evaluates the polynomial with coefficients `values` at the powers of the
+/// `n`-th root of unity in place.
+///
+/// # Panics
+///
+/// Panics if the length is not a supported size `2^a * 3^b * 5^c`.
+pub fn ntt(values: &mut [Fp]) {
+    transform(values, false);
+}
+
+/// Interpolates the coefficients of a polynomial from its evaluations at the
+/// powers of the `n`-th root of unity in place.
+///
+/// # Panics
+///
+/// Panics if the length is not a supported size `2^a * 3^b * 5^c`.
+pub fn intt(values: &mut [Fp]) {
+    transform(values, true);
+    let n = values.len();
+    if n.is_power_of_two() {
+        let log_n = n.trailing_zeros();
+        for value in values {
+            *value = mul_pow2(*value, ORDER_OF_TWO - log_n);
+        }
+    } else {
+        let n_inv = Fp::from(n as u64).inverse().unwrap();
+        for value in values {
+            *value *= n_inv;
+        }
+    }
+}
+
+/// Evaluates the polynomial with coefficients `values` on the coset
+/// `offset * <ω>` in place.
+///
+/// # Panics
+///
+/// Panics if the length is not a supported size `2^a * 3^b * 5^c`.
+pub fn coset_ntt(values: &mut [Fp], offset: Fp) {
+    scale_by_powers(values, offset);
+    ntt(values);
+}
+
+/// Interpolates the coefficients of a polynomial from its evaluations on the
+/// coset `offset * <ω>` in place.
+///
+/// # Panics
+///
+/// Panics if `offset` is zero or the length is not a supported size
+/// `2^a * 3^b * 5^c`.
+pub fn coset_intt(values: &mut [Fp], offset: Fp) {
+    intt(values);
+    scale_by_powers(values, offset.inverse().expect("coset offset is zero"));
+}
+
+/// Reorders `values` so that the element at index `i` moves to the index with
+/// the bits of `i` reversed.
+///
+/// # Panics
+///
+/// Panics if the length is not a power of two.
+pub fn bit_reverse_permute<T>(values: &mut [T]) {
+    let n = values.len();
+    assert!(n.is_power_of_two(), "length must be a power of two");
+    if n <= 2 {
+        return;
+    }
+    let shift = usize::BITS - n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> shift;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+fn transform(values: &mut [Fp], inverse: bool) {
+    let n = values.len();
+    assert!(
+        n != 0 && matches!(n >> n.trailing_zeros(), 1 | 3 | 5 | 15),
+        "length must be 2^a * 3^b * 5^c with b, c <= 1"
+    );
+    assert!(
+        n.trailing_zeros() <= Fp::TWO_ADICITY,
+        "length exceeds the two-adicity of the field"
+    );
+    if n.is_power_of_two() {
+        radix2(values, inverse);
+    } else {
+        mixed_radix(values, inverse);
+    }
+}
+
+/// Takes a radix-3 or radix-5 decimation-in-frequency step. For `n = r * m`
+/// the elements `x[i + m*s]` are combined with a radix-`r` DFT and twiddled,
+/// the `r` contiguous blocks of length `m` are transformed recursively and the
+/// result is transposed so that block `k` holds the evaluations `X[r*j + k]`.
+fn mixed_radix(values: &mut [Fp], inverse: bool) {
+    let n = values.len();
+    let radix = if n.is_multiple_of(3) { 3 } else { 5 };
+    let block_len = n / radix;
+    let mut root = root_of_unity(n as u64).unwrap();
+    if inverse {
+        root = root.inverse().unwrap();
+    }
+    let radix_root = root.pow([block_len as u64]);
+    let mut twiddle = Fp::ONE;
+    if radix == 3 {
+        // `w*x1 + w^2*x2 = -(x1 + x2)/2 + (w - w^2)/2 * (x1 - x2)` as `w + w^2 = -1`
+        let half_diff = mul_pow2(radix_root - radix_root.square(), ORDER_OF_TWO - 1);
+        for i in 0..block_len {
+            let (x0, x1, x2) = (values[i], values[i + block_len], values[i + 2 * block_len]);
+            let sum = x1 + x2;
+            let mid = x0 - mul_pow2(sum, ORDER_OF_TWO - 1);
+            let diff = (x1 - x2) * half_diff;
+            values[i] = x0 + sum;
+            values[i + block_len] = (mid + diff) * twiddle;
+            values[i + 2 * block_len] = (mid - diff) * twiddle.square();
+            twiddle *= root;
+        }
+    } else {
+        let radix_roots = powers(radix_root, 5);
+        let dft: [[Fp; 5]; 5] =
+            core::array::from_fn(|k| core::array::from_fn(|s| radix_roots[(s * k) % 5]));
+        for i in 0..block_len {
+            let inputs = [
+                values[i],
+                values[i + block_len],
+                values[i + 2 * block_len],
+                values[i + 3 * block_len],
+                values[i + 4 * block_len],
+            ];
+            values[i] = inputs.iter().sum();
+            let mut factor = twiddle;
+            for (k, row) in dft.iter().enumerate().skip(1) {
+                let output = inputs[0]
+                    + inputs[1..]
+                        .iter()
+                        .zip(&row[1..])
+                        .map(|(x, w)| *x * w)
+                        .sum::<Fp>();
+                values[i + k * block_len] = output * factor;
+                factor *= twiddle;
+            }
+            twiddle *= root;
+        }
+    }
+
+    for block in values.chunks_exact_mut(block_len) {
+        transform(block, inverse);
+    }
+    let blocks = values.to_vec();
+    for (k, block) in blocks.chunks_exact(block_len).enumerate() {
+        for (j, value) in block.iter().enumerate() {
+            values[radix * j + k] = *value;
+        }
+    }
+}
+
+fn radix2(values: &mut [Fp], inverse: bool) {
+    let n = values.len();
+    bit_reverse_permute(values);
+
+    // Levels whose twiddles are powers of two
+    let mut half = 1;
+    while half < n && 2 * half <= MAX_SHIFT_BLOCK_SIZE {
+        #[allow(clippy::cast_possible_truncation)]
+        let step = LOG_ROOT_OF_UNITY_64 * (MAX_SHIFT_BLOCK_SIZE / (2 * half)) as u32;
+        for block in values.chunks_exact_mut(2 * half) {
+            let (lo, hi) = block.split_at_mut(half);
+            let mut exp = 0;
+            for (u, v) in lo.iter_mut().zip(hi) {
+                let t = mul_pow2(*v, if inverse { ORDER_OF_TWO - exp } else { exp });
+                *v = *u - t;
+                *u += t;
+                exp = (exp + step) % ORDER_OF_TWO;
+            }
+        }
+        half *= 2;
+    }
+
+    // Remaining levels update the twiddle with one multiplication per butterfly
+    if half < n {
+        let mut root = Fp::get_root_of_unity(n as u64).unwrap();
+        if inverse {
+            root = root.inverse().unwrap();
+        }
+        while half < n {
+            let w_m = root.pow([(n / (2 * half)) as u64]);
+            for block in values.chunks_exact_mut(2 * half) {
+                let (lo, hi) = block.split_at_mut(half);
+                let mut w = Fp::ONE;
+                for (u, v) in lo.iter_mut().zip(hi) {
+                    let t = *v * w;
+                    *v = *u - t;
+                    *u += t;
+                    w *= w_m;
+                }
+            }
+            half *= 2;
+        }
+    }
+}
+
+/// Returns `[1, x, x^2, ..., x^(n-1)]`.
+fn powers(x: Fp, n: usize) -> Vec<Fp> {
+    let mut res = Vec::with_capacity(n);
+    let mut acc = Fp::ONE;
+    for _ in 0..n {
+        res.push(acc);
+        acc *= x;
+    }
+    res
+}
+
+/// Multiplies the `i`-th element of `values` by `x^i`.
+fn scale_by_powers(values: &mut [Fp], x: Fp) {
+    let mut acc = Fp::ONE;
+    for value in values {
+        *value *= acc;
+        acc *= x;
+    }
+}
+
+/// Multiplies `x` by `2^exp` with shifts and a Montgomery reduction.
+///
+/// Multiplying the Montgomery form of `x` by a constant gives the Montgomery
+/// form of the product, so `mont_red(x * 2^(exp + 64))` is the result. The
+/// factor `2^(exp + 64)` is folded into a shifted value that fits into 128 bits
+/// using `2^64 = 2^32 - 1` and `2^96 = -1`.
+#[inline]
+fn mul_pow2(x: Fp, exp: u32) -> Fp {
+    let exp = exp % ORDER_OF_TWO;
+    let (exp, negate) = if exp >= 96 {
+        (exp - 96, true)
+    } else {
+        (exp, false)
+    };
+    let raw = u128::from((x.0).0[0]);
+    let (res, negate) = if exp < 32 {
+        // 2^(exp + 64) = 2^(exp + 32) - 2^exp
+        (mont_red((raw << (exp + 32)) - (raw << exp)), negate)
+    } else {
+        // 2^(exp + 64) = -2^(exp - 32)
+        (mont_red(raw << (exp - 32)), !negate)
+    };
+    let res: Fp = ark_ff::Fp(BigInt([res]), PhantomData);
+    if negate {
+        -res
+    } else {
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        bit_reverse_permute, coset_intt, coset_ntt, intt, mul_pow2, ntt, root_of_unity,
+        ORDER_OF_TWO,
+    };
+    use crate::fp64::Fp;
+    use ark_ff::{FftField, Field};
+    use ark_poly::{EvaluationDomain, MixedRadixEvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    fn random_values(n: usize) -> Vec<Fp> {
+        let mut rng = test_rng();
+        (0..n).map(|_| Fp::rand(&mut rng)).collect()
+    }
+
+    #[test]
+    fn mul_pow2_matches_multiplication() {
+        let mut rng = test_rng();
+        let mut values = ark_std::vec![Fp::ZERO, Fp::ONE, -Fp::ONE];
+        values.extend((0..20).map(|_| Fp::rand(&mut rng)));
+        for x in values {
+            let mut expected = x;
+            for exp in 0..2 * ORDER_OF_TWO {
+                assert_eq!(mul_pow2(x, exp), expected, "exp = {exp}");
+                expected.double_in_place();
+            }
+        }
+    }
+
+    #[test]
+    fn root_of_unity_64_is_power_of_two() {
+        let root = Fp::get_root_of_unity(64).unwrap();
+        assert_eq!(
+            root,
+            Fp::from(2u8).pow([u64::from(super::LOG_ROOT_OF_UNITY_64)])
+        );
+        assert_eq!(root, Fp::from(8u8).pow([13]));
+    }
+
+    #[test]
+    fn matches_radix2_domain() {
+        for log_n in 0..=12 {
+            let n = 1 << log_n;
+            let domain = Radix2EvaluationDomain::<Fp>::new(n).unwrap();
+            let coeffs = random_values(n);
+
+            let mut expected = coeffs.clone();
+            domain.fft_in_place(&mut expected);
+            let mut evals = coeffs.clone();
+            ntt(&mut evals);
+            assert_eq!(evals, expected, "n = {n}");
+
+            let mut expected = coeffs.clone();
+            domain.ifft_in_place(&mut expected);
+            let mut values = coeffs.clone();
+            intt(&mut values);
+            assert_eq!(values, expected, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn coset_matches_radix2_domain() {
+        let offset = Fp::GENERATOR;
+        for log_n in 0..=10 {
+            let n = 1 << log_n;
+            let domain = Radix2EvaluationDomain::<Fp>::new(n)
+                .unwrap()
+                .get_coset(offset)
+                .unwrap();
+            let coeffs = random_values(n);
+
+            let mut expected = coeffs.clone();
+            domain.fft_in_place(&mut expected);
+            let mut evals = coeffs.clone();
+            coset_ntt(&mut evals, offset);
+            assert_eq!(evals, expected, "n = {n}");
+
+            coset_intt(&mut evals, offset);
+            assert_eq!(evals, coeffs, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn root_of_unity_matches_fft_field() {
+        for log_n in 0..=32 {
+            for n in [1u64 << log_n, 3 << log_n] {
+                assert_eq!(root_of_unity(n), Fp::get_root_of_unity(n), "n = {n}");
+            }
+        }
+        // the root has order exactly `n` if `root^(n/q) != 1` for every prime `q | n`
+        for (n, primes) in [
+            (5, &[5][..]),
+            (15, &[3, 5]),
+            (17 * 257, &[17, 257]),
+            (5 << 32, &[2, 5]),
+        ] {
+            let root = root_of_unity(n).unwrap();
+            assert_eq!(root.pow([n]), Fp::ONE);
+            assert!(primes.iter().all(|q| root.pow([n / q]) != Fp::ONE));
+        }
+        assert_eq!(root_of_unity(0), None);
+        assert_eq!(root_of_unity(7), None);
+    }
+
+    #[test]
+    fn matches_mixed_radix_domain() {
+        let offset = Fp::GENERATOR;
+        for log_n in 0..=10 {
+            let n = 3 << log_n;
+            let domain = MixedRadixEvaluationDomain::<Fp>::new(n).unwrap();
+            assert_eq!(domain.size(), n);
+            let coset = domain.get_coset(offset).unwrap();
+            let coeffs = random_values(n);
+
+            let mut expected = coeffs.clone();
+            domain.fft_in_place(&mut expected);
+            let mut evals = coeffs.clone();
+            ntt(&mut evals);
+            assert_eq!(evals, expected, "n = {n}");
+
+            let mut expected = evals.clone();
+            domain.ifft_in_place(&mut expected);
+            intt(&mut evals);
+            assert_eq!(evals, expected, "n = {n}");
+            assert_eq!(evals, coeffs, "n = {n}");
+
+            let mut expected = coeffs.clone();
+            coset.fft_in_place(&mut expected);
+            let mut evals = coeffs.clone();
+            coset_ntt(&mut evals, offset);
+            assert_eq!(evals, expected, "n = {n}");
+
+            let mut expected = evals.clone();
+            coset.ifft_in_place(&mut expected);
+            coset_intt(&mut evals, offset);
+            assert_eq!(evals, expected, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn matches_naive_evaluation() {
+        for n in [3, 5, 10, 15, 20, 30, 40, 60, 120, 240] {
+            let root = root_of_unity(n as u64).unwrap();
+            let coeffs = random_values(n);
+            let expected: Vec<Fp> = (0..n)
+                .map(|i| {
+                    let point = root.pow([i as u64]);
+                    coeffs.iter().rev().fold(Fp::ZERO, |acc, c| acc * point + c)
+                })
+                .collect();
+            let mut evals = coeffs.clone();
+            ntt(&mut evals);
+            assert_eq!(evals, expected, "n = {n}");
+            intt(&mut evals);
+            assert_eq!(evals, coeffs, "n = {n}");
+
+            let mut evals = coeffs.clone();
+            coset_ntt(&mut evals, Fp::GENERATOR);
+            coset_intt(&mut evals, Fp::GENERATOR);
+            assert_eq!(evals, coeffs, "n = {n}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "length must be")]
+    fn rejects_unsupported_lengths() {
+        ntt(&mut random_values(9));
+    }
+
+    #[test]
+    fn round_trip() {
+        let coeffs = random_values(1 << 14);
+        let mut values = coeffs.clone();
+        ntt(&mut values);
+        intt(&mut values);
+        assert_eq!(values, coeffs);
+    }
+
+    #[test]
+    fn bit_reverse_permutation() {
+        let mut values: Vec<usize> = (0..16).collect();
+        bit_reverse_permute(&mut values);
+        assert_eq!(
+            values,
+            [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15]
+        );
+        bit_reverse_permute(&mut values);
+        assert_eq!(values, (0..16).collect::<Vec<_>>());
+    }
+}
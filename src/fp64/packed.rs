@@ -0,0 +1,480 @@
+//! Packed arithmetic on 4 elements of the 64-bit field using AVX2.
+//!
+//! Elements stay in the Montgomery form of [`Fp`] and every lane runs the same
+//! sequence of 64-bit additions, subtractions and shifts as the scalar
+//! implementation so results are identical. AVX2 only has a 32x32-bit
+//! multiplier so the 128-bit products are assembled from four partial
+//! products before the Montgomery reduction.
+//!
+//! AVX2 support is detected at runtime with `cpuid` and the scalar
+//! implementation is used without it. The detected routines can't be inlined
+//! so each operation pays for a call. The slice operations such as
+//! [`PackedFp64x4::mul_assign_slice`] check once and inline the routines into
+//! their loop. Compiling with AVX2 enabled, for example with
+//! `-C target-cpu=native`, removes the check everywhere.
+//!
+//! [`Fp`] also implements [`SimdField`] with the same routines written with
+//! `core::simd` for the portable [`Packed`](crate::packed::Packed) type.
+
+use super::{Fp, MODULUS};
+use crate::packed::{dispatch, dispatch_slice, PackedField, SimdField};
+use ark_ff::{BigInt, Field};
+use core::{
+    marker::PhantomData,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    simd::{cmp::SimdPartialOrd, Select, Simd},
+};
+
+/// Number of field elements in a [`PackedFp64x4`]
+pub const WIDTH: usize = 4;
+
+/// Four elements of the 64-bit field operated on lane-wise
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PackedFp64x4(pub [Fp; WIDTH]);
+
+impl PackedFp64x4 {
+    pub const ZERO: Self = Self([Fp::ZERO; WIDTH]);
+
+    pub const ONE: Self = Self([Fp::ONE; WIDTH]);
+
+    /// Returns a packed value with `value` in every lane.
+    #[must_use]
+    pub const fn broadcast(value: Fp) -> Self {
+        Self([value; WIDTH])
+    }
+
+    /// Packs the first [`WIDTH`] elements of `slice`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slice` has fewer than [`WIDTH`] elements.
+    #[must_use]
+    pub fn from_slice(slice: &[Fp]) -> Self {
+        Self(slice[..WIDTH].try_into().unwrap())
+    }
+
+    /// Writes the lanes to the first [`WIDTH`] elements of `slice`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slice` has fewer than [`WIDTH`] elements.
+    pub fn to_slice(&self, slice: &mut [Fp]) {
+        slice[..WIDTH].copy_from_slice(&self.0);
+    }
+
+    #[must_use]
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// Sets `lhs[i] += rhs[i]` for every `i`, checking for AVX2 once rather
+    /// than for every element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lhs` and `rhs` have different lengths.
+    pub fn add_assign_slice(lhs: &mut [Self], rhs: &[Self]) {
+        dispatch_slice!(PackedFp64x4, add, lhs, rhs);
+    }
+
+    /// Sets `lhs[i] *= rhs[i]` for every `i`, checking for AVX2 once rather
+    /// than for every element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lhs` and `rhs` have different lengths.
+    pub fn mul_assign_slice(lhs: &mut [Self], rhs: &[Self]) {
+        dispatch_slice!(PackedFp64x4, mul, lhs, rhs);
+    }
+
+    /// Views the lanes as their Montgomery representations in place so the
+    /// AVX2 routines load them with a single read of the original memory.
+    #[cfg(target_arch = "x86_64")]
+    #[inline]
+    const fn as_raw(&self) -> &[u64; WIDTH] {
+        // `Fp` has the size of its only non zero-sized field, the limb
+        const _: () = assert!(core::mem::size_of::<Fp>() == core::mem::size_of::<u64>());
+        // SAFETY: the limb of each `Fp` is at offset 0 by the assertion above.
+        unsafe { &*core::ptr::from_ref(self).cast() }
+    }
+
+    #[inline]
+    fn from_raw(raw: [u64; WIDTH]) -> Self {
+        Self(raw.map(|limb| ark_ff::Fp(BigInt([limb]), PhantomData)))
+    }
+}
+
+impl Add for PackedFp64x4 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        dispatch!(PackedFp64x4, add, scalar::add(self, rhs), self, rhs)
+    }
+}
+
+impl Sub for PackedFp64x4 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        dispatch!(PackedFp64x4, sub, scalar::sub(self, rhs), self, rhs)
+    }
+}
+
+impl Mul for PackedFp64x4 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        dispatch!(PackedFp64x4, mul, scalar::mul(self, rhs), self, rhs)
+    }
+}
+
+impl Neg for PackedFp64x4 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        dispatch!(PackedFp64x4, neg, scalar::neg(self), self)
+    }
+}
+
+impl AddAssign for PackedFp64x4 {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for PackedFp64x4 {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for PackedFp64x4 {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl From<Fp> for PackedFp64x4 {
+    fn from(value: Fp) -> Self {
+        Self::broadcast(value)
+    }
+}
+
+impl PackedField for PackedFp64x4 {
+    type Scalar = Fp;
+
+    const WIDTH: usize = WIDTH;
+
+    #[inline]
+    fn broadcast(value: Fp) -> Self {
+        Self::broadcast(value)
+    }
+
+    #[inline]
+    fn from_fn<G: FnMut(usize) -> Fp>(f: G) -> Self {
+        Self(core::array::from_fn(f))
+    }
+
+    #[inline]
+    fn lane(&self, i: usize) -> Fp {
+        self.0[i]
+    }
+}
+
+/// Computes `a - b` and adds `p` on underflow, which is subtracting `2^32 - 1`
+/// modulo `2^64`.
+#[inline]
+fn simd_sub_raw<const N: usize>(a: Simd<u64, N>, b: Simd<u64, N>) -> Simd<u64, N> {
+    let epsilon = Simd::splat(0xFFFF_FFFF);
+    (a - b) - b.simd_gt(a).select(epsilon, Simd::splat(0))
+}
+
+/// Lanes hold the Montgomery representation and follow the AVX2 routines
+/// below.
+impl SimdField for Fp {
+    type Lane = u64;
+
+    #[inline]
+    fn to_lane(self) -> u64 {
+        (self.0).0[0]
+    }
+
+    #[inline]
+    fn from_lane(lane: u64) -> Self {
+        Self(BigInt([lane]), PhantomData)
+    }
+
+    #[inline]
+    fn simd_add<const N: usize>(a: Simd<u64, N>, b: Simd<u64, N>) -> Simd<u64, N> {
+        // a + b = a - (p - b)
+        simd_sub_raw(a, Simd::splat(MODULUS) - b)
+    }
+
+    #[inline]
+    fn simd_sub<const N: usize>(a: Simd<u64, N>, b: Simd<u64, N>) -> Simd<u64, N> {
+        simd_sub_raw(a, b)
+    }
+
+    #[inline]
+    fn simd_mul<const N: usize>(x: Simd<u64, N>, y: Simd<u64, N>) -> Simd<u64, N> {
+        // 128-bit products from four 32x32-bit products
+        let mask_lo = Simd::splat(0xFFFF_FFFF);
+        let (x_lo, x_hi) = (x & mask_lo, x >> 32);
+        let (y_lo, y_hi) = (y & mask_lo, y >> 32);
+        let ll = x_lo * y_lo;
+        let mid0 = x_lo * y_hi + (ll >> 32);
+        let mid1 = x_hi * y_lo + (mid0 & mask_lo);
+        let lo = (mid1 << 32) | (ll & mask_lo);
+        let hi = x_hi * y_hi + (mid0 >> 32) + (mid1 >> 32);
+        // Montgomery reduction, see `mont_red` in `fp64.rs`
+        let a = lo + (lo << 32);
+        let b = (a - (a >> 32)) - lo.simd_gt(a).select(Simd::splat(1), Simd::splat(0));
+        simd_sub_raw(hi, b)
+    }
+
+    #[inline]
+    fn simd_neg<const N: usize>(a: Simd<u64, N>) -> Simd<u64, N> {
+        simd_sub_raw(Simd::splat(0), a)
+    }
+}
+
+/// Lane-wise fallback using the scalar field arithmetic
+mod scalar {
+    use super::{PackedFp64x4, WIDTH};
+
+    pub fn add(a: PackedFp64x4, b: PackedFp64x4) -> PackedFp64x4 {
+        PackedFp64x4(core::array::from_fn::<_, WIDTH, _>(|i| a.0[i] + b.0[i]))
+    }
+
+    pub fn sub(a: PackedFp64x4, b: PackedFp64x4) -> PackedFp64x4 {
+        PackedFp64x4(core::array::from_fn::<_, WIDTH, _>(|i| a.0[i] - b.0[i]))
+    }
+
+    pub fn mul(a: PackedFp64x4, b: PackedFp64x4) -> PackedFp64x4 {
+        PackedFp64x4(core::array::from_fn::<_, WIDTH, _>(|i| a.0[i] * b.0[i]))
+    }
+
+    pub fn neg(a: PackedFp64x4) -> PackedFp64x4 {
+        PackedFp64x4(a.0.map(|element| -element))
+    }
+}
+
+/// AVX2 versions of the scalar routines in `fp64.rs` operating on the
+/// Montgomery representations of four elements
+#[cfg(target_arch = "x86_64")]
+#[allow(clippy::cast_possible_wrap)]
+mod avx2 {
+    use super::{MODULUS, WIDTH};
+    use core::arch::x86_64::{
+        __m256i, _mm256_add_epi64, _mm256_and_si256, _mm256_cmpgt_epi64, _mm256_loadu_si256,
+        _mm256_mul_epu32, _mm256_or_si256, _mm256_set1_epi64x, _mm256_setzero_si256,
+        _mm256_slli_epi64, _mm256_srli_epi64, _mm256_storeu_si256, _mm256_sub_epi64,
+        _mm256_xor_si256,
+    };
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn load(x: &[u64; WIDTH]) -> __m256i {
+        _mm256_loadu_si256(x.as_ptr().cast())
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn store(x: __m256i) -> [u64; WIDTH] {
+        let mut res = [0; WIDTH];
+        _mm256_storeu_si256(res.as_mut_ptr().cast(), x);
+        res
+    }
+
+    /// Returns all ones in the lanes where `x > y` as unsigned integers.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn gt_u64(x: __m256i, y: __m256i) -> __m256i {
+        let sign = _mm256_set1_epi64x(i64::MIN);
+        _mm256_cmpgt_epi64(_mm256_xor_si256(x, sign), _mm256_xor_si256(y, sign))
+    }
+
+    /// Returns `2^32 - 1` in the lanes where `mask` is set.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn epsilon_if(mask: __m256i) -> __m256i {
+        _mm256_srli_epi64::<32>(mask)
+    }
+
+    /// Computes `a - b` and adds `p` on underflow, which is subtracting
+    /// `2^32 - 1` modulo `2^64`.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn sub_raw(a: __m256i, b: __m256i) -> __m256i {
+        let diff = _mm256_sub_epi64(a, b);
+        _mm256_sub_epi64(diff, epsilon_if(gt_u64(b, a)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn add(a: &[u64; WIDTH], b: &[u64; WIDTH]) -> [u64; WIDTH] {
+        // a + b = a - (p - b)
+        let p = _mm256_set1_epi64x(MODULUS as i64);
+        store(sub_raw(load(a), _mm256_sub_epi64(p, load(b))))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sub(a: &[u64; WIDTH], b: &[u64; WIDTH]) -> [u64; WIDTH] {
+        store(sub_raw(load(a), load(b)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn neg(a: &[u64; WIDTH]) -> [u64; WIDTH] {
+        store(sub_raw(_mm256_setzero_si256(), load(a)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mul(a: &[u64; WIDTH], b: &[u64; WIDTH]) -> [u64; WIDTH] {
+        let (lo, hi) = mul_wide(load(a), load(b));
+        store(mont_red(lo, hi))
+    }
+
+    /// Computes the 128-bit products `x * y` as `(lo, hi)` from four 32x32-bit
+    /// products.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn mul_wide(x: __m256i, y: __m256i) -> (__m256i, __m256i) {
+        let mask_lo = _mm256_set1_epi64x(0xFFFF_FFFF);
+        let x_hi = _mm256_srli_epi64::<32>(x);
+        let y_hi = _mm256_srli_epi64::<32>(y);
+        let ll = _mm256_mul_epu32(x, y);
+        let lh = _mm256_mul_epu32(x, y_hi);
+        let hl = _mm256_mul_epu32(x_hi, y);
+        let hh = _mm256_mul_epu32(x_hi, y_hi);
+        // Neither sum can overflow since `(2^32 - 1)^2 + 2 * (2^32 - 1) < 2^64`.
+        let mid0 = _mm256_add_epi64(lh, _mm256_srli_epi64::<32>(ll));
+        let mid1 = _mm256_add_epi64(hl, _mm256_and_si256(mid0, mask_lo));
+        let lo = _mm256_or_si256(_mm256_slli_epi64::<32>(mid1), _mm256_and_si256(ll, mask_lo));
+        let hi = _mm256_add_epi64(
+            hh,
+            _mm256_add_epi64(_mm256_srli_epi64::<32>(mid0), _mm256_srli_epi64::<32>(mid1)),
+        );
+        (lo, hi)
+    }
+
+    /// Montgomery reduction of `hi * 2^64 + lo`. See `mont_red` in `fp64.rs`.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn mont_red(lo: __m256i, hi: __m256i) -> __m256i {
+        let a = _mm256_add_epi64(lo, _mm256_slli_epi64::<32>(lo));
+        // `overflow` is all ones i.e. -1 in the lanes where the addition wrapped
+        let overflow = gt_u64(lo, a);
+        let b = _mm256_add_epi64(_mm256_sub_epi64(a, _mm256_srli_epi64::<32>(a)), overflow);
+        sub_raw(hi, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scalar, PackedFp64x4, WIDTH};
+    use crate::fp64::{Fp, MODULUS};
+    use ark_ff::{Field, One, Zero};
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    /// Random elements together with edge cases around zero and the modulus.
+    fn test_vectors() -> Vec<PackedFp64x4> {
+        let mut rng = test_rng();
+        let edge = [
+            Fp::zero(),
+            Fp::one(),
+            -Fp::one(),
+            Fp::from(MODULUS - 2),
+            Fp::from(u64::from(u32::MAX)),
+            Fp::from(1u64 << 32),
+            Fp::from(u64::MAX),
+        ];
+        let mut vectors = Vec::new();
+        for a in edge {
+            for b in edge {
+                vectors.push(PackedFp64x4([a, b, -a, b.square()]));
+            }
+        }
+        for _ in 0..1000 {
+            vectors.push(PackedFp64x4(core::array::from_fn(|_| Fp::rand(&mut rng))));
+        }
+        vectors
+    }
+
+    #[test]
+    fn matches_scalar_arithmetic() {
+        let vectors = test_vectors();
+        for (a, b) in vectors.iter().zip(vectors.iter().rev()) {
+            let (a, b) = (*a, *b);
+            let sum = a + b;
+            let difference = a - b;
+            let product = a * b;
+            let negation = -a;
+            let square = a.square();
+            for i in 0..WIDTH {
+                assert_eq!(sum.0[i], a.0[i] + b.0[i]);
+                assert_eq!(difference.0[i], a.0[i] - b.0[i]);
+                assert_eq!(product.0[i], a.0[i] * b.0[i]);
+                assert_eq!(negation.0[i], -a.0[i]);
+                assert_eq!(square.0[i], a.0[i].square());
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn avx2_matches_fallback() {
+        use super::avx2;
+
+        // the scalar fallback is covered by `matches_scalar_arithmetic`
+        if !crate::packed::has_avx2() {
+            return;
+        }
+        let vectors = test_vectors();
+        for (a, b) in vectors.iter().zip(vectors.iter().rev()) {
+            let (a, b) = (*a, *b);
+            // SAFETY: AVX2 support has been checked above.
+            unsafe {
+                let (x, y) = (a.as_raw(), b.as_raw());
+                assert_eq!(PackedFp64x4::from_raw(avx2::add(x, y)), scalar::add(a, b));
+                assert_eq!(PackedFp64x4::from_raw(avx2::sub(x, y)), scalar::sub(a, b));
+                assert_eq!(PackedFp64x4::from_raw(avx2::mul(x, y)), scalar::mul(a, b));
+                assert_eq!(PackedFp64x4::from_raw(avx2::neg(x)), scalar::neg(a));
+            }
+        }
+    }
+
+    #[test]
+    fn slice_ops_match_elementwise() {
+        let vectors = test_vectors();
+        let rhs: Vec<PackedFp64x4> = vectors.iter().rev().copied().collect();
+        let mut sums = vectors.clone();
+        PackedFp64x4::add_assign_slice(&mut sums, &rhs);
+        let mut products = vectors.clone();
+        PackedFp64x4::mul_assign_slice(&mut products, &rhs);
+        for (i, (a, b)) in vectors.iter().zip(&rhs).enumerate() {
+            assert_eq!(sums[i], *a + *b);
+            assert_eq!(products[i], *a * *b);
+        }
+    }
+
+    #[test]
+    fn slice_round_trip() {
+        let mut rng = test_rng();
+        let values: Vec<Fp> = (0..=WIDTH).map(|_| Fp::rand(&mut rng)).collect();
+        let packed = PackedFp64x4::from_slice(&values);
+        let mut out = [Fp::zero(); WIDTH];
+        packed.to_slice(&mut out);
+        assert_eq!(out, values[..WIDTH]);
+        assert_eq!(PackedFp64x4::broadcast(values[0]).0, [values[0]; WIDTH]);
+    }
+}
@@ -0,0 +1,675 @@
+//! The Poseidon2 permutation over [`Fp`] with widths 8 and 12, a sponge and a
+//! 2-to-1 compression function producing 4 element digests.
+//!
+//! Poseidon2 (<https://eprint.iacr.org/2023/323>) runs 4 full rounds, 22
+//! partial rounds and another 4 full rounds with the S-box `x^7`, which is a
+//! permutation since `gcd(7, p - 1) = 1`. Before the first round and after
+//! every full round the external layer `circ(2*M4, M4, ..., M4)` is applied,
+//! built from the 4x4 MDS matrix
+//!
+//! ```text
+//! [5 7 1 3]
+//! [4 6 1 1]
+//! [1 3 5 7]
+//! [1 1 4 6]
+//! ```
+//!
+//! Partial rounds use the internal layer `1 + diag(d)` where `1` is the all
+//! ones matrix. The round constants, the diagonals `d` and the 4x4 matrix are
+//! the Goldilocks instances of the Horizen Labs reference implementation
+//! (`plain_impls/src/poseidon2/poseidon2_instance_goldilocks.rs` in
+//! <https://github.com/HorizenLabs/poseidon2>).
+
+use super::{into_mont, Fp};
+use ark_ff::Field;
+
+/// Number of full rounds before and after the partial rounds
+const HALF_FULL_ROUNDS: usize = 4;
+
+/// Number of partial rounds for both widths
+const PARTIAL_ROUNDS: usize = 22;
+
+/// Number of elements in a digest, which is also the capacity of the sponge
+pub const DIGEST_LEN: usize = 4;
+
+/// A sponge or compression output
+pub type Digest = [Fp; DIGEST_LEN];
+
+/// The round constants and internal diagonal of a Poseidon2 instance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Poseidon2<const WIDTH: usize> {
+    initial_constants: [[Fp; WIDTH]; HALF_FULL_ROUNDS],
+    internal_constants: [Fp; PARTIAL_ROUNDS],
+    terminal_constants: [[Fp; WIDTH]; HALF_FULL_ROUNDS],
+    /// `d` in the internal matrix `1 + diag(d)`
+    internal_diag: [Fp; WIDTH],
+}
+
+impl Poseidon2<8> {
+    /// Returns the width 8 instance.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            initial_constants: to_fp_rows(INITIAL_CONSTANTS_8),
+            internal_constants: to_fp(INTERNAL_CONSTANTS_8),
+            terminal_constants: to_fp_rows(TERMINAL_CONSTANTS_8),
+            internal_diag: to_fp(INTERNAL_DIAG_8),
+        }
+    }
+}
+
+impl Default for Poseidon2<8> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Poseidon2<12> {
+    /// Returns the width 12 instance.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            initial_constants: to_fp_rows(INITIAL_CONSTANTS_12),
+            internal_constants: to_fp(INTERNAL_CONSTANTS_12),
+            terminal_constants: to_fp_rows(TERMINAL_CONSTANTS_12),
+            internal_diag: to_fp(INTERNAL_DIAG_12),
+        }
+    }
+}
+
+impl Default for Poseidon2<12> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WIDTH: usize> Poseidon2<WIDTH> {
+    /// Applies the permutation to `state` in place.
+    pub fn permute(&self, state: &mut [Fp; WIDTH]) {
+        external_linear_layer(state);
+        for constants in &self.initial_constants {
+            full_round(state, constants);
+        }
+        for constant in &self.internal_constants {
+            state[0] = sbox(state[0] + constant);
+            internal_linear_layer(state, &self.internal_diag);
+        }
+        for constants in &self.terminal_constants {
+            full_round(state, constants);
+        }
+    }
+
+    /// Hashes `elements` with a [`Poseidon2Sponge`] using this permutation.
+    #[must_use]
+    pub fn hash(&self, elements: &[Fp]) -> Digest {
+        let mut sponge = Poseidon2Sponge::new(self.clone());
+        sponge.absorb(elements);
+        sponge.finalize()
+    }
+
+    /// Compresses two digests into the first [`DIGEST_LEN`] elements of the
+    /// permuted `left || right`, padded with zeros for width 12.
+    #[must_use]
+    pub fn compress(&self, left: &Digest, right: &Digest) -> Digest {
+        crate::compression::truncated_permutation(|state| self.permute(state), left, right)
+    }
+}
+
+/// A sponge over [`Poseidon2`] with capacity [`DIGEST_LEN`] and rate
+/// `WIDTH - DIGEST_LEN`.
+///
+/// Elements are added to the rate portion at the start of the state, which is
+/// permuted whenever it is full. The input is padded with a single one
+/// followed by zeros so inputs of different lengths are absorbed differently,
+/// even if one of them ends in zeros. The digest is the first [`DIGEST_LEN`]
+/// elements of the final state.
+#[derive(Clone, Debug)]
+pub struct Poseidon2Sponge<const WIDTH: usize> {
+    permutation: Poseidon2<WIDTH>,
+    state: [Fp; WIDTH],
+    /// Number of elements absorbed into the rate since the last permutation
+    position: usize,
+}
+
+impl<const WIDTH: usize> Poseidon2Sponge<WIDTH> {
+    const RATE: usize = WIDTH - DIGEST_LEN;
+
+    /// Creates a sponge with an all zero state.
+    #[must_use]
+    pub const fn new(permutation: Poseidon2<WIDTH>) -> Self {
+        Self {
+            permutation,
+            state: [into_mont(0); WIDTH],
+            position: 0,
+        }
+    }
+
+    /// Absorbs `elements`. Absorbing in several calls is the same as
+    /// absorbing the concatenation at once.
+    pub fn absorb(&mut self, elements: &[Fp]) {
+        for element in elements {
+            if self.position == Self::RATE {
+                self.permutation.permute(&mut self.state);
+                self.position = 0;
+            }
+            self.state[self.position] += element;
+            self.position += 1;
+        }
+    }
+
+    /// Pads the input and returns the digest.
+    #[must_use]
+    pub fn finalize(self) -> Digest {
+        let mut digest = [Fp::ZERO; DIGEST_LEN];
+        self.finalize_into(&mut digest);
+        digest
+    }
+
+    /// Pads the input and squeezes `output.len()` elements, permuting the
+    /// state before every `WIDTH - DIGEST_LEN` of them. The first
+    /// [`DIGEST_LEN`] elements are the digest.
+    pub fn finalize_into(mut self, output: &mut [Fp]) {
+        self.absorb(&[Fp::ONE]);
+        for chunk in output.chunks_mut(Self::RATE) {
+            self.permutation.permute(&mut self.state);
+            chunk.copy_from_slice(&self.state[..chunk.len()]);
+        }
+    }
+}
+
+/// Adds the round constants, applies the S-box to every element and then the
+/// external linear layer.
+#[inline]
+fn full_round<const WIDTH: usize>(state: &mut [Fp; WIDTH], constants: &[Fp; WIDTH]) {
+    for (value, constant) in state.iter_mut().zip(constants) {
+        *value = sbox(*value + constant);
+    }
+    external_linear_layer(state);
+}
+
+/// Computes `x^7 = x^3 * x^4`.
+#[inline]
+fn sbox(x: Fp) -> Fp {
+    let x2 = x.square();
+    x2 * x * x2.square()
+}
+
+/// Multiplies by the 4x4 MDS matrix with 8 additions and 6 doublings.
+#[inline]
+fn mds_4(x: &mut [Fp]) {
+    let t0 = x[0] + x[1];
+    let t1 = x[2] + x[3];
+    let t2 = x[1].double() + t1;
+    let t3 = x[3].double() + t0;
+    let t4 = t1.double().double() + t3;
+    let t5 = t0.double().double() + t2;
+    x[0] = t3 + t5;
+    x[1] = t5;
+    x[2] = t2 + t4;
+    x[3] = t4;
+}
+
+/// Multiplies by `circ(2*M4, M4, ..., M4)`. After applying `M4` to every
+/// chunk of 4, element `i` is doubled and the elements of the other chunks at
+/// the same offset are added, so the column sums are shared.
+#[inline]
+fn external_linear_layer<const WIDTH: usize>(state: &mut [Fp; WIDTH]) {
+    for chunk in state.chunks_exact_mut(4) {
+        mds_4(chunk);
+    }
+    let mut sums = [Fp::ZERO; 4];
+    for chunk in state.chunks_exact(4) {
+        for (sum, value) in sums.iter_mut().zip(chunk) {
+            *sum += value;
+        }
+    }
+    for chunk in state.chunks_exact_mut(4) {
+        for (value, sum) in chunk.iter_mut().zip(&sums) {
+            *value += sum;
+        }
+    }
+}
+
+/// Multiplies by `1 + diag(d)` as `x_i * d_i + sum(x)`.
+#[inline]
+fn internal_linear_layer<const WIDTH: usize>(state: &mut [Fp; WIDTH], diag: &[Fp; WIDTH]) {
+    let sum: Fp = state.iter().sum();
+    for (value, d) in state.iter_mut().zip(diag) {
+        *value = *value * d + sum;
+    }
+}
+
+const fn to_fp<const N: usize>(values: [u64; N]) -> [Fp; N] {
+    let mut res = [into_mont(0); N];
+    let mut i = 0;
+    while i < N {
+        res[i] = into_mont(values[i]);
+        i += 1;
+    }
+    res
+}
+
+const fn to_fp_rows<const N: usize>(
+    rows: [[u64; N]; HALF_FULL_ROUNDS],
+) -> [[Fp; N]; HALF_FULL_ROUNDS] {
+    let mut res = [[into_mont(0); N]; HALF_FULL_ROUNDS];
+    let mut i = 0;
+    while i < HALF_FULL_ROUNDS {
+        res[i] = to_fp(rows[i]);
+        i += 1;
+    }
+    res
+}
+
+/// `d` of the width 8 internal matrix
+const INTERNAL_DIAG_8: [u64; 8] = [
+    0xa988_11a1_fed4_e3a5,
+    0x1cc4_8b54_f377_e2a0,
+    0xe40c_d4f6_c560_9a26,
+    0x11de_79eb_ca97_a4a3,
+    0x9177_c73d_8b7e_929c,
+    0x2a6f_e808_5797_e791,
+    0x3de6_e933_29f8_d5ad,
+    0x3f7a_f912_5da9_62fe,
+];
+
+/// `d` of the width 12 internal matrix
+const INTERNAL_DIAG_12: [u64; 12] = [
+    0xc3b6_c08e_23ba_9300,
+    0xd84b_5de9_4a32_4fb6,
+    0x0d0c_371c_5b35_b84f,
+    0x7964_f570_e718_8037,
+    0x5daf_18bb_d996_604b,
+    0x6743_bc47_b959_5257,
+    0x5528_b936_2c59_bb70,
+    0xac45_e25b_7127_b68b,
+    0xa207_7d7d_fbb6_06b5,
+    0xf3fa_ac6f_aee3_78ae,
+    0x0c63_88b5_1545_e883,
+    0xd27d_bb69_4491_7b60,
+];
+
+/// External round constants of the first half of the width 8 permutation
+const INITIAL_CONSTANTS_8: [[u64; 8]; HALF_FULL_ROUNDS] = [
+    [
+        0xdd57_43e7_f2a5_a5d9,
+        0xcb3a_864e_58ad_a44b,
+        0xffa2_449e_d32f_8cdc,
+        0x4202_5f65_d6bd_13ee,
+        0x7889_175e_2550_6323,
+        0x34b9_8bb0_3d24_b737,
+        0xbdcc_535e_cc4f_aa2a,
+        0x5b20_ad86_9fc0_d033,
+    ],
+    [
+        0xf1dd_a5b9_259d_fcb4,
+        0x2751_5210_be11_2d59,
+        0x4227_d171_8c76_6c3f,
+        0x26d3_3316_1a5b_d794,
+        0x49b9_3895_7bf4_b026,
+        0x4a56_b593_8b21_3669,
+        0x1120_426b_48c8_353d,
+        0x6b32_3c3f_10a5_6cad,
+    ],
+    [
+        0xce57_d624_5ddc_a6b2,
+        0xb1fc_8d40_2bba_1eb1,
+        0xb5c5_096c_a959_bd04,
+        0x6db5_5cd3_06d3_1f7f,
+        0xc49d_293a_81cb_9641,
+        0x1ce5_5a4f_e979_719f,
+        0xa92e_60a9_d178_a4d1,
+        0x002c_c649_73bc_fd8c,
+    ],
+    [
+        0xcea7_21cc_e82f_b11b,
+        0xe5b5_5eb8_098e_ce81,
+        0x4e30_525c_6f1d_dd66,
+        0x43c6_7028_2707_0987,
+        0xaca6_8430_a7b5_762a,
+        0x3674_2386_34df_9c93,
+        0x88ce_e1c8_25e3_3433,
+        0xde99_ae8d_74b5_7176,
+    ],
+];
+
+/// Internal round constants of the width 8 permutation
+const INTERNAL_CONSTANTS_8: [u64; PARTIAL_ROUNDS] = [
+    0x4888_97d8_5ff5_1f56,
+    0x1140_737c_cb16_2218,
+    0xa7ee_b921_5866_ed35,
+    0x9bd2_976f_ee49_fcc9,
+    0xc0c8_f0de_580a_3fcc,
+    0x4fb2_dae6_ee8f_c793,
+    0x343a_89f3_5f37_395b,
+    0x223b_525a_77ca_72c8,
+    0x56cc_b625_74aa_a918,
+    0xc4d5_07d8_027a_f9ed,
+    0xa080_673c_f0b7_e95c,
+    0xf018_4884_eb70_dcf8,
+    0x044f_10b0_cb3d_5c69,
+    0xe9e3_f799_3938_f186,
+    0x1b76_1c80_e772_f459,
+    0x606c_ec60_7a1b_5fac,
+    0x14a0_c2e1_d45f_03cd,
+    0x4eac_e885_5398_574f,
+    0xf905_ca71_03ef_f3e6,
+    0xf8c8_f8d2_0862_c059,
+    0xb524_fe8b_dd67_8e5a,
+    0xfbb7_8659_01a1_ec41,
+];
+
+/// External round constants of the second half of the width 8 permutation
+const TERMINAL_CONSTANTS_8: [[u64; 8]; HALF_FULL_ROUNDS] = [
+    [
+        0x014e_f119_7d34_1346,
+        0x9725_e208_25d0_7394,
+        0xfdb2_5aef_2c5b_ae3b,
+        0xbe54_02dc_598c_971e,
+        0x93a5_711f_04cd_ca3d,
+        0xc45a_9a5b_2f8f_b97b,
+        0xfe89_46a9_2493_3545,
+        0x2af9_97a2_7369_091c,
+    ],
+    [
+        0xaa62_c88e_0b29_4011,
+        0x058e_b9d8_10ce_9f74,
+        0xb3cb_23ec_ed34_9ae4,
+        0xa364_8177_a77b_4a84,
+        0x4315_3d90_5992_d95d,
+        0xf4e2_a97c_da44_aa4b,
+        0x5baa_2702_b908_682f,
+        0x0829_23bd_f4f7_50d1,
+    ],
+    [
+        0x98ae_09a3_2589_3803,
+        0xf8a6_4750_7796_8838,
+        0xceb0_735b_f00b_2c5f,
+        0x0a1a_5d95_3888_e072,
+        0x2fcb_1904_89f9_4475,
+        0xb5be_0627_0dec_69fc,
+        0x739c_b934_b09a_cf8b,
+        0x5377_50b7_5ec7_f25b,
+    ],
+    [
+        0xe9dd_318b_ae1f_3961,
+        0xf746_2137_299e_fe1a,
+        0xb1f6_b8ee_e9ad_b940,
+        0xbdeb_cc8a_809d_fe6b,
+        0x40fc_1f79_1b17_8113,
+        0x3ac1_c336_2d01_4864,
+        0x9a01_6184_bdb8_aeba,
+        0x95f2_3944_59fb_c25e,
+    ],
+];
+
+/// External round constants of the first half of the width 12 permutation
+const INITIAL_CONSTANTS_12: [[u64; 12]; HALF_FULL_ROUNDS] = [
+    [
+        0x13dc_f33a_ba21_4f46,
+        0x30b3_b654_a1da_6d83,
+        0x1fc6_34ad_a615_9b56,
+        0x9374_5996_4dc0_3466,
+        0xedd2_ef2c_a794_9924,
+        0xede9_affd_e0e2_2f68,
+        0x8515_b9d6_bac9_282d,
+        0x6b5c_07b4_e9e9_00d8,
+        0x1ec6_6368_838c_8a08,
+        0x9042_367d_80d1_fbab,
+        0x4002_8356_4a3c_3799,
+        0x4a00_be04_66bc_a75e,
+    ],
+    [
+        0x7913_beee_58e3_817f,
+        0xf545_e885_3223_7d90,
+        0x22f8_cb87_3604_2005,
+        0x6f04_990e_247a_2623,
+        0xfe22_e87b_a37c_38cd,
+        0xd20e_32c8_5ffe_2815,
+        0x1172_2767_4048_fe73,
+        0x4e9f_b7ea_98a6_b145,
+        0xe086_6c23_2b8a_f08b,
+        0x00bb_c779_1688_4964,
+        0x7031_c0fb_990d_7116,
+        0x240a_9e87_cf35_108f,
+    ],
+    [
+        0x2e63_63a5_a122_44b3,
+        0x5e1c_3787_d1b5_011c,
+        0x4132_660e_2a19_6e8b,
+        0x3a01_3b64_8d3d_4327,
+        0xf798_39f4_9888_ea43,
+        0xfe85_658e_bafe_1439,
+        0xb688_9825_a142_40bd,
+        0x5784_5360_5541_382b,
+        0x4508_cda8_f6b6_3ce9,
+        0x9c3e_f358_4868_4c91,
+        0x0812_bde2_3c87_178c,
+        0xfe49_638f_7f72_2c14,
+    ],
+    [
+        0x8e3f_688c_e885_cbf5,
+        0xb8e1_10ac_f746_a87d,
+        0xb4b2_e897_3a6d_abef,
+        0x9e71_4c5d_a3d4_62ec,
+        0x6438_f903_3d3d_0c15,
+        0x2431_2f7c_f1a2_7199,
+        0x23f8_43bb_47ac_bf71,
+        0x9183_f11a_34be_9f01,
+        0x8390_62fb_b9d4_5dbf,
+        0x24b5_6e7e_6c2e_43fa,
+        0xe168_3da6_1c96_2a72,
+        0xa95c_6397_1a19_bfa7,
+    ],
+];
+
+/// Internal round constants of the width 12 permutation
+const INTERNAL_CONSTANTS_12: [u64; PARTIAL_ROUNDS] = [
+    0x4adf_842a_a75d_4316,
+    0xf8fb_b871_aa4a_b4eb,
+    0x68e8_5b6e_b2dd_6aeb,
+    0x07a0_b06b_2d27_0380,
+    0xd94e_0228_bd28_2de4,
+    0x8bdd_91d3_250c_5278,
+    0x209c_68b8_8bba_778f,
+    0xb5e1_8cda_b77f_3877,
+    0xb296_a3e8_08da_93fa,
+    0x8370_ecbd_a11a_327e,
+    0x3f90_7528_3775_dad8,
+    0xb780_95bb_23c6_aa84,
+    0x3f36_b9fe_72ad_4e5f,
+    0x69bc_9678_0b10_b553,
+    0x3f1d_341f_2eb7_b881,
+    0x4e93_9e98_1583_8818,
+    0xda36_6b3a_e2a3_1604,
+    0xbc89_db1e_7287_d509,
+    0x6102_f411_f9ef_5659,
+    0x5872_5c5e_7ac1_f0ab,
+    0x0df5_856c_7988_83e7,
+    0xf7bb_62a8_da4c_961b,
+];
+
+/// External round constants of the second half of the width 12 permutation
+const TERMINAL_CONSTANTS_12: [[u64; 12]; HALF_FULL_ROUNDS] = [
+    [
+        0xc68b_e7c9_4882_a24d,
+        0xaf99_6d5d_5cda_edd9,
+        0x9717_f025_e7da_f6a5,
+        0x6436_679e_6e72_16f4,
+        0x8a22_3d99_047a_f267,
+        0xbb51_2e35_a133_ba9a,
+        0xfbbf_4409_7671_aa03,
+        0xf040_58eb_f681_1e61,
+        0x5cca_8470_3fac_7ffb,
+        0x9b55_c794_5de6_469f,
+        0x8e05_bf09_808e_934f,
+        0x2ea9_00de_8763_07d7,
+    ],
+    [
+        0x7748_fff2_b38d_fb89,
+        0x6b99_a676_dd3b_5d81,
+        0xac4b_b7c6_27cf_7c13,
+        0xadb6_ebe5_e9e2_f5ba,
+        0x2d33_378c_afa2_4ae3,
+        0x1e5b_7380_7543_f8c2,
+        0x0920_8814_bfeb_b10f,
+        0x782e_64b6_bb5b_93dd,
+        0xadd5_a48e_ac90_b50f,
+        0xadd4_c54c_736e_a4b1,
+        0xd58d_bb86_ed81_7fd8,
+        0x6d5e_d1a5_33f3_4ddd,
+    ],
+    [
+        0x2868_6aa3_e36b_7cb9,
+        0x591a_bd34_7668_9f36,
+        0x047d_7666_78f1_3875,
+        0xa2a1_1112_625f_5b49,
+        0x21fd_10a3_f830_4958,
+        0xf9b4_0711_443b_0280,
+        0xd269_7eb8_b2bd_e88e,
+        0x3493_790b_5173_1b3f,
+        0x11ca_f9dd_7376_4023,
+        0x7acf_b8f7_2878_164e,
+        0x744e_c4db_23ce_fc26,
+        0x1e00_e58f_422c_6340,
+    ],
+    [
+        0x21dd_28d9_06a6_2dda,
+        0xf32a_46ab_5f46_5b5f,
+        0xbfce_1320_1f3f_7e6b,
+        0xf30d_2e7a_db53_04e2,
+        0xecdf_4ee4_abad_48e9,
+        0xf94e_8218_2d39_5019,
+        0x4ee5_2e37_44d8_87c5,
+        0xa134_1c7c_ac00_83b2,
+        0x2302_fb26_c30c_834a,
+        0xaea3_c587_273b_f7d3,
+        0xf798_e249_6182_3ec7,
+        0x962d_eba3_e9a2_cd94,
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        external_linear_layer, internal_linear_layer, Digest, Poseidon2, Poseidon2Sponge,
+        DIGEST_LEN,
+    };
+    use crate::fp64::Fp;
+    use ark_ff::{Field, PrimeField};
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    fn to_u64s(values: &[Fp]) -> Vec<u64> {
+        values.iter().map(|v| v.into_bigint().0[0]).collect()
+    }
+
+    /// Output of the Horizen Labs reference implementation on `[0, 1, ..., 7]`
+    #[test]
+    fn width_8_known_answer() {
+        let mut state: [Fp; 8] = core::array::from_fn(|i| Fp::from(i as u64));
+        Poseidon2::<8>::new().permute(&mut state);
+        assert_eq!(
+            to_u64s(&state),
+            [
+                0xc5fb_1cfe_0b46_97bb,
+                0x4a4a_32ff_849a_f473,
+                0xd2fd_2660_77f8_efba,
+                0xf4ad_9b74_e833_916d,
+                0xe664_8eb0_acc1_1463,
+                0x8d55_29a9_30d7_5194,
+                0xe8c9_93aa_10da_6c90,
+                0xa731_04a9_5b68_031c,
+            ]
+        );
+    }
+
+    /// Regression vector on `[0, 1, ..., 11]`. It shares every parameter
+    /// except the width with the cross-checked width 8 vector above.
+    #[test]
+    fn width_12_known_answer() {
+        let mut state: [Fp; 12] = core::array::from_fn(|i| Fp::from(i as u64));
+        Poseidon2::<12>::new().permute(&mut state);
+        assert_eq!(
+            to_u64s(&state),
+            [
+                0x01ea_ef96_bdf1_c0c1,
+                0x1f0d_2cc5_25b2_540c,
+                0x6282_c1df_e1e0_358d,
+                0xe780_d721_f698_e1e6,
+                0x280c_0b6f_753d_833b,
+                0x1b94_2dd5_0231_56ab,
+                0x43f0_df3f_cccb_8398,
+                0xe8e8_1905_8548_9025,
+                0x56bd_bf72_f77a_da22,
+                0x7911_c32b_f9dc_d705,
+                0xec46_7926_508f_be67,
+                0x6a50_450d_df85_a6ed,
+            ]
+        );
+    }
+
+    fn matrix_product<const WIDTH: usize>(
+        matrix: impl Fn(usize, usize) -> Fp,
+        state: &[Fp; WIDTH],
+    ) -> [Fp; WIDTH] {
+        core::array::from_fn(|i| (0..WIDTH).map(|j| matrix(i, j) * state[j]).sum())
+    }
+
+    #[test]
+    fn linear_layers_match_matrices() {
+        const M4: [[u64; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+        let mut rng = test_rng();
+        let state: [Fp; 12] = core::array::from_fn(|_| Fp::rand(&mut rng));
+
+        let external = |i: usize, j: usize| {
+            let entry = Fp::from(M4[i % 4][j % 4]);
+            if i / 4 == j / 4 {
+                entry.double()
+            } else {
+                entry
+            }
+        };
+        let mut actual = state;
+        external_linear_layer(&mut actual);
+        assert_eq!(actual, matrix_product(external, &state));
+
+        let diag = Poseidon2::<12>::new().internal_diag;
+        let internal = |i: usize, j: usize| if i == j { Fp::ONE + diag[i] } else { Fp::ONE };
+        let mut actual = state;
+        internal_linear_layer(&mut actual, &diag);
+        assert_eq!(actual, matrix_product(internal, &state));
+    }
+
+    #[test]
+    fn sponge_absorbs_incrementally() {
+        let mut rng = test_rng();
+        let elements: Vec<Fp> = (0..50).map(|_| Fp::rand(&mut rng)).collect();
+        for len in 0..elements.len() {
+            let expected = Poseidon2::<12>::new().hash(&elements[..len]);
+            for split in 0..=len {
+                let mut sponge = Poseidon2Sponge::new(Poseidon2::<12>::new());
+                sponge.absorb(&elements[..split]);
+                sponge.absorb(&elements[split..len]);
+                assert_eq!(sponge.finalize(), expected, "len = {len}, split = {split}");
+            }
+        }
+    }
+
+    #[test]
+    fn sponge_pads_input() {
+        let permutation = Poseidon2::<8>::new();
+        let mut state = [Fp::ZERO; 8];
+        state[0] = Fp::ONE;
+        permutation.permute(&mut state);
+        let expected: Digest = state[..DIGEST_LEN].try_into().unwrap();
+        assert_eq!(permutation.hash(&[]), expected);
+
+        // trailing zeros and block boundaries give distinct digests
+        let digests: Vec<Digest> = (0..10)
+            .map(|len| permutation.hash(&ark_std::vec![Fp::ZERO; len]))
+            .collect();
+        for (i, a) in digests.iter().enumerate() {
+            assert!(digests[i + 1..].iter().all(|b| a != b));
+        }
+    }
+}
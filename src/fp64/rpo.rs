@@ -0,0 +1,806 @@
+//! The Rescue Prime Optimized (RPO) permutation over [`Fp`] and the hash
+//! functions Miden builds on it.
+//!
+//! RPO (<https://eprint.iacr.org/2022/1577>) acts on a state of 12 elements
+//! and runs 7 rounds. Each round applies the MDS matrix, adds constants and
+//! applies the S-box `x^7`, then applies the MDS matrix again, adds another
+//! set of constants and applies the inverse S-box `x^{1/7}`. The MDS matrix
+//! is the circulant matrix with first row
+//!
+//! ```text
+//! [7 23 8 26 13 10 9 7 6 22 21 8]
+//! ```
+//!
+//! which has small powers of two as its eigenvalues, so it is applied with
+//! an FFT of length 12 over the integers instead of 144 field
+//! multiplications.
+//!
+//! The sponge matches `Rpo256` from `miden-crypto` 0.13. The capacity is the
+//! first 4 elements of the state, the rate the last 8 and the digest is read
+//! from the first 4 elements of the rate. Inputs overwrite the rate and the
+//! first capacity element encodes the length of the input modulo the rate,
+//! which pads the input and separates element hashing from byte hashing.
+
+use super::{exp_acc, into_mont, reduce_u96, Fp};
+use ark_ff::{BigInt, Field};
+use core::{marker::PhantomData, ops::Range};
+
+/// Number of elements in the state
+pub const STATE_WIDTH: usize = 12;
+
+/// Number of elements absorbed per permutation
+pub const RATE_WIDTH: usize = 8;
+
+/// Number of elements in a digest
+pub const DIGEST_LEN: usize = 4;
+
+/// Position of the capacity, which precedes the rate in the state
+const CAPACITY_RANGE: Range<usize> = 0..4;
+
+/// Position of the rate
+const RATE_RANGE: Range<usize> = 4..STATE_WIDTH;
+
+/// Position of the digest, the first half of the rate
+const DIGEST_RANGE: Range<usize> = 4..4 + DIGEST_LEN;
+
+/// Number of rounds of the permutation
+const NUM_ROUNDS: usize = 7;
+
+/// Number of bytes packed into an element by [`hash`]
+const BINARY_CHUNK_SIZE: usize = 7;
+
+/// A hash output
+pub type Digest = [Fp; DIGEST_LEN];
+
+/// Applies the RPO permutation to `state` in place.
+pub fn permute(state: &mut [Fp; STATE_WIDTH]) {
+    for (ark1, ark2) in SBOX_CONSTANTS.iter().zip(&INV_SBOX_CONSTANTS) {
+        apply_mds(state);
+        for (value, constant) in state.iter_mut().zip(ark1) {
+            *value = sbox(*value + constant);
+        }
+        apply_mds(state);
+        for (value, constant) in state.iter_mut().zip(ark2) {
+            *value = inv_sbox(*value + constant);
+        }
+    }
+}
+
+/// Hashes a sequence of bytes.
+///
+/// The bytes are packed into elements 7 at a time in little-endian order and
+/// the last chunk is followed by a one byte, so the result differs from
+/// hashing the same data as elements with [`hash_elements`]. The empty input
+/// hashes to the zero digest.
+#[must_use]
+pub fn hash(bytes: &[u8]) -> Digest {
+    let num_elements = bytes.len().div_ceil(BINARY_CHUNK_SIZE);
+    let mut state = [Fp::ZERO; STATE_WIDTH];
+    state[CAPACITY_RANGE.start] = into_mont((RATE_WIDTH + num_elements % RATE_WIDTH) as u64);
+    let mut position = RATE_RANGE.start;
+    for (i, chunk) in bytes.chunks(BINARY_CHUNK_SIZE).enumerate() {
+        let mut buf = [0; 8];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        if i == num_elements - 1 {
+            buf[chunk.len()] = 1;
+        }
+        state[position] = into_mont(u64::from_le_bytes(buf));
+        position += 1;
+        if position == RATE_RANGE.end {
+            permute(&mut state);
+            position = RATE_RANGE.start;
+        }
+    }
+    if position != RATE_RANGE.start {
+        state[position..RATE_RANGE.end].fill(Fp::ZERO);
+        permute(&mut state);
+    }
+    digest(&state)
+}
+
+/// Hashes a sequence of elements. The empty input hashes to the zero digest.
+#[must_use]
+pub fn hash_elements(elements: &[Fp]) -> Digest {
+    hash_elements_in_domain(elements, Fp::ZERO)
+}
+
+/// Hashes a sequence of elements with the domain identifier `domain` placed
+/// in the second capacity element. A zero domain gives [`hash_elements`].
+#[must_use]
+pub fn hash_elements_in_domain(elements: &[Fp], domain: Fp) -> Digest {
+    let mut state = [Fp::ZERO; STATE_WIDTH];
+    state[CAPACITY_RANGE.start] = into_mont((elements.len() % RATE_WIDTH) as u64);
+    state[CAPACITY_RANGE.start + 1] = domain;
+    for chunk in elements.chunks(RATE_WIDTH) {
+        let (input, padding) = state[RATE_RANGE].split_at_mut(chunk.len());
+        input.copy_from_slice(chunk);
+        padding.fill(Fp::ZERO);
+        permute(&mut state);
+    }
+    digest(&state)
+}
+
+/// Hashes two digests into one with a single permutation, as used for
+/// Merkle trees. This is the same as [`hash_elements`] on the 8 elements of
+/// the two digests.
+#[must_use]
+pub fn merge(values: &[Digest; 2]) -> Digest {
+    merge_in_domain(values, Fp::ZERO)
+}
+
+/// Hashes two digests into one with the domain identifier `domain` placed in
+/// the second capacity element. A zero domain gives [`merge`].
+#[must_use]
+pub fn merge_in_domain(values: &[Digest; 2], domain: Fp) -> Digest {
+    let mut state = [Fp::ZERO; STATE_WIDTH];
+    let (first, second) = state[RATE_RANGE].split_at_mut(DIGEST_LEN);
+    first.copy_from_slice(&values[0]);
+    second.copy_from_slice(&values[1]);
+    state[CAPACITY_RANGE.start + 1] = domain;
+    permute(&mut state);
+    digest(&state)
+}
+
+fn digest(state: &[Fp; STATE_WIDTH]) -> Digest {
+    let mut digest = [Fp::ZERO; DIGEST_LEN];
+    digest.copy_from_slice(&state[DIGEST_RANGE]);
+    digest
+}
+
+/// Computes `x^7 = x^3 * x^4`.
+#[inline]
+fn sbox(x: Fp) -> Fp {
+    let x2 = x.square();
+    x2 * x * x2.square()
+}
+
+/// Computes `x^{1/7} = x^10540996611094048183` with 72 multiplications on
+/// the Montgomery representation. The exponent is
+///
+/// ```text
+/// 0b1001001001001001001001001001000110110110110110110110110110110111
+/// ```
+///
+/// which is built from the repeating pattern `100` and the tail `111`.
+#[inline]
+const fn inv_sbox(x: Fp) -> Fp {
+    let x = (x.0).0[0];
+    let t1 = exp_acc::<0>(x, x); // 0b10
+    let t2 = exp_acc::<0>(t1, t1); // 0b100
+    let t3 = exp_acc::<3>(t2, t2); // 0b100100
+    let t4 = exp_acc::<6>(t3, t3); // (0b100)^4
+    let t5 = exp_acc::<12>(t4, t4); // (0b100)^8
+    let t6 = exp_acc::<6>(t5, t3); // (0b100)^10
+    let t7 = exp_acc::<31>(t6, t6); // (0b100)^10 || 0 || (0b100)^10
+    let head = exp_acc::<1>(t7, t6); // 0b1001...0001...1001
+    let tail = exp_acc::<0>(exp_acc::<0>(t1, t2), x); // 0b111
+    ark_ff::Fp(BigInt([exp_acc::<2>(head, tail)]), PhantomData)
+}
+
+/// Multiplies `state` by the MDS matrix.
+///
+/// The layer is linear, so it is applied to the Montgomery representations
+/// directly and the results are reduced modulo `p`. Every representation is
+/// split into 32-bit halves so the FFT over the integers cannot overflow.
+#[inline]
+fn apply_mds(state: &mut [Fp; STATE_WIDTH]) {
+    let mut lo = [0; STATE_WIDTH];
+    let mut hi = [0; STATE_WIDTH];
+    for ((lo, hi), value) in lo.iter_mut().zip(&mut hi).zip(state.iter()) {
+        let value = (value.0).0[0];
+        *lo = value & 0xffff_ffff;
+        *hi = value >> 32;
+    }
+    let lo = mds_multiply_freq(lo);
+    let hi = mds_multiply_freq(hi);
+    for ((value, lo), hi) in state.iter_mut().zip(lo).zip(hi) {
+        let sum = u128::from(lo) + (u128::from(hi) << 32);
+        *value = ark_ff::Fp(BigInt([reduce_u96(sum)]), PhantomData);
+    }
+}
+
+// The MDS matrix in the frequency domain. These are the three real FFTs of
+// length 4 of the first column, taken before the twiddle factors and the
+// final FFTs of length 3, scaled so the inverse FFTs need no division by 2.
+const MDS_FREQ_BLOCK_ONE: [i64; 3] = [16, 8, 16];
+const MDS_FREQ_BLOCK_TWO: [(i64, i64); 3] = [(-1, 2), (-1, 1), (4, 8)];
+const MDS_FREQ_BLOCK_THREE: [i64; 3] = [-8, 1, 1];
+
+/// Multiplies `state` by the MDS matrix with a 3x4 split FFT. The FFTs of
+/// length 3, the twiddle factors and the Hadamard product are folded into
+/// [`block1`], [`block2`] and [`block3`]. Entries of `state` must be below
+/// `2^32`.
+#[inline]
+const fn mds_multiply_freq(state: [u64; STATE_WIDTH]) -> [u64; STATE_WIDTH] {
+    let [s0, s1, s2, s3, s4, s5, s6, s7, s8, s9, s10, s11] = state;
+
+    let (u0, u1, u2) = fft4_real([s0, s3, s6, s9]);
+    let (u4, u5, u6) = fft4_real([s1, s4, s7, s10]);
+    let (u8, u9, u10) = fft4_real([s2, s5, s8, s11]);
+
+    let [v0, v4, v8] = block1([u0, u4, u8], MDS_FREQ_BLOCK_ONE);
+    let [v1, v5, v9] = block2([u1, u5, u9], MDS_FREQ_BLOCK_TWO);
+    let [v2, v6, v10] = block3([u2, u6, u10], MDS_FREQ_BLOCK_THREE);
+    // The fourth block is the complex conjugate of the second, which the real
+    // inverse FFTs don't need
+
+    let [s0, s3, s6, s9] = ifft4_real((v0, v1, v2));
+    let [s1, s4, s7, s10] = ifft4_real((v4, v5, v6));
+    let [s2, s5, s8, s11] = ifft4_real((v8, v9, v10));
+
+    [s0, s1, s2, s3, s4, s5, s6, s7, s8, s9, s10, s11]
+}
+
+#[inline]
+#[allow(clippy::cast_possible_wrap)]
+const fn fft2_real(x: [u64; 2]) -> [i64; 2] {
+    // inputs are below `2^32` so the casts are lossless
+    [(x[0] as i64 + x[1] as i64), (x[0] as i64 - x[1] as i64)]
+}
+
+#[inline]
+#[allow(clippy::cast_sign_loss)]
+const fn ifft2_real(y: [i64; 2]) -> [u64; 2] {
+    // the MDS matrix has positive entries so the outputs are non-negative
+    [(y[0] + y[1]) as u64, (y[0] - y[1]) as u64]
+}
+
+#[inline]
+const fn fft4_real(x: [u64; 4]) -> (i64, (i64, i64), i64) {
+    let [z0, z2] = fft2_real([x[0], x[2]]);
+    let [z1, z3] = fft2_real([x[1], x[3]]);
+    (z0 + z1, (z2, -z3), z0 - z1)
+}
+
+#[inline]
+const fn ifft4_real(y: (i64, (i64, i64), i64)) -> [u64; 4] {
+    let z0 = y.0 + y.2;
+    let z1 = y.0 - y.2;
+    let z2 = y.1 .0;
+    let z3 = -y.1 .1;
+    let [x0, x2] = ifft2_real([z0, z2]);
+    let [x1, x3] = ifft2_real([z1, z3]);
+    [x0, x1, x2, x3]
+}
+
+#[inline]
+const fn block1(x: [i64; 3], y: [i64; 3]) -> [i64; 3] {
+    let [x0, x1, x2] = x;
+    let [y0, y1, y2] = y;
+    [
+        x0 * y0 + x1 * y2 + x2 * y1,
+        x0 * y1 + x1 * y0 + x2 * y2,
+        x0 * y2 + x1 * y1 + x2 * y0,
+    ]
+}
+
+/// Computes `[x0*y0 - i*x1*y2 - i*x2*y1, x0*y1 + x1*y0 - i*x2*y2,
+/// x0*y2 + x1*y1 + x2*y0]` over the complex numbers, with Karatsuba for
+/// every product.
+#[inline]
+#[allow(clippy::similar_names)]
+const fn block2(x: [(i64, i64); 3], y: [(i64, i64); 3]) -> [(i64, i64); 3] {
+    let [(x0r, x0i), (x1r, x1i), (x2r, x2i)] = x;
+    let [(y0r, y0i), (y1r, y1i), (y2r, y2i)] = y;
+    let x0s = x0r + x0i;
+    let x1s = x1r + x1i;
+    let x2s = x2r + x2i;
+    let y0s = y0r + y0i;
+    let y1s = y1r + y1i;
+    let y2s = y2r + y2i;
+
+    let m0 = (x0r * y0r, x0i * y0i);
+    let m1 = (x1r * y2r, x1i * y2i);
+    let m2 = (x2r * y1r, x2i * y1i);
+    let z0r = (m0.0 - m0.1) + (x1s * y2s - m1.0 - m1.1) + (x2s * y1s - m2.0 - m2.1);
+    let z0i = (x0s * y0s - m0.0 - m0.1) + (-m1.0 + m1.1) + (-m2.0 + m2.1);
+
+    let m0 = (x0r * y1r, x0i * y1i);
+    let m1 = (x1r * y0r, x1i * y0i);
+    let m2 = (x2r * y2r, x2i * y2i);
+    let z1r = (m0.0 - m0.1) + (m1.0 - m1.1) + (x2s * y2s - m2.0 - m2.1);
+    let z1i = (x0s * y1s - m0.0 - m0.1) + (x1s * y0s - m1.0 - m1.1) + (-m2.0 + m2.1);
+
+    let m0 = (x0r * y2r, x0i * y2i);
+    let m1 = (x1r * y1r, x1i * y1i);
+    let m2 = (x2r * y0r, x2i * y0i);
+    let z2r = (m0.0 - m0.1) + (m1.0 - m1.1) + (m2.0 - m2.1);
+    let z2i = (x0s * y2s - m0.0 - m0.1) + (x1s * y1s - m1.0 - m1.1) + (x2s * y0s - m2.0 - m2.1);
+
+    [(z0r, z0i), (z1r, z1i), (z2r, z2i)]
+}
+
+#[inline]
+const fn block3(x: [i64; 3], y: [i64; 3]) -> [i64; 3] {
+    let [x0, x1, x2] = x;
+    let [y0, y1, y2] = y;
+    [
+        x0 * y0 - x1 * y2 - x2 * y1,
+        x0 * y1 + x1 * y0 - x2 * y2,
+        x0 * y2 + x1 * y1 + x2 * y0,
+    ]
+}
+
+const fn to_fp_rows(rows: &[[u64; STATE_WIDTH]; NUM_ROUNDS]) -> [[Fp; STATE_WIDTH]; NUM_ROUNDS] {
+    let mut res = [[into_mont(0); STATE_WIDTH]; NUM_ROUNDS];
+    let mut i = 0;
+    while i < NUM_ROUNDS {
+        let mut j = 0;
+        while j < STATE_WIDTH {
+            res[i][j] = into_mont(rows[i][j]);
+            j += 1;
+        }
+        i += 1;
+    }
+    res
+}
+
+/// [`ARK1`] in Montgomery form
+const SBOX_CONSTANTS: [[Fp; STATE_WIDTH]; NUM_ROUNDS] = to_fp_rows(&ARK1);
+
+/// [`ARK2`] in Montgomery form
+const INV_SBOX_CONSTANTS: [[Fp; STATE_WIDTH]; NUM_ROUNDS] = to_fp_rows(&ARK2);
+
+/// Round constants added before the `x^7` S-box, as published by Miden
+const ARK1: [[u64; STATE_WIDTH]; NUM_ROUNDS] = [
+    [
+        0x5059_5e24_6042_3080,
+        0x5a84_ce18_5f5b_ae97,
+        0xf729_73c2_3aa6_f9cb,
+        0x017c_a808_1f61_7c3c,
+        0x58aa_35ad_e942_4046,
+        0xdbe1_6fa8_b27f_aecb,
+        0x8a6e_521e_04cc_3f3f,
+        0x2e6b_c556_8c88_1614,
+        0x8a36_2633_0baa_9677,
+        0xb3dd_eacc_fbf5_a691,
+        0x8544_67ac_e60e_8a1b,
+        0xe72b_7a87_bed1_31f4,
+    ],
+    [
+        0xb43b_c4a4_deb5_d7a5,
+        0x0913_5300_915c_4f81,
+        0x3da3_ed63_dae7_f669,
+        0x380a_98ac_c7db_7371,
+        0x4de7_085b_5365_a926,
+        0xb781_7f19_1d43_2dd5,
+        0x2a28_4bb7_bfcb_3755,
+        0xe788_9f13_dd9b_ea2b,
+        0x73b4_44df_687f_ed0b,
+        0x2cca_04f1_82db_3a00,
+        0x708e_51f9_a189_3e3a,
+        0x27db_abfd_dab5_9589,
+    ],
+    [
+        0xfacf_6ea8_cd7f_5ebf,
+        0x560e_4919_ef81_a7e9,
+        0xf563_6930_8450_0b1b,
+        0x9319_157e_04fa_6d58,
+        0x0d87_e8db_62da_4d1a,
+        0x72b0_7b7d_0a30_60d1,
+        0x8bb0_c6ef_ce68_2ae2,
+        0x1e44_efc3_f951_c7b5,
+        0x57ab_9282_afc2_8a97,
+        0x1372_eb1b_d827_429c,
+        0x7b4b_f8c7_6437_d9b6,
+        0xb556_f49d_65b5_affc,
+    ],
+    [
+        0x4ec0_8822_d164_9af2,
+        0x4fec_612a_e8a2_0297,
+        0xc180_7db3_d406_eec9,
+        0x12c5_edbb_56d8_25e2,
+        0xed76_2ceb_74d6_2145,
+        0x0dee_82fe_5a88_0aa6,
+        0x397a_e162_d2d8_27b3,
+        0x70b5_0c40_15e6_7d10,
+        0xc675_a5e7_9671_61e9,
+        0xbe4b_9df1_676f_dba5,
+        0xec39_c511_47ca_6f4b,
+        0x56c3_e89e_2d94_dc42,
+    ],
+    [
+        0x43d4_4740_17ee_f67a,
+        0x2a02_792d_f9c4_708c,
+        0x8528_a357_11d4_9dd3,
+        0x921c_fe7a_0d54_80ef,
+        0x6d24_fd14_5d1a_cea7,
+        0xf354_4cec_7c8f_b490,
+        0x503c_812a_00ba_9267,
+        0xec41_ad6d_8ae8_801e,
+        0x0185_96a3_2ae6_3fc7,
+        0x6359_a43c_0ec3_956d,
+        0x2902_8ad6_2f22_f702,
+        0x6729_e445_d0ce_55d9,
+    ],
+    [
+        0xe254_ba7b_438c_b541,
+        0xa637_8971_bfbf_b3da,
+        0xadeb_7834_c155_923f,
+        0xca8b_77f9_9f83_4e42,
+        0x6531_9f21_e977_97b8,
+        0x4c88_374b_5dd3_159d,
+        0x8b22_8fd2_4a33_7113,
+        0x6538_c386_d1e5_5bfd,
+        0x5d60_9f3f_4a01_143c,
+        0x57e1_26a4_f4cf_409e,
+        0xb843_cef8_c2fa_f7e4,
+        0x2417_d2a2_7b45_b944,
+    ],
+    [
+        0x62da_3f97_91d3_ab16,
+        0x0e5a_3c77_9411_8cf2,
+        0x6b1b_386a_e880_f795,
+        0x29e5_e505_b3f5_a91a,
+        0x9e42_6915_297d_f504,
+        0x8eab_f5c5_51ce_1736,
+        0x04ad_cf0e_c4e3_f6e2,
+        0xb909_bf5a_cd54_f805,
+        0x31e8_1abb_ef89_ddf8,
+        0x7077_eea8_de2e_5d38,
+        0xc713_e626_1be1_babd,
+        0xec6e_a103_9669_e548,
+    ],
+];
+
+/// Round constants added before the `x^{1/7}` S-box, as published by Miden
+const ARK2: [[u64; STATE_WIDTH]; NUM_ROUNDS] = [
+    [
+        0x5456_1062_7c0e_253f,
+        0xd405_0299_cc1d_7937,
+        0x4a5e_0fee_fb89_88e1,
+        0xc586_c831_8133_2146,
+        0xbf69_1615_416d_26e5,
+        0xa130_1a47_1213_5881,
+        0xce60_a1a2_007b_bdae,
+        0x8cd5_c7eb_4e3b_7a2b,
+        0x3ddb_f804_0326_e3f7,
+        0xd85d_99ae_231f_d27b,
+        0x8ba8_176a_640f_a5c7,
+        0xc510_d079_0d44_1656,
+    ],
+    [
+        0x5615_4cc2_3dc0_375c,
+        0xf580_00ef_967c_75f3,
+        0x31e4_02e2_b72c_1deb,
+        0x0530_b354_a02c_ccb7,
+        0x1126_35e2_9826_1f0d,
+        0xc4af_bfd1_41f5_b352,
+        0x09d1_cdfd_29d8_7590,
+        0xd674_db73_e2d2_91ff,
+        0x030c_ced0_1922_3fa2,
+        0xf816_c89c_b0be_2bd0,
+        0xf613_5fec_5026_4fd2,
+        0x2834_b20e_fce7_52b8,
+    ],
+    [
+        0x6f58_c0a8_643b_651f,
+        0xd05b_57d2_3a80_df96,
+        0x3e3f_b288_55ba_eb0d,
+        0xad54_7620_3073_cf51,
+        0x83d8_634a_9820_15b0,
+        0x1c81_4756_1adb_f416,
+        0xac5f_3488_c1ee_4e2a,
+        0x04f0_bbdd_f9fd_028b,
+        0x7de3_771f_68fe_af14,
+        0xb12a_a71a_8096_d2d8,
+        0x7d89_c621_6fe0_8363,
+        0xb380_5512_5e76_c95a,
+    ],
+    [
+        0xff33_b8d6_6ff1_c2c4,
+        0xe833_1207_b185_b3eb,
+        0x3d9e_cb3a_80a1_35f0,
+        0xeed0_b078_f2cf_1cea,
+        0x7948_ecee_bc83_b020,
+        0xebee_e2b7_c12e_c72f,
+        0xbbcf_e0c6_3695_5337,
+        0x074a_9b1b_5c66_2c37,
+        0xebbf_df02_e951_8234,
+        0x4bdd_ff91_d264_912e,
+        0xc967_b70b_e3bf_f877,
+        0x984b_52de_2f0e_a2ae,
+    ],
+    [
+        0x60e6_16ff_ff6d_6221,
+        0xc332_6eb1_c066_f68b,
+        0xe450_b290_06e2_a864,
+        0x6313_50b9_87e2_7ae7,
+        0x7d11_141c_0755_e6d7,
+        0xcae3_4d92_dc29_f5c1,
+        0x135b_5f37_0979_e6d3,
+        0x4053_3906_04d1_5b1f,
+        0xe110_0aa2_bedb_ac65,
+        0x95a1_6c73_8e50_183b,
+        0x5efb_96ea_7a0b_1962,
+        0x6757_3c0c_226b_f3d7,
+    ],
+    [
+        0x33db_c0d5_d695_4218,
+        0x0804_f188_5d65_a6ed,
+        0x5cd7_a60a_805f_62dd,
+        0xc0b5_3529_a6f8_4a34,
+        0xc743_a850_c9c4_3478,
+        0x6b78_b89a_3847_d5f4,
+        0xdfeb_4958_cce4_67db,
+        0xaa92_0eb9_1c6b_33a1,
+        0xa75d_6b94_7c97_c6ec,
+        0xe45f_85a2_5b0b_6767,
+        0x3f71_2dd1_8a72_ba74,
+        0xeeb4_117d_f819_ac88,
+    ],
+    [
+        0xedbb_6646_3142_ccad,
+        0x0736_a3c1_3b07_ede4,
+        0x8594_3c1b_27ad_eb26,
+        0x171a_e369_0f17_f576,
+        0x69e1_b308_6dbd_2562,
+        0x305e_0ef3_9ce8_6971,
+        0x8763_e3e6_8d1c_072e,
+        0xd331_b927_7f5c_d123,
+        0xe46f_d0dd_b6c1_d138,
+        0x85d6_41ec_b35b_eee3,
+        0x321e_1684_863d_6bc3,
+        0xfd5b_b083_0a60_d1dc,
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        apply_mds, hash, hash_elements, hash_elements_in_domain, inv_sbox, merge, merge_in_domain,
+        permute, sbox, Digest, BINARY_CHUNK_SIZE, CAPACITY_RANGE, DIGEST_LEN, DIGEST_RANGE,
+        RATE_RANGE, RATE_WIDTH, STATE_WIDTH,
+    };
+    use crate::fp64::Fp;
+    use ark_ff::{Field, PrimeField};
+    use ark_std::{test_rng, vec, vec::Vec, UniformRand};
+
+    /// First row of the circulant MDS matrix
+    const MDS_ROW: [u64; STATE_WIDTH] = [7, 23, 8, 26, 13, 10, 9, 7, 6, 22, 21, 8];
+
+    fn to_u64s(values: &[Fp]) -> Vec<u64> {
+        values.iter().map(|v| v.into_bigint().0[0]).collect()
+    }
+
+    #[test]
+    fn inv_sbox_inverts_sbox() {
+        let mut rng = test_rng();
+        for _ in 0..100 {
+            let x = Fp::rand(&mut rng);
+            let y = inv_sbox(x);
+            assert_eq!(y, x.pow([10_540_996_611_094_048_183]));
+            assert_eq!(sbox(y), x);
+            assert_eq!(inv_sbox(sbox(x)), x);
+        }
+        assert_eq!(inv_sbox(Fp::ZERO), Fp::ZERO);
+        assert_eq!(inv_sbox(-Fp::ONE), -Fp::ONE);
+    }
+
+    #[test]
+    fn mds_matches_matrix() {
+        let mut rng = test_rng();
+        let mut inputs = vec![[-Fp::ONE; STATE_WIDTH], [Fp::ZERO; STATE_WIDTH]];
+        inputs.extend((0..100).map(|_| [(); STATE_WIDTH].map(|()| Fp::rand(&mut rng))));
+        for input in inputs {
+            let expected: [Fp; STATE_WIDTH] = core::array::from_fn(|i| {
+                (0..STATE_WIDTH)
+                    .map(|j| Fp::from(MDS_ROW[(STATE_WIDTH + j - i) % STATE_WIDTH]) * input[j])
+                    .sum()
+            });
+            let mut state = input;
+            apply_mds(&mut state);
+            assert_eq!(state, expected);
+        }
+    }
+
+    /// `EXPECTED` of the `hash_test_vectors` test in `miden-crypto` 0.13, the
+    /// output of `Rpo256::hash_elements` on `[0, 1, ..., i]` for `i` in `0..19`
+    const HASH_ELEMENTS_KAT: [[u64; DIGEST_LEN]; 19] = [
+        [
+            0xfb8f_1676_d9ca_02c3,
+            0x6689_6d7b_7f09_46cd,
+            0xc652_f1e0_9e81_e0ef,
+            0x6cf8_e8f4_45c2_3792,
+        ],
+        [
+            0x8cb6_0a66_62d7_c4d7,
+            0x1fd5_a0f4_c58a_74bd,
+            0xd70e_229a_c696_4c1f,
+            0xf87b_27cc_6628_8ca4,
+        ],
+        [
+            0xf245_a9d4_6388_4e5f,
+            0x0b28_593f_8547_681e,
+            0x9022_813a_252a_abee,
+            0xfc24_9281_e452_24fe,
+        ],
+        [
+            0xb56a_d8cb_e625_5ffc,
+            0x8d32_8d6c_0e77_5339,
+            0x7fb2_d567_250a_b509,
+            0x5f5f_e3ac_b5b8_453a,
+        ],
+        [
+            0x284c_61c3_28f8_1252,
+            0x1984_fb34_7d26_aee3,
+            0x3b19_a78a_d15e_a09c,
+            0xf08f_a9fd_0fca_4618,
+        ],
+        [
+            0x76f3_1817_8ce6_a1ee,
+            0x29dd_19b4_b451_ec79,
+            0xc15e_5af1_b6b6_8cf6,
+            0x096b_03d3_5428_7970,
+        ],
+        [
+            0xe267_603a_5eb9_98a6,
+            0xca71_a541_7e78_d143,
+            0x26fa_895f_a11c_6c1e,
+            0x93f1_c434_7125_ee60,
+        ],
+        [
+            0x1f1e_938d_5e3e_8344,
+            0xb019_bdbe_bfda_c84a,
+            0x0343_bacb_cc0b_43fa,
+            0x4607_805b_cd64_5bbb,
+        ],
+        [
+            0x486a_547a_b2a0_550c,
+            0xee45_f6d9_8e9e_7c28,
+            0x7aae_41e5_7ce9_b2be,
+            0xabd7_0649_c40a_67c7,
+        ],
+        [
+            0x87c7_29b1_586d_599e,
+            0xaaef_b588_c7de_6e61,
+            0x3697_7bcf_c478_b624,
+            0x1993_cdde_eb30_69de,
+        ],
+        [
+            0xc934_136f_1891_f167,
+            0xe9a9_f4a4_a237_b013,
+            0x5c7b_c90b_3be3_48e3,
+            0x3fb4_9731_52bd_24e2,
+        ],
+        [
+            0x40b2_0ae9_8c54_e423,
+            0xa3ae_272e_0866_c711,
+            0x7d6c_82b3_46a7_3820,
+            0x5742_d3b8_4007_0523,
+        ],
+        [
+            0x6b97_1949_13a7_4201,
+            0x666a_852e_0dc2_bf10,
+            0x80a6_7881_83ea_3872,
+            0x93a2_d6d6_f0b9_65fa,
+        ],
+        [
+            0xa02f_dc74_7443_ba7d,
+            0xd816_4ac9_7138_0c03,
+            0x6858_25af_9c5b_f3ed,
+            0xb056_55d0_3117_039b,
+        ],
+        [
+            0x8496_5afc_bb3f_6782,
+            0x650b_fc90_84e8_32e2,
+            0x2288_d97b_3cea_54e3,
+            0x469f_99ed_bca7_e685,
+        ],
+        [
+            0x447e_2807_1484_43f3,
+            0xaea4_2aec_29bd_a193,
+            0x799a_b893_ba8e_2b7e,
+            0xfc04_d6bd_d247_ac79,
+        ],
+        [
+            0xb192_7f76_8438_b916,
+            0xc749_d9b7_ade9_e9ef,
+            0x3501_d116_3d6a_7982,
+            0x96b3_5b5d_9454_c49b,
+        ],
+        [
+            0x2569_33a0_cca9_dabd,
+            0x7f00_b72d_eb4e_a7ef,
+            0xdc0b_3795_b2fd_cd1d,
+            0xee8f_4103_6169_2f2a,
+        ],
+        [
+            0xb742_8ded_696b_5c5e,
+            0x230a_3dcb_b372_4145,
+            0xcae0_48ba_4949_3d5b,
+            0x9352_3cee_1c68_b27a,
+        ],
+    ];
+
+    #[test]
+    fn hash_elements_known_answers() {
+        let elements = (0..19).map(Fp::from).collect::<Vec<_>>();
+        for (i, expected) in HASH_ELEMENTS_KAT.iter().enumerate() {
+            assert_eq!(to_u64s(&hash_elements(&elements[..=i])), expected);
+        }
+    }
+
+    #[test]
+    fn merge_matches_hash_elements() {
+        let mut rng = test_rng();
+        let elements = (0..RATE_WIDTH)
+            .map(|_| Fp::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let mut digests: [Digest; 2] = Default::default();
+        digests[0].copy_from_slice(&elements[..DIGEST_LEN]);
+        digests[1].copy_from_slice(&elements[DIGEST_LEN..]);
+        assert_eq!(merge(&digests), hash_elements(&elements));
+    }
+
+    #[test]
+    fn domain_separation() {
+        let mut rng = test_rng();
+        let digests: [Digest; 2] = [(); 2].map(|()| [(); DIGEST_LEN].map(|()| Fp::rand(&mut rng)));
+        assert_eq!(merge_in_domain(&digests, Fp::ZERO), merge(&digests));
+        assert_ne!(merge_in_domain(&digests, Fp::ONE), merge(&digests));
+
+        let elements = (1..=16).map(Fp::from).collect::<Vec<_>>();
+        assert_eq!(
+            hash_elements_in_domain(&elements, Fp::ZERO),
+            hash_elements(&elements)
+        );
+        assert_ne!(
+            hash_elements_in_domain(&elements, Fp::ONE),
+            hash_elements(&elements)
+        );
+    }
+
+    #[test]
+    fn empty_inputs_hash_to_zero() {
+        assert_eq!(hash_elements(&[]), [Fp::ZERO; DIGEST_LEN]);
+        assert_eq!(hash(&[]), [Fp::ZERO; DIGEST_LEN]);
+    }
+
+    #[test]
+    fn hash_elements_padding() {
+        let elements = [Fp::from(5u64), Fp::from(7u64)];
+        let padded = [elements[0], elements[1], Fp::ZERO];
+        assert_ne!(hash_elements(&elements), hash_elements(&padded));
+    }
+
+    #[test]
+    fn hash_bytes_padding() {
+        let pairs: [(&[u8], &[u8]); 4] = [
+            (&[1, 2, 3], &[1, 2, 3, 0]),
+            (&[1, 2, 3, 4, 5, 6], &[1, 2, 3, 4, 5, 6, 0]),
+            (&[1, 2, 3, 4, 5, 6, 7], &[1, 2, 3, 4, 5, 6, 7, 0]),
+            (
+                &[1, 2, 3, 4, 5, 6, 7, 0, 0],
+                &[1, 2, 3, 4, 5, 6, 7, 0, 0, 0, 0],
+            ),
+        ];
+        for (a, b) in pairs {
+            assert_ne!(hash(a), hash(b));
+        }
+
+        let mut zeros = Vec::new();
+        let mut digests = Vec::new();
+        for _ in 0..64 {
+            let digest = hash(&zeros);
+            assert!(!digests.contains(&digest));
+            digests.push(digest);
+            zeros.push(0);
+        }
+    }
+
+    /// A full rate of bytes is absorbed with a single permutation and the
+    /// padding byte lands in the last rate element
+    #[test]
+    fn hash_bytes_full_rate() {
+        let mut bytes = vec![0; BINARY_CHUNK_SIZE * RATE_WIDTH];
+        *bytes.last_mut().unwrap() = 97;
+
+        let mut state = [Fp::ZERO; STATE_WIDTH];
+        state[CAPACITY_RANGE.start] = Fp::from(RATE_WIDTH as u64);
+        state[RATE_RANGE.end - 1] = Fp::from(u64::from_le_bytes([0, 0, 0, 0, 0, 0, 97, 1]));
+        permute(&mut state);
+
+        assert_eq!(hash(&bytes), state[DIGEST_RANGE]);
+    }
+}
@@ -0,0 +1,648 @@
+//! The Tip5 permutation over [`Fp`] and the sponge Triton VM builds on it.
+//!
+//! Tip5 (<https://eprint.iacr.org/2023/107>) acts on a state of 16 elements
+//! and runs 5 rounds. Each round applies the S-box layer, multiplies by a
+//! circulant MDS matrix and adds round constants. The S-box layer maps the
+//! first 4 elements through split-and-lookup and raises the other 12 to the
+//! power 7.
+//!
+//! Split-and-lookup replaces every byte of the Montgomery representation
+//! with `(x + 1)^3 - 1 mod 257`, which fixes 0 and 255 and therefore maps
+//! representations below `p` to representations below `p`. [`Fp`] stores
+//! elements as `x * 2^64 mod p`, the same Montgomery form Triton uses, so the
+//! lookup operates on the stored limb directly.
+//!
+//! The sponge matches `Tip5` from `twenty-first`. The rate is the first 10
+//! elements of the state, the capacity the last 6 and digests are the first
+//! 5. Variable length inputs start from a zero capacity and are padded with
+//! a one followed by zeros, fixed length inputs start from an all ones
+//! capacity and are not padded.
+
+use super::{reduce_u96, Fp};
+use ark_ff::{BigInt, Field};
+use core::{array, marker::PhantomData};
+
+/// Number of elements in the state
+pub const STATE_WIDTH: usize = 16;
+
+/// Number of elements absorbed or squeezed per permutation
+pub const RATE_WIDTH: usize = 10;
+
+/// Number of elements in a digest
+pub const DIGEST_LEN: usize = 5;
+
+/// Number of rounds of the permutation
+const NUM_ROUNDS: usize = 5;
+
+/// Number of elements that go through split-and-lookup in every round
+const NUM_SPLIT_AND_LOOKUP: usize = 4;
+
+/// A hash output
+pub type Digest = [Fp; DIGEST_LEN];
+
+/// How the capacity of a [`Tip5Sponge`] is initialized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Domain {
+    /// For inputs that may be longer than the rate. The capacity starts as
+    /// zeros.
+    VariableLength,
+    /// For inputs that always fit in the rate, such as a pair of digests. The
+    /// capacity starts as ones.
+    FixedLength,
+}
+
+/// A sponge over the Tip5 permutation with rate [`RATE_WIDTH`].
+///
+/// Absorbing overwrites the rate and permutes. Squeezing returns the rate and
+/// then permutes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tip5Sponge {
+    state: [Fp; STATE_WIDTH],
+}
+
+impl Tip5Sponge {
+    /// Creates a sponge with a zero rate and the capacity for `domain`.
+    #[must_use]
+    pub fn new(domain: Domain) -> Self {
+        let mut state = [Fp::ZERO; STATE_WIDTH];
+        if domain == Domain::FixedLength {
+            state[RATE_WIDTH..].fill(Fp::ONE);
+        }
+        Self { state }
+    }
+
+    /// Overwrites the rate with `input` and permutes.
+    pub fn absorb(&mut self, input: &[Fp; RATE_WIDTH]) {
+        self.state[..RATE_WIDTH].copy_from_slice(input);
+        permute(&mut self.state);
+    }
+
+    /// Returns the rate and permutes.
+    pub fn squeeze(&mut self) -> [Fp; RATE_WIDTH] {
+        let mut output = [Fp::ZERO; RATE_WIDTH];
+        output.copy_from_slice(&self.state[..RATE_WIDTH]);
+        permute(&mut self.state);
+        output
+    }
+
+    /// Pads `input` with a one followed by zeros up to a multiple of the rate
+    /// and absorbs it. The padding is always at least one element.
+    pub fn pad_and_absorb_all(&mut self, input: &[Fp]) {
+        let mut chunks = input.chunks_exact(RATE_WIDTH);
+        for chunk in &mut chunks {
+            self.state[..RATE_WIDTH].copy_from_slice(chunk);
+            permute(&mut self.state);
+        }
+        let remainder = chunks.remainder();
+        let mut last = [Fp::ZERO; RATE_WIDTH];
+        last[..remainder.len()].copy_from_slice(remainder);
+        last[remainder.len()] = Fp::ONE;
+        self.absorb(&last);
+    }
+
+    fn digest(&self) -> Digest {
+        let mut digest = [Fp::ZERO; DIGEST_LEN];
+        digest.copy_from_slice(&self.state[..DIGEST_LEN]);
+        digest
+    }
+}
+
+/// Hashes exactly [`RATE_WIDTH`] elements in the fixed length domain.
+#[must_use]
+pub fn hash_10(input: &[Fp; RATE_WIDTH]) -> Digest {
+    let mut sponge = Tip5Sponge::new(Domain::FixedLength);
+    sponge.absorb(input);
+    sponge.digest()
+}
+
+/// Hashes two digests into one, as used for Merkle trees. This is
+/// [`hash_10`] on the concatenation of the digests.
+#[must_use]
+pub fn hash_pair(left: &Digest, right: &Digest) -> Digest {
+    let mut input = [Fp::ZERO; RATE_WIDTH];
+    input[..DIGEST_LEN].copy_from_slice(left);
+    input[DIGEST_LEN..].copy_from_slice(right);
+    hash_10(&input)
+}
+
+/// Hashes a sequence of elements of any length in the variable length
+/// domain. This differs from [`hash_10`] even on inputs that are the same
+/// after padding, because the capacities start out differently.
+#[must_use]
+pub fn hash_varlen(input: &[Fp]) -> Digest {
+    let mut sponge = Tip5Sponge::new(Domain::VariableLength);
+    sponge.pad_and_absorb_all(input);
+    sponge.digest()
+}
+
+/// Applies the Tip5 permutation to `state` in place.
+pub fn permute(state: &mut [Fp; STATE_WIDTH]) {
+    for constants in &ROUND_CONSTANTS_MONT {
+        let (lookup, power) = state.split_at_mut(NUM_SPLIT_AND_LOOKUP);
+        for value in lookup {
+            *value = split_and_lookup(*value);
+        }
+        for value in power {
+            let x2 = value.square();
+            *value *= x2 * x2.square();
+        }
+        apply_mds(state);
+        for (value, constant) in state.iter_mut().zip(constants) {
+            *value += constant;
+        }
+    }
+}
+
+/// Maps every byte of the Montgomery representation through
+/// [`LOOKUP_TABLE`].
+#[inline]
+fn split_and_lookup(x: Fp) -> Fp {
+    let bytes = (x.0).0[0]
+        .to_le_bytes()
+        .map(|byte| LOOKUP_TABLE[byte as usize]);
+    ark_ff::Fp(BigInt([u64::from_le_bytes(bytes)]), PhantomData)
+}
+
+/// Multiplies `state` by the MDS matrix.
+///
+/// The layer is linear, so it is applied to the Montgomery representations
+/// directly and the results are reduced modulo `p`. Every representation is
+/// split into 32-bit halves so the convolutions over the integers cannot
+/// overflow.
+#[inline]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn apply_mds(state: &mut [Fp; STATE_WIDTH]) {
+    let mut lo = [0; STATE_WIDTH];
+    let mut hi = [0; STATE_WIDTH];
+    for ((lo, hi), value) in lo.iter_mut().zip(&mut hi).zip(state.iter()) {
+        let value = (value.0).0[0];
+        *lo = i64::from(value as u32);
+        *hi = i64::from((value >> 32) as u32);
+    }
+    let lo = cyclomul16(lo, MDS_FIRST_COLUMN);
+    let hi = cyclomul16(hi, MDS_FIRST_COLUMN);
+    for ((value, lo), hi) in state.iter_mut().zip(lo).zip(hi) {
+        // the MDS matrix has positive entries so the products are
+        // non-negative
+        let sum = lo as u128 + ((hi as u128) << 32);
+        *value = ark_ff::Fp(BigInt([reduce_u96(sum)]), PhantomData);
+    }
+}
+
+// The MDS matrix is circulant, so multiplying by it is a cyclic convolution
+// with its first column. A cyclic convolution modulo `x^N - 1` is split
+// into one modulo `x^(N/2) - 1` and a negacyclic one modulo `x^(N/2) + 1`,
+// and the latter is computed as a complex convolution of half the length
+// with Karatsuba multiplication.
+
+type Complex = (i64, i64);
+
+#[inline]
+fn cyclomul16(f: [i64; 16], g: [i64; 16]) -> [i64; 16] {
+    let ((f_lo, f_hi), (g_lo, g_hi)) = (split(&f), split(&g));
+    combine(&cyclomul8(f_lo, g_lo), &negacyclomul8(f_hi, g_hi))
+}
+
+#[inline]
+fn cyclomul8(f: [i64; 8], g: [i64; 8]) -> [i64; 8] {
+    let ((f_lo, f_hi), (g_lo, g_hi)) = (split(&f), split(&g));
+    combine(&cyclomul4(f_lo, g_lo), &negacyclomul4(f_hi, g_hi))
+}
+
+#[inline]
+fn cyclomul4(f: [i64; 4], g: [i64; 4]) -> [i64; 4] {
+    let ((f_lo, f_hi), (g_lo, g_hi)) = (split(&f), split(&g));
+    combine(&cyclomul2(f_lo, g_lo), &negacyclomul2(f_hi, g_hi))
+}
+
+#[inline]
+const fn cyclomul2(f: [i64; 2], g: [i64; 2]) -> [i64; 2] {
+    let lo = (f[0] + f[1]) * (g[0] + g[1]);
+    let hi = (f[0] - f[1]) * (g[0] - g[1]);
+    [(lo + hi) >> 1, (lo - hi) >> 1]
+}
+
+#[inline]
+fn negacyclomul8(f: [i64; 8], g: [i64; 8]) -> [i64; 8] {
+    let h = complex_karatsuba4(to_complex(&f), to_complex(&g));
+    from_complex::<8, 4, 7>(&h)
+}
+
+#[inline]
+fn negacyclomul4(f: [i64; 4], g: [i64; 4]) -> [i64; 4] {
+    let h = complex_karatsuba2(to_complex(&f), to_complex(&g));
+    from_complex::<4, 2, 3>(&h)
+}
+
+#[inline]
+const fn negacyclomul2(f: [i64; 2], g: [i64; 2]) -> [i64; 2] {
+    let h = complex_mul((f[0], -f[1]), (g[0], -g[1]));
+    [h.0, -h.1]
+}
+
+/// Reduces `f` modulo `x^H - 1` and `x^H + 1` where `N = 2 * H`.
+#[inline]
+fn split<const N: usize, const H: usize>(f: &[i64; N]) -> ([i64; H], [i64; H]) {
+    (
+        array::from_fn(|i| f[i] + f[i + H]),
+        array::from_fn(|i| f[i] - f[i + H]),
+    )
+}
+
+/// Recovers the product modulo `x^N - 1` from the products modulo
+/// `x^H - 1` and `x^H + 1`.
+#[inline]
+fn combine<const N: usize, const H: usize>(lo: &[i64; H], hi: &[i64; H]) -> [i64; N] {
+    array::from_fn(|i| {
+        if i < H {
+            (lo[i] + hi[i]) >> 1
+        } else {
+            (lo[i - H] - hi[i - H]) >> 1
+        }
+    })
+}
+
+/// Writes `f = f_0 + x^H f_1` modulo `x^N + 1` as `f_0 - i f_1`.
+#[inline]
+fn to_complex<const N: usize, const H: usize>(f: &[i64; N]) -> [Complex; H] {
+    array::from_fn(|i| (f[i], -f[i + H]))
+}
+
+/// Inverse of [`to_complex`] applied to a product of length `2 * H - 1`,
+/// reduced modulo `x^N + 1`.
+#[inline]
+fn from_complex<const N: usize, const H: usize, const M: usize>(h: &[Complex; M]) -> [i64; N] {
+    let mut res = [0; N];
+    for (i, &(re, im)) in h.iter().enumerate() {
+        res[i] += re;
+        if i + H < N {
+            res[i + H] -= im;
+        } else {
+            res[i + H - N] += im;
+        }
+    }
+    res
+}
+
+#[inline]
+fn complex_karatsuba4(f: [Complex; 4], g: [Complex; 4]) -> [Complex; 7] {
+    let (f_lo, f_hi) = ([f[0], f[1]], [f[2], f[3]]);
+    let (g_lo, g_hi) = ([g[0], g[1]], [g[2], g[3]]);
+    let lo = complex_karatsuba2(f_lo, g_lo);
+    let hi = complex_karatsuba2(f_hi, g_hi);
+    let mid = complex_karatsuba2(
+        [complex_add(f_lo[0], f_hi[0]), complex_add(f_lo[1], f_hi[1])],
+        [complex_add(g_lo[0], g_hi[0]), complex_add(g_lo[1], g_hi[1])],
+    );
+    let mut res = [(0, 0); 7];
+    for i in 0..3 {
+        res[i] = complex_add(res[i], lo[i]);
+        res[i + 2] = complex_add(res[i + 2], complex_sub(mid[i], complex_add(lo[i], hi[i])));
+        res[i + 4] = complex_add(res[i + 4], hi[i]);
+    }
+    res
+}
+
+#[inline]
+const fn complex_karatsuba2(f: [Complex; 2], g: [Complex; 2]) -> [Complex; 3] {
+    let lo = complex_mul(f[0], g[0]);
+    let hi = complex_mul(f[1], g[1]);
+    let mid = complex_mul(complex_add(f[0], f[1]), complex_add(g[0], g[1]));
+    [lo, complex_sub(mid, complex_add(lo, hi)), hi]
+}
+
+#[inline]
+const fn complex_mul(f: Complex, g: Complex) -> Complex {
+    (f.0 * g.0 - f.1 * g.1, f.0 * g.1 + f.1 * g.0)
+}
+
+#[inline]
+const fn complex_add(f: Complex, g: Complex) -> Complex {
+    (f.0 + g.0, f.1 + g.1)
+}
+
+#[inline]
+const fn complex_sub(f: Complex, g: Complex) -> Complex {
+    (f.0 - g.0, f.1 - g.1)
+}
+
+/// Maps `x` to `(x + 1)^3 - 1 mod 257`, which permutes `0..256`
+const fn lookup_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let x = i as u64 + 1;
+        // `x^3` is never 0 modulo 257 so the result fits in a byte
+        #[allow(clippy::cast_possible_truncation)]
+        let y = ((x * x * x + 256) % 257) as u8;
+        table[i] = y;
+        i += 1;
+    }
+    table
+}
+
+/// The split-and-lookup S-box applied to each byte
+const LOOKUP_TABLE: [u8; 256] = lookup_table();
+
+/// First column of the MDS matrix, the SHA-256 hash of `"Tip5"` split into
+/// 16-bit chunks
+const MDS_FIRST_COLUMN: [i64; STATE_WIDTH] = [
+    61402, 1108, 28750, 33823, 7454, 43244, 53865, 12034, 56951, 27521, 41351, 40901, 12021, 59689,
+    26798, 17845,
+];
+
+/// [`ROUND_CONSTANTS`] in Montgomery form
+const ROUND_CONSTANTS_MONT: [[Fp; STATE_WIDTH]; NUM_ROUNDS] = {
+    let mut res = [[Fp::ZERO; STATE_WIDTH]; NUM_ROUNDS];
+    let mut i = 0;
+    while i < NUM_ROUNDS {
+        let mut j = 0;
+        while j < STATE_WIDTH {
+            res[i][j] = super::into_mont(ROUND_CONSTANTS[i][j]);
+            j += 1;
+        }
+        i += 1;
+    }
+    res
+};
+
+/// Round constants published with Tip5, derived from BLAKE3 hashes of
+/// `"Tip5"` followed by the constant index
+const ROUND_CONSTANTS: [[u64; STATE_WIDTH]; NUM_ROUNDS] = [
+    [
+        0xbd2a_3deb_61ab_60de,
+        0xea7d_f21a_d954_7ed2,
+        0x900b_3677_a1de_063f,
+        0x1b46_887e_876c_8677,
+        0xd364_d977_889c_fb97,
+        0xdc8d_fac8_4369_9f02,
+        0x375c_405d_7190_db58,
+        0x2792_4006_d2b0_d4b1,
+        0x78dd_1172_d483_cd38,
+        0x3346_c662_4488_2a56,
+        0xb024_9b27_9f49_8aa5,
+        0x94cd_51be_7933_8d4d,
+        0xb0e0_dc70_52c5_b218,
+        0xf8dc_c4d2_48ad_ad95,
+        0x68e3_c635_fec8_68b7,
+        0xd7d0_6b3f_fb6b_0d8c,
+    ],
+    [
+        0xf350_0dea_20ef_032a,
+        0x4865_bf17_5bba_5803,
+        0xd5f7_fe30_2728_7a27,
+        0xa573_33f4_4e19_3412,
+        0x8726_e153_a977_eae2,
+        0x3014_a984_63fc_191b,
+        0xba14_5461_af39_b212,
+        0x03ab_7010_5933_202f,
+        0x3d90_b7ee_bfcf_71e5,
+        0x3863_22b1_cc52_0bfd,
+        0x27c2_c8da_f774_f675,
+        0x4fcb_83f5_0309_bc6a,
+        0x5e6d_5ce8_275f_3cb3,
+        0xecc2_f659_2c8f_905c,
+        0x837f_5324_61e6_09b4,
+        0xb2b1_f6b9_5c92_c93c,
+    ],
+    [
+        0xc002_7af5_5641_1dc1,
+        0x16e1_8c88_5fc2_a26c,
+        0x8880_ef18_3d9f_2bf3,
+        0xb293_0bdb_5ca8_8c45,
+        0x9c2e_c832_2e1c_1553,
+        0xe5b0_5eaf_3220_a674,
+        0xa49c_c6ae_4b86_1c4e,
+        0x1170_8e0a_eb86_ebd7,
+        0xc09d_e92b_bc39_02e0,
+        0x929b_3c79_516b_cbc1,
+        0xe006_e5bf_738f_27d1,
+        0x2d9e_1ec0_eac8_ea38,
+        0x0984_d8d9_4bf9_37c5,
+        0x4959_273c_220e_6747,
+        0xfe1d_9342_07e7_96fa,
+        0x2b9b_9298_f2f6_dd73,
+    ],
+    [
+        0x07a1_f5a6_7d6e_3a41,
+        0x4407_593e_e737_43d9,
+        0x9f05_4720_ef80_2e59,
+        0x78d4_b711_336e_6aa6,
+        0xadc6_38ae_f3c8_b228,
+        0xa4d6_d3e8_6afb_2114,
+        0x9d48_08e7_2553_1968,
+        0x3698_04df_3866_d0ef,
+        0xe6db_d9a9_d221_5024,
+        0x8ed2_2ca2_12ee_85b2,
+        0x397b_b882_fcd2_3eb6,
+        0xeb8f_8786_d727_7531,
+        0x9999_d4cd_aff5_43b5,
+        0xf382_a612_17f1_92d6,
+        0x49c3_7260_b026_adc1,
+        0x3ff8_918c_e35c_1019,
+    ],
+    [
+        0x2e7d_f8b7_6080_bd07,
+        0xf5db_ac25_0b8a_28b9,
+        0x853c_3727_ae9d_a4cc,
+        0xb2f1_f5f3_d9e5_a26d,
+        0x3fce_2201_2d33_7847,
+        0x6b5a_3e6d_b7ee_e347,
+        0x1715_82cd_59dd_e50d,
+        0xc0c0_b309_5ee6_2a8a,
+        0x665b_25c6_f6a2_03d2,
+        0x3099_aed9_3b6a_e69f,
+        0x801d_f609_2be6_9c38,
+        0x8066_ad0c_dfff_43cd,
+        0x8af9_d44a_5f4f_dc6b,
+        0xd802_19cd_97c0_d762,
+        0x10c9_ceba_1414_8ebb,
+        0x539b_d4c3_f2f2_4474,
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        apply_mds, hash_10, hash_pair, hash_varlen, permute, split_and_lookup, Digest, Domain,
+        Tip5Sponge, DIGEST_LEN, LOOKUP_TABLE, MDS_FIRST_COLUMN, RATE_WIDTH, STATE_WIDTH,
+    };
+    use crate::fp64::{Fp, MODULUS};
+    use ark_ff::{BigInt, Field, PrimeField};
+    use ark_std::{test_rng, vec, vec::Vec, UniformRand};
+    use core::marker::PhantomData;
+
+    fn from_raw(value: u64) -> Fp {
+        ark_ff::Fp(BigInt([value]), PhantomData)
+    }
+
+    fn to_u64s(values: &[Fp]) -> Vec<u64> {
+        values.iter().map(|v| v.into_bigint().0[0]).collect()
+    }
+
+    /// The table published with Tip5
+    #[test]
+    fn lookup_table_matches_published() {
+        const PUBLISHED: [u8; 256] = [
+            0, 7, 26, 63, 124, 215, 85, 254, 214, 228, 45, 185, 140, 173, 33, 240, 29, 177, 176,
+            32, 8, 110, 87, 202, 204, 99, 150, 106, 230, 14, 235, 128, 213, 239, 212, 138, 23, 130,
+            208, 6, 44, 71, 93, 116, 146, 189, 251, 81, 199, 97, 38, 28, 73, 179, 95, 84, 152, 48,
+            35, 119, 49, 88, 242, 3, 148, 169, 72, 120, 62, 161, 166, 83, 175, 191, 137, 19, 100,
+            129, 112, 55, 221, 102, 218, 61, 151, 237, 68, 164, 17, 147, 46, 234, 203, 216, 22,
+            141, 65, 57, 123, 12, 244, 54, 219, 231, 96, 77, 180, 154, 5, 253, 133, 165, 98, 195,
+            205, 134, 245, 30, 9, 188, 59, 142, 186, 197, 181, 144, 92, 31, 224, 163, 111, 74, 58,
+            69, 113, 196, 67, 246, 225, 10, 121, 50, 60, 157, 90, 122, 2, 250, 101, 75, 178, 159,
+            24, 36, 201, 11, 243, 132, 198, 190, 114, 233, 39, 52, 21, 209, 108, 238, 91, 187, 18,
+            104, 194, 37, 153, 34, 200, 143, 126, 155, 236, 118, 64, 80, 172, 89, 94, 193, 135,
+            183, 86, 107, 252, 13, 167, 206, 136, 220, 207, 103, 171, 160, 76, 182, 227, 217, 158,
+            56, 174, 4, 66, 109, 139, 162, 184, 211, 249, 47, 125, 232, 117, 43, 16, 42, 127, 20,
+            241, 25, 149, 105, 156, 51, 53, 168, 145, 247, 223, 79, 78, 226, 15, 222, 82, 115, 70,
+            210, 27, 41, 1, 170, 40, 131, 192, 229, 248, 255,
+        ];
+        assert_eq!(LOOKUP_TABLE, PUBLISHED);
+    }
+
+    #[test]
+    fn split_and_lookup_stays_canonical() {
+        let mut rng = test_rng();
+        let mut inputs = vec![Fp::ZERO, Fp::ONE, -Fp::ONE, from_raw(MODULUS - 1)];
+        inputs.extend((0..1000).map(|_| Fp::rand(&mut rng)));
+        for x in inputs {
+            assert!((split_and_lookup(x).0).0[0] < MODULUS);
+        }
+    }
+
+    #[test]
+    fn mds_matches_matrix() {
+        let mut rng = test_rng();
+        let mut inputs = vec![
+            [from_raw(MODULUS - 1); STATE_WIDTH],
+            [Fp::ZERO; STATE_WIDTH],
+        ];
+        inputs.extend((0..100).map(|_| [(); STATE_WIDTH].map(|()| Fp::rand(&mut rng))));
+        for input in inputs {
+            let expected: [Fp; STATE_WIDTH] = core::array::from_fn(|i| {
+                (0..STATE_WIDTH)
+                    .map(|j| {
+                        let entry = MDS_FIRST_COLUMN[(STATE_WIDTH + i - j) % STATE_WIDTH];
+                        Fp::from(entry) * input[j]
+                    })
+                    .sum()
+            });
+            let mut state = input;
+            apply_mds(&mut state);
+            assert_eq!(state, expected);
+        }
+    }
+
+    /// Triton's permutation snapshot on Montgomery representations taken from
+    /// `twenty-first`
+    #[test]
+    fn permutation_known_answer() {
+        let mut state = [
+            0x0000_000f_ffff_fff0,
+            0x0000_0000_ffff_ffff,
+            0x0000_0000_ffff_ffff,
+            0x0000_0028_ffff_ffd7,
+            0x0000_0006_ffff_fff9,
+            0x0000_0002_ffff_fffd,
+            0x0000_0000_ffff_ffff,
+            0x0000_0030_ffff_ffcf,
+            0x0000_0397_ffff_fc68,
+            0x0000_000f_ffff_fff0,
+            0x316b_fb72_3638_2123,
+            0x216f_521b_66ef_83f5,
+            0x5689_d7b3_63f5_2df0,
+            0xeb2f_59e3_aeae_25fc,
+            0xb082_99d2_77cb_b4dc,
+            0xcbe3_d9fd_c534_9140,
+        ]
+        .map(from_raw);
+        permute(&mut state);
+        let expected = [
+            0x15d3_8ea9_29f6_632a,
+            0xf988_e509_ff73_8bb4,
+            0x48bc_dfae_88a2_e9f3,
+            0x8733_9e83_2daa_c02a,
+            0x511e_4126_8150_fdac,
+        ]
+        .map(from_raw);
+        assert_eq!(state[..5], expected);
+    }
+
+    /// Chains `hash_10` six times as in `twenty-first`'s snapshot test
+    #[test]
+    fn hash_10_known_answer() {
+        let mut input = [Fp::ZERO; RATE_WIDTH];
+        for i in 0..6 {
+            let digest = hash_10(&input);
+            input[i..i + DIGEST_LEN].copy_from_slice(&digest);
+        }
+        assert_eq!(
+            to_u64s(&hash_10(&input)),
+            [
+                0x96d9_3b45_fec2_9c10,
+                0x19b9_f5d8_964b_752f,
+                0x5f27_0a94_30f0_0ab6,
+                0xee65_ef5f_19da_4055,
+                0x6a2c_9ba1_6f1b_1c65,
+            ]
+        );
+    }
+
+    /// Sum of `hash_varlen([0, 1, ..., i - 1])` for `i` in `0..20` as in
+    /// `twenty-first`'s test vectors
+    #[test]
+    fn hash_varlen_known_answer() {
+        let mut sum = [Fp::ZERO; DIGEST_LEN];
+        for i in 0..20 {
+            let input = (0..i).map(Fp::from).collect::<Vec<_>>();
+            for (sum, value) in sum.iter_mut().zip(hash_varlen(&input)) {
+                *sum += value;
+            }
+        }
+        assert_eq!(
+            to_u64s(&sum),
+            [
+                0x699c_2a62_86fa_baef,
+                0x4f73_fd4f_1c8a_2f65,
+                0x4185_800a_3ad2_1a02,
+                0x180b_17f9_e07d_872a,
+                0x039a_ff6f_9bf6_4fea,
+            ]
+        );
+    }
+
+    #[test]
+    fn hash_pair_is_hash_10() {
+        let mut rng = test_rng();
+        let left: Digest = [(); DIGEST_LEN].map(|()| Fp::rand(&mut rng));
+        let right: Digest = [(); DIGEST_LEN].map(|()| Fp::rand(&mut rng));
+        let mut input = [Fp::ZERO; RATE_WIDTH];
+        input[..DIGEST_LEN].copy_from_slice(&left);
+        input[DIGEST_LEN..].copy_from_slice(&right);
+        assert_eq!(hash_pair(&left, &right), hash_10(&input));
+        assert_ne!(hash_pair(&left, &right), hash_pair(&right, &left));
+    }
+
+    #[test]
+    fn hash_varlen_matches_sponge() {
+        for len in 0..=2 * RATE_WIDTH + 1 {
+            let input = vec![Fp::from(42u64); len];
+            let mut sponge = Tip5Sponge::new(Domain::VariableLength);
+            sponge.pad_and_absorb_all(&input);
+            let squeezed = sponge.squeeze();
+            assert_eq!(hash_varlen(&input), squeezed[..DIGEST_LEN]);
+        }
+    }
+
+    #[test]
+    fn domains_differ() {
+        let input = [Fp::ONE; RATE_WIDTH];
+        let mut fixed = Tip5Sponge::new(Domain::FixedLength);
+        let mut variable = Tip5Sponge::new(Domain::VariableLength);
+        fixed.absorb(&input);
+        variable.absorb(&input);
+        assert_ne!(fixed.squeeze(), variable.squeeze());
+    }
+}
@@ -0,0 +1,377 @@
+//! The parts of the FRI low degree tests in [`fp64::fri`](crate::fp64::fri)
+//! and [`circle::fri`](crate::circle::fri) that don't depend on how a layer is
+//! folded.
+//!
+//! A layer of `n` values that folds by `arity` is committed with a Merkle tree
+//! whose leaf `i` holds the values at indices `i + j * n / arity` for
+//! `j < arity`, which are the values that fold together. Both protocols then
+//! follow the same transcript: a folding challenge after each commitment, the
+//! coefficients of the last layer, a proof of work and the query positions.
+//! The verifier follows each query through the committed layers, checking
+//! every folded value against the value opened in the next layer.
+
+use crate::{
+    merkle::{BatchMerkleProof, MerkleError, MerkleHasher, MerkleTree},
+    transcript::{DuplexPermutation, Transcript},
+};
+use ark_ff::Field;
+use ark_std::{
+    fmt::{self, Display, Formatter},
+    vec::Vec,
+};
+
+/// The reasons a proof is rejected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FriError {
+    /// The domain size is not supported by the options
+    InvalidDomainSize(usize),
+    /// The proof has the wrong number of layers, opened values or remainder
+    /// coefficients
+    InvalidProofShape,
+    /// The proof of work witness is invalid
+    InvalidProofOfWork,
+    /// An opening doesn't match the commitment of a layer. Layer 0 is the
+    /// input.
+    InvalidOpening { layer: usize, error: MerkleError },
+    /// A folded value differs from the value opened in the next layer
+    FoldingMismatch { layer: usize, position: usize },
+    /// A folded value of the last layer differs from the remainder
+    /// polynomial, which circle FRI calls the last layer
+    RemainderMismatch { position: usize },
+}
+
+impl Display for FriError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDomainSize(n) => write!(f, "unsupported domain size {n}"),
+            Self::InvalidProofShape => write!(f, "proof has the wrong shape"),
+            Self::InvalidProofOfWork => write!(f, "invalid proof of work"),
+            Self::InvalidOpening { layer, error } => {
+                write!(f, "invalid opening in layer {layer}: {error}")
+            }
+            Self::FoldingMismatch { layer, position } => {
+                write!(
+                    f,
+                    "folded value at position {position} doesn't match layer {layer}"
+                )
+            }
+            Self::RemainderMismatch { position } => {
+                write!(
+                    f,
+                    "folded value at position {position} doesn't match the remainder"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FriError {}
+
+/// The opened leaves of a layer at the distinct query positions in increasing
+/// order. Each leaf holds the values that fold together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FriQueries<F, D> {
+    pub cosets: Vec<Vec<F>>,
+    pub proof: BatchMerkleProof<D>,
+}
+
+/// Commits to `values` with the cosets that fold together as leaves and
+/// absorbs the root.
+pub(crate) fn commit<F, H, P, const WIDTH: usize>(
+    hasher: &H,
+    transcript: &mut Transcript<P, WIDTH>,
+    values: &[F],
+    arity: usize,
+) -> MerkleTree<H>
+where
+    F: Field<BasePrimeField = P::Field>,
+    H: MerkleHasher<Field = P::Field> + Clone,
+    H::Digest: AsRef<[P::Field]>,
+    P: DuplexPermutation<WIDTH>,
+{
+    let num_leaves = values.len() / arity;
+    let leaves = (0..num_leaves)
+        .map(|i| {
+            values[i..]
+                .iter()
+                .step_by(num_leaves)
+                .flat_map(Field::to_base_prime_field_elements)
+                .collect()
+        })
+        .collect();
+    let tree = MerkleTree::new(hasher.clone(), leaves).unwrap();
+    transcript.absorb_slice(tree.root().as_ref());
+    tree
+}
+
+/// Absorbs the coefficients of the last layer, grinds the proof of work and
+/// draws the query positions among the `num_leaves` leaves of the input layer.
+pub(crate) fn finish_commit_phase<E, P, const WIDTH: usize>(
+    transcript: &mut Transcript<P, WIDTH>,
+    last_layer: &[E],
+    proof_of_work_bits: u32,
+    num_queries: usize,
+    num_leaves: usize,
+) -> (P::Field, Vec<usize>)
+where
+    E: Field<BasePrimeField = P::Field>,
+    P: DuplexPermutation<WIDTH> + Clone,
+{
+    for coefficient in last_layer {
+        transcript.absorb_extension(coefficient);
+    }
+    let proof_of_work = transcript.grind(proof_of_work_bits);
+    let positions = query_positions(transcript, num_queries, num_leaves);
+    (proof_of_work, positions)
+}
+
+/// Replays the transcript of the prover from the commitments and returns the
+/// folding challenges and the query positions.
+///
+/// # Errors
+///
+/// Returns [`FriError::InvalidProofOfWork`] if the proof of work is invalid.
+pub(crate) fn replay_commit_phase<E, D, P, const WIDTH: usize>(
+    transcript: &mut Transcript<P, WIDTH>,
+    commitments: &[D],
+    last_layer: &[E],
+    proof_of_work_bits: u32,
+    proof_of_work: P::Field,
+    num_queries: usize,
+    num_leaves: usize,
+) -> Result<(Vec<E>, Vec<usize>), FriError>
+where
+    E: Field<BasePrimeField = P::Field>,
+    D: AsRef<[P::Field]>,
+    P: DuplexPermutation<WIDTH>,
+{
+    let betas = commitments
+        .iter()
+        .map(|root| {
+            transcript.absorb_slice(root.as_ref());
+            transcript.squeeze_extension()
+        })
+        .collect();
+    for coefficient in last_layer {
+        transcript.absorb_extension(coefficient);
+    }
+    if !transcript.check_witness(proof_of_work_bits, proof_of_work) {
+        return Err(FriError::InvalidProofOfWork);
+    }
+    Ok((betas, query_positions(transcript, num_queries, num_leaves)))
+}
+
+/// Draws `num_queries` leaf positions among `num_leaves`.
+fn query_positions<P, const WIDTH: usize>(
+    transcript: &mut Transcript<P, WIDTH>,
+    num_queries: usize,
+    num_leaves: usize,
+) -> Vec<usize>
+where
+    P: DuplexPermutation<WIDTH>,
+{
+    let bits = num_leaves.trailing_zeros();
+    (0..num_queries)
+        .map(|_| transcript.squeeze_bits(bits))
+        .collect()
+}
+
+/// Opens the leaves at the distinct `positions` of a layer with values in `F`.
+pub(crate) fn open<F, H>(
+    tree: &MerkleTree<H>,
+    positions: &[usize],
+    arity: usize,
+) -> FriQueries<F, H::Digest>
+where
+    F: Field<BasePrimeField = H::Field>,
+    H: MerkleHasher,
+{
+    let indices = distinct(positions);
+    let degree = usize::try_from(F::extension_degree()).unwrap();
+    let cosets = indices
+        .iter()
+        .map(|&index| {
+            let leaf = tree.leaf(index).unwrap();
+            leaf.chunks_exact(degree)
+                .take(arity)
+                .map(|coefficients| F::from_base_prime_field_elems(coefficients).unwrap())
+                .collect()
+        })
+        .collect();
+    FriQueries {
+        cosets,
+        proof: tree.open_batch(&indices).unwrap(),
+    }
+}
+
+/// Opens the folded layers committed in `trees` at the query positions among
+/// the `num_leaves` leaves of the input layer.
+pub(crate) fn open_layers<E, H>(
+    trees: &[MerkleTree<H>],
+    mut positions: Vec<usize>,
+    mut num_leaves: usize,
+    arity: usize,
+) -> Vec<FriQueries<E, H::Digest>>
+where
+    E: Field<BasePrimeField = H::Field>,
+    H: MerkleHasher,
+{
+    trees
+        .iter()
+        .map(|tree| {
+            num_leaves /= arity;
+            for position in &mut positions {
+                *position %= num_leaves;
+            }
+            open(tree, &positions, arity)
+        })
+        .collect()
+}
+
+/// Verifies the openings of `layer`, which has `num_leaves` leaves, and returns
+/// the coset of each position.
+///
+/// # Errors
+///
+/// Returns [`FriError::InvalidProofShape`] if a coset doesn't have `arity`
+/// values and [`FriError::InvalidOpening`] if the openings don't match `root`.
+pub(crate) fn opened_cosets<'a, F, H>(
+    layer: usize,
+    hasher: &H,
+    root: &H::Digest,
+    queries: &'a FriQueries<F, H::Digest>,
+    positions: &[usize],
+    num_leaves: usize,
+    arity: usize,
+) -> Result<Vec<&'a [F]>, FriError>
+where
+    F: Field<BasePrimeField = H::Field>,
+    H: MerkleHasher,
+{
+    let indices = distinct(positions);
+    if queries.cosets.iter().any(|coset| coset.len() != arity) {
+        return Err(FriError::InvalidProofShape);
+    }
+    let leaves: Vec<Vec<H::Field>> = queries
+        .cosets
+        .iter()
+        .map(|coset| {
+            coset
+                .iter()
+                .flat_map(Field::to_base_prime_field_elements)
+                .collect()
+        })
+        .collect();
+    queries
+        .proof
+        .verify(hasher, root, num_leaves, &indices, &leaves)
+        .map_err(|error| FriError::InvalidOpening { layer, error })?;
+    Ok(positions
+        .iter()
+        .map(|position| {
+            let i = indices.binary_search(position).unwrap();
+            queries.cosets[i].as_slice()
+        })
+        .collect())
+}
+
+/// Follows the queries through the folded layers with the given `commitments`
+/// and openings `queries`, starting from the `folded` values of the input
+/// layer at `positions` among its `num_leaves` leaves.
+///
+/// Each value is checked against the opened coset of the next layer, which is
+/// then folded with `fold(layer, coset, position, num_leaves)`. On success
+/// `positions` and `folded` hold the positions and values in the last layer.
+///
+/// # Errors
+///
+/// Returns the [`FriError`] of the first opening or folded value that doesn't
+/// match.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn verify_layers<E, H>(
+    hasher: &H,
+    commitments: &[H::Digest],
+    queries: &[FriQueries<E, H::Digest>],
+    positions: &mut [usize],
+    folded: &mut [E],
+    mut num_leaves: usize,
+    arity: usize,
+    mut fold: impl FnMut(usize, &[E], usize, usize) -> E,
+) -> Result<(), FriError>
+where
+    E: Field<BasePrimeField = H::Field>,
+    H: MerkleHasher,
+{
+    for (layer, (queries, root)) in queries
+        .iter()
+        .zip(commitments)
+        .enumerate()
+        .map(|(i, query)| (i + 1, query))
+    {
+        num_leaves /= arity;
+        let slots: Vec<usize> = positions
+            .iter_mut()
+            .map(|position| {
+                let slot = *position / num_leaves;
+                *position %= num_leaves;
+                slot
+            })
+            .collect();
+        let cosets = opened_cosets(layer, hasher, root, queries, positions, num_leaves, arity)?;
+        for (i, coset) in cosets.into_iter().enumerate() {
+            if coset[slots[i]] != folded[i] {
+                return Err(FriError::FoldingMismatch {
+                    layer,
+                    position: positions[i],
+                });
+            }
+            folded[i] = fold(layer, coset, positions[i], num_leaves);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the sorted distinct positions.
+fn distinct(positions: &[usize]) -> Vec<usize> {
+    let mut indices = positions.to_vec();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{commit, open, opened_cosets, FriError};
+    use crate::{
+        fp64::{ext2::Fp2, poseidon2::Poseidon2},
+        transcript::Transcript,
+    };
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    #[test]
+    fn openings_hold_the_values_that_fold_together() {
+        let mut rng = test_rng();
+        let hasher = Poseidon2::<8>::new();
+        let mut transcript = Transcript::new(Poseidon2::<12>::new(), b"fri");
+        let values: Vec<Fp2> = (0..32).map(|_| Fp2::rand(&mut rng)).collect();
+        let tree = commit(&hasher, &mut transcript, &values, 4);
+        assert_eq!(tree.num_leaves(), 8);
+
+        let positions = [5, 1, 5];
+        let queries = open::<Fp2, _>(&tree, &positions, 4);
+        assert_eq!(queries.cosets.len(), 2);
+        let cosets = opened_cosets(0, &hasher, &tree.root(), &queries, &positions, 8, 4).unwrap();
+        for (&position, coset) in positions.iter().zip(cosets) {
+            let expected: Vec<Fp2> = (0..4).map(|j| values[position + 8 * j]).collect();
+            assert_eq!(coset, expected);
+        }
+
+        let mut tampered = queries;
+        tampered.cosets[1].pop();
+        assert_eq!(
+            opened_cosets(0, &hasher, &tree.root(), &tampered, &positions, 8, 4),
+            Err(FriError::InvalidProofShape)
+        );
+    }
+}
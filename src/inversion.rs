@@ -0,0 +1,133 @@
+//! Batch inversion shared by the prime fields in this crate.
+//!
+//! The serial routines wrap [`ark_ff::batch_inversion`], which inverts the
+//! product of all elements once with Montgomery's trick and works in place
+//! with a single buffer of prefix products. Zeros are skipped so they neither
+//! poison the product nor need to be filtered out by the caller. The parallel
+//! routines split the slice so every rayon task pays for one inversion.
+
+use ark_ff::Field;
+
+/// Smallest number of elements a parallel task inverts, below which the
+/// single inversion per task dominates
+#[cfg(feature = "parallel")]
+const MIN_PARALLEL_CHUNK: usize = 1 << 10;
+
+/// Writes the inverse of every element of `values` to `out` and zero for
+/// every zero.
+pub fn batch_inverse_into<F: Field>(values: &[F], out: &mut [F]) {
+    assert_eq!(values.len(), out.len(), "output length must match input");
+    out.copy_from_slice(values);
+    ark_ff::batch_inversion(out);
+}
+
+/// Replaces every non-zero element of `values` with its inverse.
+pub fn batch_inverse<F: Field>(values: &mut [F]) {
+    ark_ff::batch_inversion(values);
+}
+
+#[cfg(feature = "parallel")]
+fn chunk_size(len: usize) -> usize {
+    len.div_ceil(rayon::current_num_threads())
+        .max(MIN_PARALLEL_CHUNK)
+}
+
+/// [`batch_inverse_into`] on chunks of `values` in parallel.
+#[cfg(feature = "parallel")]
+pub fn par_batch_inverse_into<F: Field>(values: &[F], out: &mut [F]) {
+    use rayon::prelude::*;
+
+    assert_eq!(values.len(), out.len(), "output length must match input");
+    let chunk_size = chunk_size(values.len());
+    values
+        .par_chunks(chunk_size)
+        .zip(out.par_chunks_mut(chunk_size))
+        .for_each(|(values, out)| batch_inverse_into(values, out));
+}
+
+/// [`batch_inverse`] on chunks of `values` in parallel.
+#[cfg(feature = "parallel")]
+pub fn par_batch_inverse<F: Field>(values: &mut [F]) {
+    use rayon::prelude::*;
+
+    let chunk_size = chunk_size(values.len());
+    values.par_chunks_mut(chunk_size).for_each(batch_inverse);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{batch_inverse, batch_inverse_into};
+    use crate::{fp31, fp64};
+    use ark_ff::{Field, PrimeField, Zero};
+    use ark_std::{test_rng, vec::Vec};
+
+    fn expected_inverses<F: Field>(values: &[F]) -> Vec<F> {
+        values
+            .iter()
+            .map(|value| value.inverse().unwrap_or_default())
+            .collect()
+    }
+
+    /// Checks the batch routines against one inversion per element on `len`
+    /// random elements with `zeros` written over evenly spaced positions.
+    fn check_batch_inverse<F: Field>(
+        len: usize,
+        zeros: &[F],
+        serial: fn(&mut [F]),
+        serial_into: fn(&[F], &mut [F]),
+    ) {
+        let mut rng = test_rng();
+        let mut values: Vec<F> = (0..len).map(|_| F::rand(&mut rng)).collect();
+        let step = len / zeros.len();
+        for (i, zero) in zeros.iter().enumerate() {
+            values[i * step + step / 2] = *zero;
+        }
+        let expected = expected_inverses(&values);
+
+        let mut out = ark_std::vec![F::ZERO; len];
+        serial_into(&values, &mut out);
+        assert_eq!(out, expected);
+        serial(&mut values);
+        assert_eq!(values, expected);
+
+        let mut only_zeros = zeros.to_vec();
+        serial(&mut only_zeros);
+        assert!(only_zeros.iter().all(Zero::is_zero));
+        serial(&mut []);
+    }
+
+    /// The encoding of zero as the modulus, which `Fp31` allows besides `0`
+    fn fp31_modulus() -> fp31::Fp {
+        fp31::Fp(u32::try_from(fp31::Fp::MODULUS.as_ref()[0]).unwrap())
+    }
+
+    #[test]
+    fn batch_inverse_matches_inverse() {
+        check_batch_inverse(100, &[fp64::Fp::ZERO; 4], batch_inverse, batch_inverse_into);
+        check_batch_inverse(
+            100,
+            &[fp31::Fp(0), fp31_modulus(), fp31_modulus(), fp31::Fp(0)],
+            batch_inverse,
+            batch_inverse_into,
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn par_batch_inverse_matches_inverse() {
+        use super::{par_batch_inverse, par_batch_inverse_into};
+
+        check_batch_inverse(
+            5000,
+            &[fp64::Fp::ZERO; 2],
+            par_batch_inverse,
+            par_batch_inverse_into,
+        );
+        check_batch_inverse(
+            5000,
+            &[fp31_modulus(), fp31::Fp(0)],
+            par_batch_inverse,
+            par_batch_inverse_into,
+        );
+    }
+}
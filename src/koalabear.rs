@@ -0,0 +1,79 @@
+//! An implementation of the 31-bit `KoalaBear` prime field with modulus `2^31 -
+//! 2^24 + 1`.
+//!
+//! The field has a multiplicative subgroup of order `2^24` for radix-2 NTTs and,
+//! since `3` does not divide `p - 1`, the map `x -> x^3` is a permutation. This
+//! makes it possible to use degree-3 S-boxes in algebraic hashes such as
+//! Poseidon2. Field elements are stored in Montgomery form with auxiliary
+//! modulus `R = 2^32`.
+//!
+//! This field and its implementation has a couple of attractive properties:
+//! * Addition of two field elements never overflows a 32-bit int.
+//! * Multiplication only needs a single 32x32 -> 64-bit product followed by a
+//!   32-bit Montgomery reduction.
+//! * Efficient for GPUs which optimize throughput for 32-bit arithmetic.
+
+crate::mont31::mont31_field! {
+    // `p = 2^31 - 2^24 + 1`
+    modulus: 2_130_706_433,
+    mu: 2_164_260_865,
+    r2: 402_124_772,
+    generator: 3,
+    two_adicity: 24,
+    two_adic_root_of_unity: 1_791_270_792,
+    // Computes `x^(p-2)` where `p-2 = 0b1111110111...1` has 24 trailing ones
+    inverse: |x| {
+        let x2 = Fp::mul(Fp::sq(x), x);
+        let x3 = Fp::mul(Fp::sq(x2), x);
+        let x6 = Fp::mul(Fp::sqn(x3, 3), x3);
+        let x12 = Fp::mul(Fp::sqn(x6, 6), x6);
+        let x24 = Fp::mul(Fp::sqn(x12, 12), x12);
+        Fp::mul(Fp::sqn(x6, 25), x24)
+    },
+}
+
+impl Fp {
+    /// Returns `self^3`. Cubing is a permutation of the field since `p - 1` is
+    /// not divisible by `3`.
+    #[inline]
+    #[must_use]
+    pub const fn cube(self) -> Self {
+        self.sq().mul(self)
+    }
+
+    /// Returns the unique `x` such that `x^3 = self`.
+    ///
+    /// Computes `self^d` where `d = 3^-1 mod (p - 1) = 0b1010100(10)^11 11`.
+    /// Writing `e(k) = (4^k - 1) / 3` for the exponent with `k` alternating
+    /// ones we have `d = 2 * (e(3) * 2^25 + e(12)) + 1`.
+    #[inline]
+    #[must_use]
+    pub const fn cube_root(self) -> Self {
+        let e2 = self.sqn(2).mul(self);
+        let e3 = e2.sqn(2).mul(self);
+        let e4 = e2.sqn(4).mul(e2);
+        let e8 = e4.sqn(8).mul(e4);
+        let e12 = e8.sqn(8).mul(e4);
+        e3.sqn(25).mul(e12).sq().mul(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fp as TestField;
+    use ark_algebra_test_templates::test_field;
+
+    test_field!(generated; TestField; prime);
+
+    #[test]
+    fn cube_root_inverts_cube() {
+        use ark_std::{test_rng, UniformRand};
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            let a = TestField::rand(&mut rng);
+            assert_eq!(a.cube().cube_root(), a);
+            assert_eq!(a.cube_root().cube(), a);
+            assert_eq!(a.cube(), a * a * a);
+        }
+    }
+}
@@ -1,10 +1,24 @@
 #![doc = include_str!("../README.md")]
-#![feature(const_mut_refs, const_trait_impl)]
+#![feature(const_mut_refs, const_trait_impl, portable_simd)]
 #![doc(issue_tracker_base_url = "https://github.com/andrewmilson/optimized-fields/issues/")]
 #![warn(clippy::all, clippy::pedantic, clippy::cargo, clippy::nursery)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod babybear;
+pub mod circle;
+pub mod cm31;
+pub mod ecgfp5;
 pub mod fp31;
 pub mod fp64;
+pub mod fri;
+pub mod koalabear;
+pub mod merkle;
+pub mod packed;
+pub mod qm31;
+pub mod schnorr;
+pub mod transcript;
 
+pub(crate) mod compression;
+pub(crate) mod inversion;
 pub(crate) mod macros;
+pub(crate) mod mont31;
@@ -9,10 +9,33 @@
 // Demo: http://andrewmilson.com/optimized-fields/criterion/report/index.html
 #[macro_export]
 macro_rules! field_compare {
-    (prime; $test_name:expr; $mod_name:ident; $( $field:ident ),+) => {
+    (@conversions prime; $group:ident; $f:ident; $field_name:ident; $field:ident; $samples:ident) => {{
+        use ark_ff::{BigInteger, PrimeField};
+
+        // conversions
+        let bigints = $f.iter().map(|f| f.into_bigint()).collect::<Vec<_>>();
+        let description = "From BigInt";
+        $group.bench_with_input(BenchmarkId::new($field_name, description), description, |b, _| {
+            let mut i = 0;
+            b.iter(|| {
+                i = (i + 1) % $samples;
+                <$field>::from_bigint(bigints[i])
+            })
+        });
+        let description = "Into BigInt";
+        $group.bench_with_input(BenchmarkId::new($field_name, description), description, |b, _| {
+            let mut i = 0;
+            b.iter(|| {
+                i = (i + 1) % $samples;
+                $f[i].into_bigint()
+            })
+        });
+    }};
+    (@conversions extension; $group:ident; $f:ident; $field_name:ident; $field:ident; $samples:ident) => {};
+    ($kind:ident; $test_name:expr; $mod_name:ident; $( $field:ident ),+) => {
         mod $mod_name {
             use super::*;
-            use ark_ff::{Field, PrimeField, UniformRand, BigInteger};
+            use ark_ff::{Field, UniformRand};
             use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
             fn bench_compare(c: &mut Criterion) {
@@ -201,24 +224,7 @@ macro_rules! field_compare {
                         })
                     });
 
-                    // conversions
-                    let bigints = f.iter().map(|f| f.into_bigint()).collect::<Vec<_>>();
-                    let description = "From BigInt";
-                    group.bench_with_input(BenchmarkId::new(field_name, description), description, |b, _| {
-                        let mut i = 0;
-                        b.iter(|| {
-                            i = (i + 1) % SAMPLES;
-                            <$field>::from_bigint(bigints[i])
-                        })
-                    });
-                    let description = "Into BigInt";
-                    group.bench_with_input(BenchmarkId::new(field_name, description), description, |b, _| {
-                        let mut i = 0;
-                        b.iter(|| {
-                            i = (i + 1) % SAMPLES;
-                            f[i].into_bigint()
-                        })
-                    });
+                    $crate::field_compare!(@conversions $kind; group; f; field_name; $field; SAMPLES);
                 )*
                 group.finish();
             }
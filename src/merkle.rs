@@ -0,0 +1,566 @@
+//! Merkle tree commitments to vectors of field elements.
+//!
+//! The tree is generic over a [`MerkleHasher`] that hashes a leaf, a batch of
+//! field elements, to a digest and compresses two digests into one. Using a
+//! field-native hash keeps the commitment cheap to verify inside a proof over
+//! the same field. [`MerkleHasher`] is implemented for Poseidon2 over both
+//! [`fp31::Fp`](crate::fp31::Fp) and [`fp64::Fp`](crate::fp64::Fp).
+//!
+//! Nodes are stored in heap order: the root is node 1, the children of node
+//! `k` are nodes `2k` and `2k + 1` and leaf `i` of `n` is node `n + i`. The
+//! number of leaves must be a power of two.
+//!
+//! Several leaves can be opened at once with a [`BatchMerkleProof`]. It only
+//! contains the siblings that the verifier can't compute from the opened
+//! leaves, so leaves that share subtrees share the siblings above them.
+
+use crate::{fp31, fp64};
+use ark_std::{
+    fmt::{self, Debug, Display, Formatter},
+    vec::Vec,
+};
+
+/// A hash over a field used to build a [`MerkleTree`]
+pub trait MerkleHasher {
+    type Field: Copy;
+    type Digest: Copy + Default + Eq + Debug;
+
+    /// Hashes a leaf to a digest.
+    fn hash_leaf(&self, leaf: &[Self::Field]) -> Self::Digest;
+
+    /// Compresses the digests of two sibling nodes into their parent's.
+    fn compress(&self, left: &Self::Digest, right: &Self::Digest) -> Self::Digest;
+}
+
+impl<const WIDTH: usize> MerkleHasher for fp31::poseidon2::Poseidon2<WIDTH> {
+    type Field = fp31::Fp;
+    type Digest = fp31::poseidon2::Digest;
+
+    fn hash_leaf(&self, leaf: &[fp31::Fp]) -> Self::Digest {
+        self.hash(leaf)
+    }
+
+    fn compress(&self, left: &Self::Digest, right: &Self::Digest) -> Self::Digest {
+        self.compress(left, right)
+    }
+}
+
+impl<const WIDTH: usize> MerkleHasher for fp64::poseidon2::Poseidon2<WIDTH> {
+    type Field = fp64::Fp;
+    type Digest = fp64::poseidon2::Digest;
+
+    fn hash_leaf(&self, leaf: &[fp64::Fp]) -> Self::Digest {
+        self.hash(leaf)
+    }
+
+    fn compress(&self, left: &Self::Digest, right: &Self::Digest) -> Self::Digest {
+        self.compress(left, right)
+    }
+}
+
+/// The reasons a tree or an opening can't be created or verified
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MerkleError {
+    /// The number of leaves is not a power of two
+    InvalidNumLeaves(usize),
+    /// A leaf index is not below the number of leaves
+    IndexOutOfRange { index: usize, num_leaves: usize },
+    /// A batch opening has no indices or they are not strictly increasing
+    InvalidIndices,
+    /// The number of opened leaves doesn't match the number of indices
+    LeafCountMismatch { indices: usize, leaves: usize },
+    /// The proof has too few or too many siblings
+    InvalidProofLength,
+    /// The recomputed root differs from the commitment
+    RootMismatch,
+}
+
+impl Display for MerkleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNumLeaves(n) => write!(f, "{n} leaves is not a power of two"),
+            Self::IndexOutOfRange { index, num_leaves } => {
+                write!(
+                    f,
+                    "leaf index {index} is out of range for {num_leaves} leaves"
+                )
+            }
+            Self::InvalidIndices => write!(f, "indices are empty or not strictly increasing"),
+            Self::LeafCountMismatch { indices, leaves } => {
+                write!(f, "expected {indices} leaves but got {leaves}")
+            }
+            Self::InvalidProofLength => write!(f, "proof has the wrong number of siblings"),
+            Self::RootMismatch => write!(f, "recomputed root doesn't match the commitment"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MerkleError {}
+
+/// A Merkle tree over leaves of field elements
+#[derive(Clone, Debug)]
+pub struct MerkleTree<H: MerkleHasher> {
+    hasher: H,
+    leaves: Vec<Vec<H::Field>>,
+    /// All nodes in heap order. Node 0 is unused.
+    nodes: Vec<H::Digest>,
+}
+
+impl<H: MerkleHasher> MerkleTree<H> {
+    /// Builds a tree over `leaves`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MerkleError::InvalidNumLeaves`] if the number of leaves is not
+    /// a power of two.
+    pub fn new(hasher: H, leaves: Vec<Vec<H::Field>>) -> Result<Self, MerkleError> {
+        let num_leaves = leaves.len();
+        if !num_leaves.is_power_of_two() {
+            return Err(MerkleError::InvalidNumLeaves(num_leaves));
+        }
+        let mut nodes = ark_std::vec![H::Digest::default(); 2 * num_leaves];
+        for (node, leaf) in nodes[num_leaves..].iter_mut().zip(&leaves) {
+            *node = hasher.hash_leaf(leaf);
+        }
+        for k in (1..num_leaves).rev() {
+            nodes[k] = hasher.compress(&nodes[2 * k], &nodes[2 * k + 1]);
+        }
+        Ok(Self {
+            hasher,
+            leaves,
+            nodes,
+        })
+    }
+
+    /// Returns the root, which is the commitment to the leaves.
+    #[must_use]
+    pub fn root(&self) -> H::Digest {
+        self.nodes[1]
+    }
+
+    #[must_use]
+    pub const fn num_leaves(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Returns the depth of the tree, i.e. the length of an authentication
+    /// path.
+    #[must_use]
+    pub const fn depth(&self) -> u32 {
+        self.num_leaves().ilog2()
+    }
+
+    #[must_use]
+    pub const fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    #[must_use]
+    pub fn leaf(&self, index: usize) -> Option<&[H::Field]> {
+        self.leaves.get(index).map(Vec::as_slice)
+    }
+
+    /// Returns the authentication path of leaf `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MerkleError::IndexOutOfRange`] if `index` is not a leaf.
+    pub fn open(&self, index: usize) -> Result<MerklePath<H::Digest>, MerkleError> {
+        let num_leaves = self.num_leaves();
+        if index >= num_leaves {
+            return Err(MerkleError::IndexOutOfRange { index, num_leaves });
+        }
+        let mut node = num_leaves + index;
+        let mut siblings = Vec::with_capacity(self.depth() as usize);
+        while node > 1 {
+            siblings.push(self.nodes[node ^ 1]);
+            node /= 2;
+        }
+        Ok(MerklePath { siblings })
+    }
+
+    /// Returns a proof for the leaves at `indices`, which must be strictly
+    /// increasing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MerkleError::InvalidIndices`] if `indices` is empty or not
+    /// strictly increasing and [`MerkleError::IndexOutOfRange`] if an index
+    /// is not a leaf.
+    pub fn open_batch(
+        &self,
+        indices: &[usize],
+    ) -> Result<BatchMerkleProof<H::Digest>, MerkleError> {
+        let mut nodes = leaf_nodes(self.num_leaves(), indices)?;
+        let mut siblings = Vec::new();
+        while nodes[0] > 1 {
+            let mut i = 0;
+            let mut len = 0;
+            while i < nodes.len() {
+                let node = nodes[i];
+                if nodes.get(i + 1) == Some(&(node ^ 1)) {
+                    i += 1;
+                } else {
+                    siblings.push(self.nodes[node ^ 1]);
+                }
+                nodes[len] = node / 2;
+                len += 1;
+                i += 1;
+            }
+            nodes.truncate(len);
+        }
+        Ok(BatchMerkleProof { siblings })
+    }
+}
+
+/// Converts strictly increasing leaf indices to node indices.
+fn leaf_nodes(num_leaves: usize, indices: &[usize]) -> Result<Vec<usize>, MerkleError> {
+    if !num_leaves.is_power_of_two() {
+        return Err(MerkleError::InvalidNumLeaves(num_leaves));
+    }
+    if indices.is_empty() || indices.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(MerkleError::InvalidIndices);
+    }
+    if let Some(&index) = indices.iter().find(|&&index| index >= num_leaves) {
+        return Err(MerkleError::IndexOutOfRange { index, num_leaves });
+    }
+    Ok(indices.iter().map(|index| num_leaves + index).collect())
+}
+
+/// The siblings of the nodes on the path from a leaf to the root, starting
+/// with the leaf's sibling
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerklePath<D> {
+    pub siblings: Vec<D>,
+}
+
+impl<D: Copy + Eq> MerklePath<D> {
+    /// Checks that `leaf` is the leaf at `index` of a tree with `num_leaves`
+    /// leaves and the given `root`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `num_leaves` or `index` are invalid, the path
+    /// length isn't the depth of the tree or the recomputed root differs.
+    pub fn verify<H: MerkleHasher<Digest = D>>(
+        &self,
+        hasher: &H,
+        root: &D,
+        num_leaves: usize,
+        index: usize,
+        leaf: &[H::Field],
+    ) -> Result<(), MerkleError> {
+        let mut node = leaf_nodes(num_leaves, &[index])?[0];
+        if self.siblings.len() != num_leaves.ilog2() as usize {
+            return Err(MerkleError::InvalidProofLength);
+        }
+        let mut digest = hasher.hash_leaf(leaf);
+        for sibling in &self.siblings {
+            digest = if node % 2 == 0 {
+                hasher.compress(&digest, sibling)
+            } else {
+                hasher.compress(sibling, &digest)
+            };
+            node /= 2;
+        }
+        if digest == *root {
+            Ok(())
+        } else {
+            Err(MerkleError::RootMismatch)
+        }
+    }
+}
+
+/// An opening of several leaves with the siblings that can't be recomputed
+/// from the leaves, ordered by level from the leaves up and by position
+/// within a level
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchMerkleProof<D> {
+    pub siblings: Vec<D>,
+}
+
+impl<D: Copy + Eq> BatchMerkleProof<D> {
+    /// Checks that `leaves[j]` is the leaf at `indices[j]` of a tree with
+    /// `num_leaves` leaves and the given `root`. The indices must be strictly
+    /// increasing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `num_leaves` or the indices are invalid, the number
+    /// of leaves or siblings is wrong or the recomputed root differs.
+    pub fn verify<H: MerkleHasher<Digest = D>, L: AsRef<[H::Field]>>(
+        &self,
+        hasher: &H,
+        root: &D,
+        num_leaves: usize,
+        indices: &[usize],
+        leaves: &[L],
+    ) -> Result<(), MerkleError> {
+        let mut nodes = leaf_nodes(num_leaves, indices)?;
+        if leaves.len() != indices.len() {
+            return Err(MerkleError::LeafCountMismatch {
+                indices: indices.len(),
+                leaves: leaves.len(),
+            });
+        }
+        let mut digests: Vec<D> = leaves
+            .iter()
+            .map(|leaf| hasher.hash_leaf(leaf.as_ref()))
+            .collect();
+        let mut siblings = self.siblings.iter();
+        while nodes[0] > 1 {
+            let mut i = 0;
+            let mut len = 0;
+            while i < nodes.len() {
+                let node = nodes[i];
+                let digest = if nodes.get(i + 1) == Some(&(node ^ 1)) {
+                    i += 1;
+                    hasher.compress(&digests[i - 1], &digests[i])
+                } else {
+                    let sibling = siblings.next().ok_or(MerkleError::InvalidProofLength)?;
+                    if node % 2 == 0 {
+                        hasher.compress(&digests[i], sibling)
+                    } else {
+                        hasher.compress(sibling, &digests[i])
+                    }
+                };
+                nodes[len] = node / 2;
+                digests[len] = digest;
+                len += 1;
+                i += 1;
+            }
+            nodes.truncate(len);
+            digests.truncate(len);
+        }
+        if siblings.next().is_some() {
+            return Err(MerkleError::InvalidProofLength);
+        }
+        if digests[0] == *root {
+            Ok(())
+        } else {
+            Err(MerkleError::RootMismatch)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchMerkleProof, MerkleError, MerkleHasher, MerkleTree};
+    use crate::{fp31, fp64};
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    fn random_tree<H: MerkleHasher>(hasher: H, num_leaves: usize) -> MerkleTree<H>
+    where
+        H::Field: UniformRand,
+    {
+        let mut rng = test_rng();
+        let leaves = (0..num_leaves)
+            .map(|i| (0..=i % 5).map(|_| H::Field::rand(&mut rng)).collect())
+            .collect();
+        MerkleTree::new(hasher, leaves).unwrap()
+    }
+
+    fn check_paths<H: MerkleHasher>(tree: &MerkleTree<H>) {
+        let root = tree.root();
+        let num_leaves = tree.num_leaves();
+        for index in 0..num_leaves {
+            let leaf = tree.leaf(index).unwrap();
+            let path = tree.open(index).unwrap();
+            assert_eq!(path.siblings.len(), tree.depth() as usize);
+            let hasher = tree.hasher();
+            assert_eq!(path.verify(hasher, &root, num_leaves, index, leaf), Ok(()));
+            let other = (index + 1) % num_leaves;
+            if other != index {
+                assert_eq!(
+                    path.verify(hasher, &root, num_leaves, other, leaf),
+                    Err(MerkleError::RootMismatch)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn paths_verify() {
+        for log_num_leaves in 0..6 {
+            let num_leaves = 1 << log_num_leaves;
+            check_paths(&random_tree(
+                fp31::poseidon2::Poseidon2::<16>::new(),
+                num_leaves,
+            ));
+            check_paths(&random_tree(
+                fp64::poseidon2::Poseidon2::<8>::new(),
+                num_leaves,
+            ));
+        }
+    }
+
+    #[test]
+    fn root_of_two_leaves() {
+        let hasher = fp64::poseidon2::Poseidon2::<12>::new();
+        let leaves = ark_std::vec![ark_std::vec![fp64::Fp::from(1u64)], Vec::new()];
+        let tree = MerkleTree::new(hasher.clone(), leaves).unwrap();
+        let expected = hasher.compress(&hasher.hash(&[fp64::Fp::from(1u64)]), &hasher.hash(&[]));
+        assert_eq!(tree.root(), expected);
+    }
+
+    #[test]
+    fn rejects_invalid_num_leaves() {
+        let hasher = fp31::poseidon2::Poseidon2::<16>::new();
+        assert_eq!(
+            MerkleTree::new(hasher.clone(), Vec::new()).unwrap_err(),
+            MerkleError::InvalidNumLeaves(0)
+        );
+        assert_eq!(
+            MerkleTree::new(hasher, ark_std::vec![Vec::new(); 6]).unwrap_err(),
+            MerkleError::InvalidNumLeaves(6)
+        );
+    }
+
+    #[test]
+    fn path_rejects_tampering() {
+        let tree = random_tree(fp31::poseidon2::Poseidon2::<16>::new(), 16);
+        let hasher = tree.hasher();
+        let root = tree.root();
+        let leaf = tree.leaf(5).unwrap();
+        let mut path = tree.open(5).unwrap();
+        assert_eq!(
+            tree.open(16),
+            Err(MerkleError::IndexOutOfRange {
+                index: 16,
+                num_leaves: 16
+            })
+        );
+        assert_eq!(
+            path.verify(hasher, &root, 16, 16, leaf),
+            Err(MerkleError::IndexOutOfRange {
+                index: 16,
+                num_leaves: 16
+            })
+        );
+        assert_eq!(
+            path.verify(hasher, &root, 12, 5, leaf),
+            Err(MerkleError::InvalidNumLeaves(12))
+        );
+        assert_eq!(
+            path.verify(hasher, &root, 32, 5, leaf),
+            Err(MerkleError::InvalidProofLength)
+        );
+        let mut modified = leaf.to_vec();
+        modified[0] += fp31::Fp::from(1u32);
+        assert_eq!(
+            path.verify(hasher, &root, 16, 5, &modified),
+            Err(MerkleError::RootMismatch)
+        );
+        path.siblings[2][0] += fp31::Fp::from(1u32);
+        assert_eq!(
+            path.verify(hasher, &root, 16, 5, leaf),
+            Err(MerkleError::RootMismatch)
+        );
+    }
+
+    fn opened_leaves<H: MerkleHasher>(
+        tree: &MerkleTree<H>,
+        indices: &[usize],
+    ) -> Vec<Vec<H::Field>> {
+        indices
+            .iter()
+            .map(|&index| tree.leaf(index).unwrap().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn batch_openings_verify() {
+        let tree = random_tree(fp64::poseidon2::Poseidon2::<12>::new(), 32);
+        let hasher = tree.hasher();
+        let root = tree.root();
+        for indices in [
+            &[0][..],
+            &[31],
+            &[0, 1],
+            &[3, 4],
+            &[1, 2, 3, 17, 30],
+            &(0..32).collect::<Vec<_>>(),
+        ] {
+            let proof = tree.open_batch(indices).unwrap();
+            let leaves = opened_leaves(&tree, indices);
+            assert_eq!(proof.verify(hasher, &root, 32, indices, &leaves), Ok(()));
+        }
+    }
+
+    #[test]
+    fn batch_proof_deduplicates_siblings() {
+        let tree = random_tree(fp31::poseidon2::Poseidon2::<24>::new(), 16);
+        // a single leaf needs the whole path
+        assert_eq!(
+            tree.open_batch(&[6]).unwrap().siblings,
+            tree.open(6).unwrap().siblings
+        );
+        // siblings 0 and 1 share every node above them
+        assert_eq!(tree.open_batch(&[0, 1]).unwrap().siblings.len(), 3);
+        // 0..4 and 8..12 are full subtrees that only need the roots of 4..8
+        // and 12..16
+        let indices = [0, 1, 2, 3, 8, 9, 10, 11];
+        assert_eq!(tree.open_batch(&indices).unwrap().siblings.len(), 2);
+        // all leaves need no siblings
+        let indices: Vec<usize> = (0..16).collect();
+        assert!(tree.open_batch(&indices).unwrap().siblings.is_empty());
+    }
+
+    #[test]
+    fn batch_rejects_invalid_openings() {
+        let tree = random_tree(fp31::poseidon2::Poseidon2::<16>::new(), 16);
+        let hasher = tree.hasher();
+        let root = tree.root();
+        assert_eq!(tree.open_batch(&[]), Err(MerkleError::InvalidIndices));
+        assert_eq!(tree.open_batch(&[3, 3]), Err(MerkleError::InvalidIndices));
+        assert_eq!(tree.open_batch(&[4, 3]), Err(MerkleError::InvalidIndices));
+        assert_eq!(
+            tree.open_batch(&[3, 16]),
+            Err(MerkleError::IndexOutOfRange {
+                index: 16,
+                num_leaves: 16
+            })
+        );
+
+        let indices = [2, 7, 13];
+        let proof = tree.open_batch(&indices).unwrap();
+        let leaves = opened_leaves(&tree, &indices);
+        assert_eq!(
+            proof.verify(hasher, &root, 16, &indices, &leaves[..2]),
+            Err(MerkleError::LeafCountMismatch {
+                indices: 3,
+                leaves: 2
+            })
+        );
+        assert_eq!(
+            proof.verify(hasher, &root, 16, &[2, 7, 12], &leaves),
+            Err(MerkleError::RootMismatch)
+        );
+        let mut swapped = leaves.clone();
+        swapped.swap(0, 1);
+        assert_eq!(
+            proof.verify(hasher, &root, 16, &indices, &swapped),
+            Err(MerkleError::RootMismatch)
+        );
+
+        let mut short = proof.clone();
+        short.siblings.pop();
+        assert_eq!(
+            short.verify(hasher, &root, 16, &indices, &leaves),
+            Err(MerkleError::InvalidProofLength)
+        );
+        let mut long = proof.clone();
+        long.siblings.push(root);
+        assert_eq!(
+            long.verify(hasher, &root, 16, &indices, &leaves),
+            Err(MerkleError::InvalidProofLength)
+        );
+        let mut tampered: BatchMerkleProof<_> = proof;
+        tampered.siblings[0][7] += fp31::Fp::from(1u32);
+        assert_eq!(
+            tampered.verify(hasher, &root, 16, &indices, &leaves),
+            Err(MerkleError::RootMismatch)
+        );
+    }
+}
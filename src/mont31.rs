@@ -0,0 +1,946 @@
+//! Shared implementation of 31-bit prime fields stored in Montgomery form with
+//! auxiliary modulus `R = 2^32`.
+//!
+//! [`crate::babybear`] and [`crate::koalabear`] only differ in their constants
+//! and in the addition chain used for inversion so both are generated by the
+//! [`mont31_field`] macro below.
+
+/// Defines a prime field `Fp` for a modulus `p < 2^31` in the invoking module.
+///
+/// * `modulus`: the field modulus `p`
+/// * `mu`: `p^-1 mod 2^32` used for Montgomery reduction
+/// * `r2`: `(2^32)^2 mod p`
+/// * `generator`, `two_adicity`, `two_adic_root_of_unity`: the [`FftField`]
+///   constants given as integers in `0..p`
+/// * `inverse`: an expression computing `x^(p-2)` for the element bound to `x`
+///
+/// [`FftField`]: ark_ff::FftField
+macro_rules! mont31_field {
+    (
+        modulus: $modulus:expr,
+        mu: $mu:expr,
+        r2: $r2:expr,
+        generator: $generator:expr,
+        two_adicity: $two_adicity:expr,
+        two_adic_root_of_unity: $two_adic_root_of_unity:expr,
+        inverse: |$x:ident| $inverse:expr $(,)?
+    ) => {
+        use ark_ff::{
+            BigInt, FftField, Field, LegendreSymbol, One, PrimeField, SqrtPrecomputation, Zero,
+        };
+        use ark_serialize::{
+            buffer_byte_size, CanonicalDeserialize, CanonicalDeserializeWithFlags,
+            CanonicalSerialize, CanonicalSerializeWithFlags, Compress, EmptyFlags, Flags,
+            SerializationError, Valid, Validate,
+        };
+        use ark_std::string::ToString;
+        use core::{
+            fmt::{Debug, Display, Formatter},
+            hash::Hash,
+            iter::{Product, Sum},
+            ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+            str::FromStr,
+        };
+
+        /// Field modulus `p`
+        const MODULUS: u32 = $modulus;
+
+        const MODULUS_BIT_SIZE: u32 = 31;
+
+        /// Inverse of the modulus `MU ≡ p^-1 mod 2^32` used for Montgomery reduction
+        const MU: u32 = $mu;
+
+        /// Square of auxiliary modulus R for Montgomery reduction `R2 ≡ (2^32)^2 mod p`
+        const R2: u32 = $r2;
+
+        #[derive(Clone, Copy, Default)]
+        pub struct Fp(pub u32);
+
+        impl Fp {
+            /// Converts a value in the range `0..p` into Montgomery representation.
+            #[inline]
+            const fn new(value: u32) -> Self {
+                Self(mont_red(value as u64 * R2 as u64))
+            }
+
+            #[inline]
+            const fn add_assign(&mut self, rhs: Self) {
+                let (sum, overflow) = self.0.overflowing_sub(MODULUS - rhs.0);
+                self.0 = if overflow {
+                    sum.wrapping_add(MODULUS)
+                } else {
+                    sum
+                };
+            }
+
+            #[inline]
+            const fn sub_assign(&mut self, rhs: Self) {
+                let (diff, underflow) = self.0.overflowing_sub(rhs.0);
+                self.0 = if underflow {
+                    diff.wrapping_add(MODULUS)
+                } else {
+                    diff
+                };
+            }
+
+            #[inline]
+            const fn mul(self, rhs: Self) -> Self {
+                Self(mont_red(self.0 as u64 * rhs.0 as u64))
+            }
+
+            #[inline]
+            const fn sq(self) -> Self {
+                self.mul(self)
+            }
+
+            const fn sqn(mut self, n: u32) -> Self {
+                let mut i = 0;
+                while i < n {
+                    self = self.sq();
+                    i += 1;
+                }
+                self
+            }
+
+            const fn is_zero(self) -> bool {
+                self.0 == 0
+            }
+
+            const fn into_integer(self) -> u32 {
+                mont_red(self.0 as u64)
+            }
+        }
+
+        /// Performs Montgomery reduction i.e. computes `x * 2^-32 mod p` for an input
+        /// `x < p * 2^32`. The output is in the range `0..p`.
+        #[inline]
+        const fn mont_red(x: u64) -> u32 {
+            #[allow(clippy::cast_possible_truncation)]
+            let t = (x as u32).wrapping_mul(MU);
+            let u = t as u64 * MODULUS as u64;
+            let (x_sub_u, underflow) = x.overflowing_sub(u);
+            #[allow(clippy::cast_possible_truncation)]
+            let r = (x_sub_u >> 32) as u32;
+            if underflow {
+                r.wrapping_add(MODULUS)
+            } else {
+                r
+            }
+        }
+
+        impl Field for Fp {
+            type BasePrimeField = Self;
+            type BasePrimeFieldIter = core::iter::Once<Self::BasePrimeField>;
+
+            const SQRT_PRECOMP: Option<SqrtPrecomputation<Self>> =
+                Some(SqrtPrecomputation::TonelliShanks {
+                    two_adicity: <Self as FftField>::TWO_ADICITY,
+                    quadratic_nonresidue_to_trace: <Self as FftField>::TWO_ADIC_ROOT_OF_UNITY,
+                    trace_of_modulus_minus_one_div_two:
+                        &<Self as PrimeField>::TRACE_MINUS_ONE_DIV_TWO.0,
+                });
+
+            const ZERO: Self = Self::new(0);
+
+            const ONE: Self = Self::new(1);
+
+            fn extension_degree() -> u64 {
+                1
+            }
+
+            fn from_base_prime_field(elem: Self::BasePrimeField) -> Self {
+                elem
+            }
+
+            fn to_base_prime_field_elements(&self) -> Self::BasePrimeFieldIter {
+                core::iter::once(*self)
+            }
+
+            fn from_base_prime_field_elems(elems: &[Self::BasePrimeField]) -> Option<Self> {
+                if elems.len() != usize::try_from(Self::extension_degree()).unwrap() {
+                    return None;
+                }
+                Some(elems[0])
+            }
+
+            #[inline]
+            fn double(&self) -> Self {
+                let mut temp = *self;
+                temp.double_in_place();
+                temp
+            }
+
+            #[inline]
+            fn double_in_place(&mut self) -> &mut Self {
+                Self::add_assign(self, *self);
+                self
+            }
+
+            #[inline]
+            fn neg_in_place(&mut self) -> &mut Self {
+                if !self.is_zero() {
+                    self.0 = MODULUS - self.0;
+                }
+                self
+            }
+
+            #[inline]
+            fn characteristic() -> &'static [u64] {
+                const _MODULUS: &[u64] = &[MODULUS as u64];
+                _MODULUS
+            }
+
+            #[inline]
+            fn sum_of_products<const T: usize>(a: &[Self; T], b: &[Self; T]) -> Self {
+                a.iter().zip(b).map(|(&a, b)| a * b).sum()
+            }
+
+            #[inline]
+            fn from_random_bytes_with_flags<F: Flags>(bytes: &[u8]) -> Option<(Self, F)> {
+                if F::BIT_SIZE > 8 {
+                    return None;
+                }
+                let output_byte_size = buffer_byte_size(MODULUS_BIT_SIZE as usize + F::BIT_SIZE);
+                let mut b = [0u8; 5];
+                let len = bytes.len().min(output_byte_size);
+                b[..len].copy_from_slice(&bytes[..len]);
+                let flags = F::from_u8_remove_flags(&mut b[output_byte_size - 1])?;
+                let value = u32::from_le_bytes(b[0..4].try_into().unwrap()) & (u32::MAX >> 1);
+                if value < MODULUS {
+                    Some((Self::new(value), flags))
+                } else {
+                    None
+                }
+            }
+
+            #[inline]
+            fn square(&self) -> Self {
+                let mut temp = *self;
+                temp.square_in_place();
+                temp
+            }
+
+            fn square_in_place(&mut self) -> &mut Self {
+                *self = self.sq();
+                self
+            }
+
+            #[inline]
+            fn inverse(&self) -> Option<Self> {
+                if self.is_zero() {
+                    None
+                } else {
+                    let $x = *self;
+                    Some($inverse)
+                }
+            }
+
+            fn inverse_in_place(&mut self) -> Option<&mut Self> {
+                self.inverse().map(|inverse| {
+                    *self = inverse;
+                    self
+                })
+            }
+
+            /// The Frobenius map has no effect in a prime field.
+            #[inline]
+            fn frobenius_map_in_place(&mut self, _: usize) {}
+
+            #[inline]
+            fn legendre(&self) -> LegendreSymbol {
+                let s = self.pow([(u64::from(MODULUS) - 1) / 2]);
+                if s.is_zero() {
+                    LegendreSymbol::Zero
+                } else if s.is_one() {
+                    LegendreSymbol::QuadraticResidue
+                } else {
+                    LegendreSymbol::QuadraticNonResidue
+                }
+            }
+        }
+
+        impl PrimeField for Fp {
+            type BigInt = BigInt<1>;
+            const MODULUS: Self::BigInt = BigInt([MODULUS as u64]);
+            const MODULUS_MINUS_ONE_DIV_TWO: Self::BigInt = Self::MODULUS.divide_by_2_round_down();
+            const MODULUS_BIT_SIZE: u32 = Self::MODULUS.const_num_bits();
+            const TRACE: Self::BigInt = Self::MODULUS.two_adic_coefficient();
+            const TRACE_MINUS_ONE_DIV_TWO: Self::BigInt = Self::TRACE.divide_by_2_round_down();
+
+            #[inline]
+            fn from_bigint(r: BigInt<1>) -> Option<Self> {
+                let value = r.0[0];
+                if value < u64::from(MODULUS) {
+                    #[allow(clippy::cast_possible_truncation)]
+                    Some(Self::new(value as u32))
+                } else {
+                    None
+                }
+            }
+
+            fn into_bigint(self) -> BigInt<1> {
+                BigInt([self.into_integer().into()])
+            }
+        }
+
+        impl FftField for Fp {
+            const GENERATOR: Self = Self::new($generator);
+            const TWO_ADICITY: u32 = $two_adicity;
+            const TWO_ADIC_ROOT_OF_UNITY: Self = Self::new($two_adic_root_of_unity);
+            const SMALL_SUBGROUP_BASE: Option<u32> = None;
+            const SMALL_SUBGROUP_BASE_ADICITY: Option<u32> = None;
+            const LARGE_SUBGROUP_ROOT_OF_UNITY: Option<Self> = None;
+        }
+
+        impl zeroize::Zeroize for Fp {
+            fn zeroize(&mut self) {
+                self.0.zeroize();
+            }
+        }
+
+        impl Debug for Fp {
+            fn fmt(&self, f: &mut Formatter<'_>) -> ark_std::fmt::Result {
+                ark_std::fmt::Debug::fmt(&self.into_integer(), f)
+            }
+        }
+
+        impl Zero for Fp {
+            #[inline]
+            fn zero() -> Self {
+                Self::ZERO
+            }
+
+            #[inline]
+            fn is_zero(&self) -> bool {
+                (*self).is_zero()
+            }
+        }
+
+        impl One for Fp {
+            #[inline]
+            fn one() -> Self {
+                Self::ONE
+            }
+
+            #[inline]
+            fn is_one(&self) -> bool {
+                *self == Self::ONE
+            }
+        }
+
+        impl PartialEq for Fp {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl Hash for Fp {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.into_integer().hash(state);
+            }
+        }
+
+        impl Eq for Fp {}
+
+        /// Note that this implementation of `Ord` compares field elements viewing
+        /// them as integers in the range 0, 1, ..., `P::MODULUS` - 1. However, other
+        /// implementations of `PrimeField` might choose a different ordering, and
+        /// as such, users should use this `Ord` for applications where
+        /// any ordering suffices (like in a `BTreeMap`), and not in applications
+        /// where a particular ordering is required.
+        impl Ord for Fp {
+            #[inline]
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.into_integer().cmp(&other.into_integer())
+            }
+        }
+
+        /// Note that this implementation of `PartialOrd` compares field elements
+        /// viewing them as integers in the range 0, 1, ..., `P::MODULUS` - 1. However,
+        /// other implementations of `PrimeField` might choose a different ordering, and
+        /// as such, users should use this `PartialOrd` for applications where
+        /// any ordering suffices (like in a `BTreeMap`), and not in applications
+        /// where a particular ordering is required.
+        impl PartialOrd for Fp {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl From<num_bigint::BigUint> for Fp {
+            fn from(other: num_bigint::BigUint) -> Self {
+                (other % MODULUS)
+                    .to_u32_digits()
+                    .iter()
+                    .copied()
+                    .map(Self::from)
+                    .sum()
+            }
+        }
+
+        impl From<Fp> for num_bigint::BigUint {
+            fn from(fp: Fp) -> Self {
+                fp.into_integer().into()
+            }
+        }
+
+        impl From<BigInt<1>> for Fp {
+            fn from(other: BigInt<1>) -> Self {
+                other.0[0].into()
+            }
+        }
+
+        impl From<Fp> for BigInt<1> {
+            fn from(fp: Fp) -> Self {
+                Self([fp.into_integer().into()])
+            }
+        }
+
+        impl From<u128> for Fp {
+            fn from(other: u128) -> Self {
+                #[allow(clippy::cast_possible_truncation)]
+                Self::new((other % u128::from(MODULUS)) as u32)
+            }
+        }
+
+        impl From<i128> for Fp {
+            fn from(other: i128) -> Self {
+                let abs = Self::from(other.unsigned_abs());
+                if other.is_positive() {
+                    abs
+                } else {
+                    -abs
+                }
+            }
+        }
+
+        impl From<bool> for Fp {
+            fn from(other: bool) -> Self {
+                Self::new(u32::from(other))
+            }
+        }
+
+        impl From<u64> for Fp {
+            fn from(other: u64) -> Self {
+                #[allow(clippy::cast_possible_truncation)]
+                Self::new((other % u64::from(MODULUS)) as u32)
+            }
+        }
+
+        impl From<i64> for Fp {
+            fn from(other: i64) -> Self {
+                let abs = Self::from(other.unsigned_abs());
+                if other.is_positive() {
+                    abs
+                } else {
+                    -abs
+                }
+            }
+        }
+
+        impl From<u32> for Fp {
+            fn from(other: u32) -> Self {
+                Self::new(other % MODULUS)
+            }
+        }
+
+        impl From<i32> for Fp {
+            fn from(other: i32) -> Self {
+                let abs = Self::from(other.unsigned_abs());
+                if other.is_positive() {
+                    abs
+                } else {
+                    -abs
+                }
+            }
+        }
+
+        impl From<u16> for Fp {
+            fn from(other: u16) -> Self {
+                Self::new(other.into())
+            }
+        }
+
+        impl From<i16> for Fp {
+            fn from(other: i16) -> Self {
+                let abs = Self::from(other.unsigned_abs());
+                if other.is_positive() {
+                    abs
+                } else {
+                    -abs
+                }
+            }
+        }
+
+        impl From<u8> for Fp {
+            fn from(other: u8) -> Self {
+                Self::new(other.into())
+            }
+        }
+
+        impl From<i8> for Fp {
+            fn from(other: i8) -> Self {
+                let abs = Self::from(other.unsigned_abs());
+                if other.is_positive() {
+                    abs
+                } else {
+                    -abs
+                }
+            }
+        }
+
+        impl ark_std::rand::distributions::Distribution<Fp>
+            for ark_std::rand::distributions::Standard
+        {
+            #[inline]
+            fn sample<R: ark_std::rand::Rng + ?Sized>(&self, rng: &mut R) -> Fp {
+                loop {
+                    let mut tmp: u32 = rng.sample(Self);
+
+                    // Mask away the unused bits at the beginning.
+                    let mask = u32::MAX >> (32 - MODULUS_BIT_SIZE);
+                    tmp &= mask;
+
+                    // Montgomery form is a bijection on `0..p` so the raw value can be
+                    // used directly.
+                    if tmp < MODULUS {
+                        return Fp(tmp);
+                    }
+                }
+            }
+        }
+
+        impl CanonicalSerializeWithFlags for Fp {
+            fn serialize_with_flags<W: ark_std::io::Write, F: Flags>(
+                &self,
+                mut writer: W,
+                flags: F,
+            ) -> Result<(), SerializationError> {
+                // All reasonable `Flags` should be less than 8 bits in size
+                // (256 values are enough for anyone!)
+                if F::BIT_SIZE > 8 {
+                    return Err(SerializationError::NotEnoughSpace);
+                }
+
+                // Calculate the number of bytes required to represent a field element
+                // serialized with `flags`.
+                let output_byte_size = buffer_byte_size(MODULUS_BIT_SIZE as usize + F::BIT_SIZE);
+                let mut b = [0u8; 5];
+                b[..4].copy_from_slice(&self.into_integer().to_le_bytes());
+                // Mask out the bits of the last byte that correspond to the flag.
+                b[output_byte_size - 1] |= flags.u8_bitmask();
+                writer.write_all(&b[..output_byte_size])?;
+                Ok(())
+            }
+
+            // Let `m = 8 * n` for some `n` be the smallest multiple of 8 greater
+            // than `P::MODULUS_BIT_SIZE`.
+            // If `(m - P::MODULUS_BIT_SIZE) >= F::BIT_SIZE` , then this method returns `n`;
+            // otherwise, it returns `n + 1`.
+            fn serialized_size_with_flags<F: Flags>(&self) -> usize {
+                buffer_byte_size(MODULUS_BIT_SIZE as usize + F::BIT_SIZE)
+            }
+        }
+
+        impl CanonicalSerialize for Fp {
+            #[inline]
+            fn serialize_with_mode<W: ark_std::io::Write>(
+                &self,
+                writer: W,
+                _compress: Compress,
+            ) -> Result<(), SerializationError> {
+                self.serialize_with_flags(writer, EmptyFlags)
+            }
+
+            #[inline]
+            fn serialized_size(&self, _compress: Compress) -> usize {
+                self.serialized_size_with_flags::<EmptyFlags>()
+            }
+        }
+
+        impl CanonicalDeserializeWithFlags for Fp {
+            fn deserialize_with_flags<R: ark_std::io::Read, F: Flags>(
+                mut reader: R,
+            ) -> Result<(Self, F), SerializationError> {
+                // All reasonable `Flags` should be less than 8 bits in size
+                // (256 values are enough for anyone!)
+                if F::BIT_SIZE > 8 {
+                    return Err(SerializationError::NotEnoughSpace);
+                }
+                // Calculate the number of bytes required to represent a field element
+                // serialized with `flags`.
+                let output_byte_size = buffer_byte_size(MODULUS_BIT_SIZE as usize + F::BIT_SIZE);
+                let mut b = [0u8; 5];
+                reader.read_exact(&mut b[..output_byte_size])?;
+                let flags = F::from_u8_remove_flags(&mut b[output_byte_size - 1])
+                    .ok_or(SerializationError::UnexpectedFlags)?;
+                let self_integer = u32::from_le_bytes(b[0..4].try_into().unwrap());
+                if self_integer < MODULUS {
+                    Ok((Self::new(self_integer), flags))
+                } else {
+                    Err(SerializationError::InvalidData)
+                }
+            }
+        }
+
+        impl Valid for Fp {
+            fn check(&self) -> Result<(), SerializationError> {
+                Ok(())
+            }
+        }
+
+        impl CanonicalDeserialize for Fp {
+            fn deserialize_with_mode<R: ark_std::io::Read>(
+                reader: R,
+                _compress: Compress,
+                _validate: Validate,
+            ) -> Result<Self, SerializationError> {
+                Self::deserialize_with_flags::<R, EmptyFlags>(reader).map(|(r, _)| r)
+            }
+        }
+
+        impl FromStr for Fp {
+            type Err = ();
+
+            /// Interpret a string of numbers as a (congruent) prime field element.
+            /// Does not accept unnecessary leading zeroes or a blank string.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                if s.is_empty() {
+                    return Err(());
+                }
+
+                if s == "0" {
+                    return Ok(Self::zero());
+                }
+
+                let mut res = Self::zero();
+
+                let ten = Self::new(10);
+
+                let mut first_digit = true;
+
+                for c in s.chars() {
+                    match c.to_digit(10) {
+                        Some(c) => {
+                            if first_digit {
+                                if c == 0 {
+                                    return Err(());
+                                }
+
+                                first_digit = false;
+                            }
+
+                            res.mul_assign(&ten);
+                            let digit = Self::from(u64::from(c));
+                            res.add_assign(digit);
+                        }
+                        None => {
+                            return Err(());
+                        }
+                    }
+                }
+                Ok(res)
+            }
+        }
+
+        /// Outputs a string containing the value of `self`,
+        /// represented as a decimal without leading zeroes.
+        impl Display for Fp {
+            #[inline]
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                let string = self.into_integer().to_string();
+                write!(f, "{string}")
+            }
+        }
+
+        impl Neg for Fp {
+            type Output = Self;
+            #[inline]
+            fn neg(mut self) -> Self {
+                Self::neg_in_place(&mut self);
+                self
+            }
+        }
+
+        impl Add<&Self> for Fp {
+            type Output = Self;
+
+            #[inline]
+            fn add(mut self, other: &Self) -> Self {
+                self.add_assign(*other);
+                self
+            }
+        }
+
+        impl Sub<&Self> for Fp {
+            type Output = Self;
+
+            #[inline]
+            fn sub(mut self, other: &Self) -> Self {
+                self.sub_assign(*other);
+                self
+            }
+        }
+
+        impl Mul<&Self> for Fp {
+            type Output = Self;
+
+            #[inline]
+            fn mul(mut self, other: &Self) -> Self {
+                self.mul_assign(other);
+                self
+            }
+        }
+
+        impl Div<&Self> for Fp {
+            type Output = Self;
+
+            /// Returns `self * other.inverse()` if `other.inverse()` is `Some`, and
+            /// panics otherwise.
+            #[inline]
+            fn div(mut self, other: &Self) -> Self {
+                self.mul_assign(&other.inverse().unwrap());
+                self
+            }
+        }
+
+        impl Add<&Fp> for &Fp {
+            type Output = Fp;
+
+            #[inline]
+            fn add(self, other: &Fp) -> Fp {
+                let mut result = *self;
+                result.add_assign(*other);
+                result
+            }
+        }
+
+        impl Sub<&Fp> for &Fp {
+            type Output = Fp;
+
+            #[inline]
+            fn sub(self, other: &Fp) -> Fp {
+                let mut result = *self;
+                result.sub_assign(*other);
+                result
+            }
+        }
+
+        impl Mul<&Fp> for &Fp {
+            type Output = Fp;
+
+            #[inline]
+            fn mul(self, other: &Fp) -> Fp {
+                let mut result = *self;
+                result.mul_assign(other);
+                result
+            }
+        }
+
+        impl Div<&Fp> for &Fp {
+            type Output = Fp;
+
+            #[inline]
+            fn div(self, other: &Fp) -> Fp {
+                let mut result = *self;
+                result.div_assign(other);
+                result
+            }
+        }
+
+        impl AddAssign<&Self> for Fp {
+            #[inline]
+            fn add_assign(&mut self, other: &Self) {
+                Self::add_assign(self, *other);
+            }
+        }
+
+        impl SubAssign<&Self> for Fp {
+            #[inline]
+            fn sub_assign(&mut self, other: &Self) {
+                Self::sub_assign(self, *other);
+            }
+        }
+
+        impl AddAssign<&mut Self> for Fp {
+            #[inline]
+            fn add_assign(&mut self, other: &mut Self) {
+                Self::add_assign(self, *other);
+            }
+        }
+
+        impl SubAssign<&mut Self> for Fp {
+            #[inline]
+            fn sub_assign(&mut self, other: &mut Self) {
+                Self::sub_assign(self, *other);
+            }
+        }
+
+        impl AddAssign<Self> for Fp {
+            #[inline]
+            fn add_assign(&mut self, other: Self) {
+                Self::add_assign(self, other);
+            }
+        }
+
+        impl SubAssign<Self> for Fp {
+            #[inline]
+            fn sub_assign(&mut self, other: Self) {
+                Self::sub_assign(self, other);
+            }
+        }
+
+        impl Mul<Self> for Fp {
+            type Output = Self;
+
+            #[inline]
+            fn mul(mut self, other: Self) -> Self {
+                self.mul_assign(&other);
+                self
+            }
+        }
+
+        impl Div<Self> for Fp {
+            type Output = Self;
+
+            #[inline]
+            fn div(mut self, other: Self) -> Self {
+                self.div_assign(&other);
+                self
+            }
+        }
+
+        impl Add<Self> for Fp {
+            type Output = Self;
+
+            #[inline]
+            fn add(mut self, other: Self) -> Self {
+                self.add_assign(other);
+                self
+            }
+        }
+
+        impl Sub<Self> for Fp {
+            type Output = Self;
+
+            #[inline]
+            fn sub(mut self, other: Self) -> Self {
+                self.sub_assign(other);
+                self
+            }
+        }
+
+        impl Add<&mut Self> for Fp {
+            type Output = Self;
+
+            #[inline]
+            fn add(self, other: &mut Self) -> Self {
+                let mut result = self;
+                result.add_assign(*other);
+                result
+            }
+        }
+
+        impl Sub<&mut Self> for Fp {
+            type Output = Self;
+
+            #[inline]
+            fn sub(self, other: &mut Self) -> Self {
+                let mut result = self;
+                result.sub_assign(*other);
+                result
+            }
+        }
+
+        impl Mul<&mut Self> for Fp {
+            type Output = Self;
+
+            #[inline]
+            fn mul(mut self, other: &mut Self) -> Self {
+                self.mul_assign(&*other);
+                self
+            }
+        }
+
+        impl Div<&mut Self> for Fp {
+            type Output = Self;
+
+            #[inline]
+            fn div(mut self, other: &mut Self) -> Self {
+                self.div_assign(&*other);
+                self
+            }
+        }
+
+        impl Product<Self> for Fp {
+            fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::one(), core::ops::Mul::mul)
+            }
+        }
+
+        impl<'a> Product<&'a Self> for Fp {
+            fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.fold(Self::one(), Mul::mul)
+            }
+        }
+
+        impl Sum<Self> for Fp {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::zero(), core::ops::Add::add)
+            }
+        }
+
+        impl<'a> Sum<&'a Self> for Fp {
+            fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.fold(Self::zero(), core::ops::Add::add)
+            }
+        }
+
+        impl MulAssign<Self> for Fp {
+            #[inline]
+            fn mul_assign(&mut self, other: Self) {
+                self.mul_assign(&other);
+            }
+        }
+
+        impl DivAssign<Self> for Fp {
+            #[inline]
+            fn div_assign(&mut self, other: Self) {
+                self.div_assign(&other);
+            }
+        }
+
+        impl MulAssign<&Self> for Fp {
+            #[inline]
+            fn mul_assign(&mut self, other: &Self) {
+                *self = Self::mul(*self, *other);
+            }
+        }
+
+        impl MulAssign<&mut Self> for Fp {
+            #[inline]
+            fn mul_assign(&mut self, other: &mut Self) {
+                self.mul_assign(&*other);
+            }
+        }
+
+        impl DivAssign<&mut Self> for Fp {
+            #[inline]
+            fn div_assign(&mut self, other: &mut Self) {
+                self.div_assign(&*other);
+            }
+        }
+
+        /// Computes `self *= other.inverse()` if `other.inverse()` is `Some`, and
+        /// panics otherwise.
+        impl DivAssign<&Self> for Fp {
+            #[inline]
+            fn div_assign(&mut self, other: &Self) {
+                self.mul_assign(&other.inverse().unwrap());
+            }
+        }
+    };
+}
+
+pub(crate) use mont31_field;
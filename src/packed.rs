@@ -0,0 +1,505 @@
+//! Packed field arithmetic shared by the fields in this crate.
+//!
+//! [`PackedField`] is implemented by every packed type so higher-level code
+//! can be written once:
+//! * [`Packed`] is a portable implementation on top of `core::simd` for any
+//!   [`SimdField`] and any number of lanes. It compiles on every target and
+//!   leaves the choice of instructions to LLVM.
+//! * [`PackedFp64x4`](crate::fp64::packed::PackedFp64x4) and
+//!   [`PackedFp31x8`](crate::fp31::packed::PackedFp31x8) use hand-written AVX2
+//!   when it is available.
+
+use ark_ff::Field;
+use core::{
+    fmt::{Debug, Formatter},
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    simd::{Simd, SimdElement},
+};
+
+/// A vector of [`WIDTH`](PackedField::WIDTH) field elements supporting
+/// lane-wise arithmetic
+pub trait PackedField:
+    'static
+    + Copy
+    + Debug
+    + Default
+    + PartialEq
+    + Eq
+    + Send
+    + Sync
+    + From<Self::Scalar>
+    + Add<Output = Self>
+    + AddAssign
+    + Sub<Output = Self>
+    + SubAssign
+    + Mul<Output = Self>
+    + MulAssign
+    + Neg<Output = Self>
+{
+    type Scalar: Field;
+
+    /// Number of lanes
+    const WIDTH: usize;
+
+    /// Returns a packed value with `value` in every lane.
+    fn broadcast(value: Self::Scalar) -> Self;
+
+    /// Returns a packed value with `f(i)` in lane `i`.
+    fn from_fn<G: FnMut(usize) -> Self::Scalar>(f: G) -> Self;
+
+    /// Returns the element in lane `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not less than [`WIDTH`](Self::WIDTH).
+    fn lane(&self, i: usize) -> Self::Scalar;
+
+    /// Packs the first [`WIDTH`](Self::WIDTH) elements of `slice`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slice` has fewer than [`WIDTH`](Self::WIDTH) elements.
+    fn from_slice(slice: &[Self::Scalar]) -> Self {
+        assert!(slice.len() >= Self::WIDTH);
+        Self::from_fn(|i| slice[i])
+    }
+
+    /// Writes the lanes to the first [`WIDTH`](Self::WIDTH) elements of
+    /// `slice`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slice` has fewer than [`WIDTH`](Self::WIDTH) elements.
+    fn to_slice(&self, slice: &mut [Self::Scalar]) {
+        for (i, element) in slice[..Self::WIDTH].iter_mut().enumerate() {
+            *element = self.lane(i);
+        }
+    }
+
+    /// Splits the lanes of `self` and `other` into blocks of `block_len` and
+    /// swaps the odd blocks of `self` with the even blocks of `other`.
+    ///
+    /// With `block_len = 1` and 4 lanes `[a0, a1, a2, a3]` and
+    /// `[b0, b1, b2, b3]` become `[a0, b0, a2, b2]` and `[a1, b1, a3, b3]`.
+    /// With `block_len = WIDTH` both values are returned unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_len` is not a power of two dividing
+    /// [`WIDTH`](Self::WIDTH).
+    #[must_use]
+    fn interleave(&self, other: &Self, block_len: usize) -> (Self, Self) {
+        assert!(block_len.is_power_of_two() && Self::WIDTH.is_multiple_of(block_len));
+        if block_len == Self::WIDTH {
+            return (*self, *other);
+        }
+        let is_even_block = |i: usize| (i / block_len).is_multiple_of(2);
+        let res0 = Self::from_fn(|i| {
+            if is_even_block(i) {
+                self.lane(i)
+            } else {
+                other.lane(i - block_len)
+            }
+        });
+        let res1 = Self::from_fn(|i| {
+            if is_even_block(i) {
+                self.lane(i + block_len)
+            } else {
+                other.lane(i)
+            }
+        });
+        (res0, res1)
+    }
+
+    #[must_use]
+    fn double(&self) -> Self {
+        *self + *self
+    }
+
+    #[must_use]
+    fn square(&self) -> Self {
+        *self * *self
+    }
+}
+
+/// A field whose elements can be stored in `core::simd` lanes
+///
+/// Each element is stored as its internal representation so that the lane-wise
+/// routines give the same results as the scalar field arithmetic.
+pub trait SimdField: Field {
+    type Lane: SimdElement + Send + Sync;
+
+    fn to_lane(self) -> Self::Lane;
+
+    fn from_lane(lane: Self::Lane) -> Self;
+
+    fn simd_add<const N: usize>(
+        a: Simd<Self::Lane, N>,
+        b: Simd<Self::Lane, N>,
+    ) -> Simd<Self::Lane, N>;
+
+    fn simd_sub<const N: usize>(
+        a: Simd<Self::Lane, N>,
+        b: Simd<Self::Lane, N>,
+    ) -> Simd<Self::Lane, N>;
+
+    fn simd_mul<const N: usize>(
+        a: Simd<Self::Lane, N>,
+        b: Simd<Self::Lane, N>,
+    ) -> Simd<Self::Lane, N>;
+
+    fn simd_neg<const N: usize>(a: Simd<Self::Lane, N>) -> Simd<Self::Lane, N>;
+
+    fn simd_double<const N: usize>(a: Simd<Self::Lane, N>) -> Simd<Self::Lane, N> {
+        Self::simd_add(a, a)
+    }
+}
+
+/// `N` elements of a [`SimdField`] operated on lane-wise with `core::simd`
+#[derive(Clone, Copy)]
+pub struct Packed<F: SimdField, const N: usize>(Simd<F::Lane, N>);
+
+impl<F: SimdField, const N: usize> Packed<F, N> {
+    #[must_use]
+    pub fn from_array(elements: [F; N]) -> Self {
+        Self(Simd::from_array(elements.map(F::to_lane)))
+    }
+
+    #[must_use]
+    pub fn to_array(&self) -> [F; N] {
+        self.0.to_array().map(F::from_lane)
+    }
+}
+
+impl<F: SimdField, const N: usize> PackedField for Packed<F, N> {
+    type Scalar = F;
+
+    const WIDTH: usize = N;
+
+    #[inline]
+    fn broadcast(value: F) -> Self {
+        Self(Simd::splat(value.to_lane()))
+    }
+
+    #[inline]
+    fn from_fn<G: FnMut(usize) -> F>(mut f: G) -> Self {
+        Self(Simd::from_array(core::array::from_fn(|i| f(i).to_lane())))
+    }
+
+    #[inline]
+    fn lane(&self, i: usize) -> F {
+        F::from_lane(self.0[i])
+    }
+
+    #[inline]
+    fn double(&self) -> Self {
+        Self(F::simd_double(self.0))
+    }
+}
+
+impl<F: SimdField, const N: usize> Default for Packed<F, N> {
+    fn default() -> Self {
+        Self::broadcast(F::ZERO)
+    }
+}
+
+impl<F: SimdField, const N: usize> Debug for Packed<F, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Packed").field(&self.to_array()).finish()
+    }
+}
+
+/// Compares lanes as field elements since a field may have more than one
+/// representation of an element.
+impl<F: SimdField, const N: usize> PartialEq for Packed<F, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_array() == other.to_array()
+    }
+}
+
+impl<F: SimdField, const N: usize> Eq for Packed<F, N> {}
+
+impl<F: SimdField, const N: usize> From<F> for Packed<F, N> {
+    fn from(value: F) -> Self {
+        Self::broadcast(value)
+    }
+}
+
+impl<F: SimdField, const N: usize> Add for Packed<F, N> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self(F::simd_add(self.0, rhs.0))
+    }
+}
+
+impl<F: SimdField, const N: usize> Sub for Packed<F, N> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(F::simd_sub(self.0, rhs.0))
+    }
+}
+
+impl<F: SimdField, const N: usize> Mul for Packed<F, N> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self(F::simd_mul(self.0, rhs.0))
+    }
+}
+
+impl<F: SimdField, const N: usize> Neg for Packed<F, N> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self(F::simd_neg(self.0))
+    }
+}
+
+impl<F: SimdField, const N: usize> AddAssign for Packed<F, N> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<F: SimdField, const N: usize> SubAssign for Packed<F, N> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<F: SimdField, const N: usize> MulAssign for Packed<F, N> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+/// Returns the AVX2 routine `avx2::$op` of the packed type `$packed` applied
+/// to the `$arg`s if AVX2 is supported and evaluates `$scalar` otherwise. The
+/// `avx2` module and the `as_raw`/`from_raw` conversions are those of the
+/// module using the macro.
+macro_rules! dispatch {
+    ($packed:ident, $op:ident, $scalar:expr, $($arg:expr),+) => {{
+        #[cfg(target_arch = "x86_64")]
+        if crate::packed::has_avx2() {
+            // SAFETY: AVX2 support has been checked above.
+            return $packed::from_raw(unsafe { avx2::$op($($arg.as_raw()),+) });
+        }
+        $scalar
+    }};
+}
+pub(crate) use dispatch;
+
+/// Sets `lhs[i] = $op(lhs[i], rhs[i])` for every `i` with the `avx2` or the
+/// `scalar` routine of the using module. AVX2 support is checked once and the
+/// loop is compiled with AVX2 enabled so the routines inline into it.
+macro_rules! dispatch_slice {
+    ($packed:ident, $op:ident, $lhs:expr, $rhs:expr) => {{
+        let (lhs, rhs): (&mut [$packed], &[$packed]) = ($lhs, $rhs);
+        assert_eq!(lhs.len(), rhs.len());
+        #[cfg(target_arch = "x86_64")]
+        if crate::packed::has_avx2() {
+            #[target_feature(enable = "avx2")]
+            unsafe fn apply(lhs: &mut [$packed], rhs: &[$packed]) {
+                for (x, y) in lhs.iter_mut().zip(rhs) {
+                    *x = $packed::from_raw(avx2::$op(x.as_raw(), y.as_raw()));
+                }
+            }
+            // SAFETY: AVX2 support has been checked above.
+            return unsafe { apply(lhs, rhs) };
+        }
+        for (x, y) in lhs.iter_mut().zip(rhs) {
+            *x = scalar::$op(*x, *y);
+        }
+    }};
+}
+pub(crate) use dispatch_slice;
+
+/// Returns `true` if the CPU supports AVX2.
+///
+/// This is a constant when the crate is compiled with AVX2 enabled, which lets
+/// the AVX2 routines inline into their callers. Otherwise the CPU is queried
+/// with `cpuid` on the first call and the answer is cached, so the check works
+/// without `std`.
+#[cfg(target_arch = "x86_64")]
+#[inline]
+pub(crate) fn has_avx2() -> bool {
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    const UNKNOWN: u8 = 0;
+    const UNSUPPORTED: u8 = 1;
+    const SUPPORTED: u8 = 2;
+    static AVX2: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    if cfg!(target_feature = "avx2") {
+        return true;
+    }
+    match AVX2.load(Ordering::Relaxed) {
+        UNKNOWN => {
+            let supported = detect_avx2();
+            AVX2.store(
+                if supported { SUPPORTED } else { UNSUPPORTED },
+                Ordering::Relaxed,
+            );
+            supported
+        }
+        state => state == SUPPORTED,
+    }
+}
+
+/// Queries AVX2 support with `cpuid`. Besides the CPU flag the OS must have
+/// enabled saving the YMM registers, which is reported by `xgetbv`.
+#[cfg(target_arch = "x86_64")]
+#[cold]
+fn detect_avx2() -> bool {
+    use core::arch::x86_64::{__cpuid, __cpuid_count, _xgetbv};
+
+    /// Reads the `XCR0` register holding the state saved by the OS.
+    #[target_feature(enable = "xsave")]
+    unsafe fn xcr0() -> u64 {
+        _xgetbv(0)
+    }
+
+    const OSXSAVE: u32 = 1 << 27;
+    const AVX: u32 = 1 << 28;
+    const XMM_YMM_STATE: u64 = 0b110;
+    const AVX2: u32 = 1 << 5;
+
+    if __cpuid(0).eax < 7 {
+        return false;
+    }
+    let features = __cpuid(1).ecx;
+    if features & (OSXSAVE | AVX) != OSXSAVE | AVX {
+        return false;
+    }
+    // SAFETY: `xgetbv` is enabled since the OSXSAVE bit is set.
+    if unsafe { xcr0() } & XMM_YMM_STATE != XMM_YMM_STATE {
+        return false;
+    }
+    __cpuid_count(7, 0).ebx & AVX2 != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Packed, PackedField};
+    use crate::{
+        fp31::{self, packed::PackedFp31x8},
+        fp64::{self, packed::PackedFp64x4},
+    };
+    use ark_ff::Field;
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    fn random<P: PackedField>() -> Vec<P> {
+        let mut rng = test_rng();
+        (0..100)
+            .map(|_| P::from_fn(|_| P::Scalar::rand(&mut rng)))
+            .collect()
+    }
+
+    /// Checks every operation of `P` against the scalar field lane by lane.
+    fn check_arithmetic<P: PackedField>() {
+        let vectors = random::<P>();
+        for (&a, &b) in vectors.iter().zip(vectors.iter().rev()) {
+            for i in 0..P::WIDTH {
+                let (x, y) = (a.lane(i), b.lane(i));
+                assert_eq!((a + b).lane(i), x + y);
+                assert_eq!((a - b).lane(i), x - y);
+                assert_eq!((a * b).lane(i), x * y);
+                assert_eq!((-a).lane(i), -x);
+                assert_eq!(a.double().lane(i), x.double());
+                assert_eq!(a.square().lane(i), x.square());
+            }
+        }
+        let value = P::Scalar::from(7u8);
+        assert_eq!(P::broadcast(value), P::from(value));
+        assert_eq!(P::default(), P::broadcast(P::Scalar::ZERO));
+    }
+
+    fn check_slices_and_interleave<P: PackedField>() {
+        let mut rng = test_rng();
+        let values: Vec<P::Scalar> = (0..2 * P::WIDTH)
+            .map(|_| P::Scalar::rand(&mut rng))
+            .collect();
+        let a = P::from_slice(&values);
+        let b = P::from_slice(&values[P::WIDTH..]);
+        let mut out = values.clone();
+        out.reverse();
+        a.to_slice(&mut out);
+        assert_eq!(out[..P::WIDTH], values[..P::WIDTH]);
+
+        assert_eq!(a.interleave(&b, P::WIDTH), (a, b));
+        let mut block_len = 1;
+        while block_len < P::WIDTH {
+            let (res0, res1) = a.interleave(&b, block_len);
+            for i in 0..P::WIDTH {
+                // even blocks of the results come from `a` and odd blocks from
+                // `b`, taking the first and the second block of each pair
+                let block = i / block_len;
+                let offset = i % block_len;
+                let pair_start = (block / 2) * 2 * block_len;
+                if block % 2 == 0 {
+                    assert_eq!(res0.lane(i), a.lane(pair_start + offset));
+                    assert_eq!(res1.lane(i), a.lane(pair_start + block_len + offset));
+                } else {
+                    assert_eq!(res0.lane(i), b.lane(pair_start + offset));
+                    assert_eq!(res1.lane(i), b.lane(pair_start + block_len + offset));
+                }
+            }
+            let (x, y) = res0.interleave(&res1, block_len);
+            assert_eq!((x, y), (a, b));
+            block_len *= 2;
+        }
+    }
+
+    #[test]
+    fn portable_fp64() {
+        check_arithmetic::<Packed<fp64::Fp, 1>>();
+        check_arithmetic::<Packed<fp64::Fp, 4>>();
+        check_arithmetic::<Packed<fp64::Fp, 8>>();
+        check_slices_and_interleave::<Packed<fp64::Fp, 8>>();
+    }
+
+    #[test]
+    fn portable_fp31() {
+        check_arithmetic::<Packed<fp31::Fp, 4>>();
+        check_arithmetic::<Packed<fp31::Fp, 8>>();
+        check_arithmetic::<Packed<fp31::Fp, 16>>();
+        check_slices_and_interleave::<Packed<fp31::Fp, 16>>();
+    }
+
+    #[test]
+    fn avx2_backends() {
+        check_arithmetic::<PackedFp64x4>();
+        check_slices_and_interleave::<PackedFp64x4>();
+        check_arithmetic::<PackedFp31x8>();
+        check_slices_and_interleave::<PackedFp31x8>();
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn avx2_detection_matches_std() {
+        extern crate std;
+
+        let expected = std::is_x86_feature_detected!("avx2");
+        assert_eq!(super::has_avx2(), expected);
+        // the cached answer
+        assert_eq!(super::has_avx2(), expected);
+    }
+
+    #[test]
+    fn interleave_example() {
+        let a = Packed::<fp64::Fp, 4>::from_fn(|i| fp64::Fp::from(i as u64));
+        let b = Packed::<fp64::Fp, 4>::from_fn(|i| fp64::Fp::from(i as u64 + 4));
+        let (res0, res1) = a.interleave(&b, 1);
+        assert_eq!(res0.to_array(), [0u64, 4, 2, 6].map(fp64::Fp::from));
+        assert_eq!(res1.to_array(), [1u64, 5, 3, 7].map(fp64::Fp::from));
+    }
+}
@@ -0,0 +1,750 @@
+//! An implementation of the degree-4 extension of the Mersenne-31 field built
+//! as the quadratic extension `CM31[u]/(u^2 - 2 - i)` of [`crate::cm31::Fp2`].
+//!
+//! The field has `p^4 ≈ 2^124` elements which gives enough soundness for
+//! drawing verifier challenges in STARKs over Mersenne-31 (such as
+//! Circle-STARKs). Elements are represented as `c0 + c1 * u` with `c0, c1` in
+//! CM31. Implementation details:
+//! * Multiplication by the non-residue `2 + i` only needs additions.
+//! * Multiplying by an element of `Fp` or `CM31` avoids a full extension
+//!   multiplication.
+//! * Inversion reduces to an inversion in CM31 (and in turn `Fp`) via the norm.
+
+use crate::{cm31::Fp2, fp31::Fp};
+use ark_ff::{Field, LegendreSymbol, One, SqrtPrecomputation, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
+    CanonicalSerializeWithFlags, Compress, EmptyFlags, Flags, SerializationError, Valid, Validate,
+};
+use core::{
+    fmt::{Display, Formatter},
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// The quadratic non-residue `2 + i` in CM31 that defines the extension
+const NONRESIDUE: Fp2 = Fp2::new(Fp(2), Fp(1));
+
+/// `NONRESIDUE^((p - 1) / 2)` i.e. the coefficient `u^(p - 1)` used by the
+/// Frobenius map
+const FROBENIUS_COEFF: Fp2 = Fp2::new(Fp(21_189_756), Fp(42_379_512));
+
+/// Multiplies an element of CM31 by the non-residue `2 + i` using
+/// `(2 + i)(a + b*i) = (2a - b) + (a + 2b)*i`.
+#[inline]
+fn mul_by_nonresidue(a: Fp2) -> Fp2 {
+    Fp2::new(a.c0.double() - a.c1, a.c0 + a.c1.double())
+}
+
+/// An element `c0 + c1 * u` of the degree-4 extension of the Mersenne-31 field
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+pub struct Fp4 {
+    pub c0: Fp2,
+    pub c1: Fp2,
+}
+
+impl Fp4 {
+    /// Creates the element `c0 + c1 * u`.
+    #[must_use]
+    pub const fn new(c0: Fp2, c1: Fp2) -> Self {
+        Self { c0, c1 }
+    }
+
+    /// Returns the norm `c0^2 - (2 + i) * c1^2` in CM31 which is the product of
+    /// an element and its conjugate `c0 - c1 * u`.
+    #[must_use]
+    pub fn norm(&self) -> Fp2 {
+        self.c0.square() - mul_by_nonresidue(self.c1.square())
+    }
+
+    /// Multiplies `self` by an element of CM31.
+    pub fn mul_assign_by_base_field(&mut self, element: &Fp2) {
+        self.c0 *= element;
+        self.c1 *= element;
+    }
+
+    /// Multiplies `self` by an element of the Mersenne-31 field. This only
+    /// needs four base field multiplications.
+    pub fn mul_assign_by_base_prime_field(&mut self, element: &Fp) {
+        self.c0.mul_assign_by_base_field(element);
+        self.c1.mul_assign_by_base_field(element);
+    }
+}
+
+impl Field for Fp4 {
+    type BasePrimeField = Fp;
+    type BasePrimeFieldIter = core::array::IntoIter<Fp, 4>;
+
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<Self>> = None;
+
+    const ZERO: Self = Self::new(Fp2::ZERO, Fp2::ZERO);
+
+    const ONE: Self = Self::new(Fp2::ONE, Fp2::ZERO);
+
+    fn extension_degree() -> u64 {
+        4
+    }
+
+    fn from_base_prime_field(elem: Self::BasePrimeField) -> Self {
+        Self::new(Fp2::from_base_prime_field(elem), Fp2::ZERO)
+    }
+
+    fn to_base_prime_field_elements(&self) -> Self::BasePrimeFieldIter {
+        [self.c0.c0, self.c0.c1, self.c1.c0, self.c1.c1].into_iter()
+    }
+
+    fn from_base_prime_field_elems(elems: &[Self::BasePrimeField]) -> Option<Self> {
+        if elems.len() != usize::try_from(Self::extension_degree()).unwrap() {
+            return None;
+        }
+        Some(Self::new(
+            Fp2::new(elems[0], elems[1]),
+            Fp2::new(elems[2], elems[3]),
+        ))
+    }
+
+    #[inline]
+    fn double(&self) -> Self {
+        let mut temp = *self;
+        temp.double_in_place();
+        temp
+    }
+
+    #[inline]
+    fn double_in_place(&mut self) -> &mut Self {
+        self.c0.double_in_place();
+        self.c1.double_in_place();
+        self
+    }
+
+    #[inline]
+    fn neg_in_place(&mut self) -> &mut Self {
+        self.c0.neg_in_place();
+        self.c1.neg_in_place();
+        self
+    }
+
+    #[inline]
+    fn from_random_bytes_with_flags<F: Flags>(bytes: &[u8]) -> Option<(Self, F)> {
+        let split_at = bytes.len() / 2;
+        let c0 = Fp2::from_random_bytes(&bytes[..split_at])?;
+        let (c1, flags) = Fp2::from_random_bytes_with_flags(&bytes[split_at..])?;
+        Some((Self::new(c0, c1), flags))
+    }
+
+    #[inline]
+    fn square(&self) -> Self {
+        let mut temp = *self;
+        temp.square_in_place();
+        temp
+    }
+
+    /// Complex squaring: with `v0 = c0 * c1` we have `(c0 + c1*u)^2 =
+    /// ((c0 + c1)(c0 + R*c1) - v0 - R*v0) + 2*v0*u` where `R = 2 + i`.
+    #[inline]
+    fn square_in_place(&mut self) -> &mut Self {
+        let v0 = self.c0 * self.c1;
+        let r_v0 = mul_by_nonresidue(v0);
+        self.c0 = (self.c0 + self.c1) * (self.c0 + mul_by_nonresidue(self.c1)) - v0 - r_v0;
+        self.c1 = v0.double();
+        self
+    }
+
+    #[inline]
+    fn inverse(&self) -> Option<Self> {
+        // 1/(c0 + c1*u) = (c0 - c1*u)/(c0^2 - (2 + i)*c1^2)
+        self.norm().inverse().map(|norm_inv| {
+            let mut res = Self::new(self.c0, -self.c1);
+            res.mul_assign_by_base_field(&norm_inv);
+            res
+        })
+    }
+
+    fn inverse_in_place(&mut self) -> Option<&mut Self> {
+        self.inverse().map(|inverse| {
+            *self = inverse;
+            self
+        })
+    }
+
+    /// The Frobenius map conjugates the CM31 coefficients and maps `u` to
+    /// `u^p = (2 + i)^((p - 1)/2) * u`. Applying it twice maps `u` to `-u`.
+    #[inline]
+    fn frobenius_map_in_place(&mut self, power: usize) {
+        if power % 2 == 1 {
+            self.c0.conjugate_in_place();
+            self.c1.conjugate_in_place();
+            self.c1 *= FROBENIUS_COEFF;
+        }
+        if power % 4 >= 2 {
+            self.c1.neg_in_place();
+        }
+    }
+
+    /// An element is a square in the extension iff its norm is a square in
+    /// CM31.
+    #[inline]
+    fn legendre(&self) -> LegendreSymbol {
+        self.norm().legendre()
+    }
+
+    /// Square root based on the complex method. See Algorithm 8 in
+    /// <https://eprint.iacr.org/2012/685.pdf>.
+    fn sqrt(&self) -> Option<Self> {
+        if self.c1.is_zero() {
+            // Either `c0` or `c0 / (2 + i)` has a root in CM31.
+            return self.c0.sqrt().map_or_else(
+                || {
+                    (self.c0 / NONRESIDUE)
+                        .sqrt()
+                        .map(|c1| Self::new(Fp2::ZERO, c1))
+                },
+                |c0| Some(Self::new(c0, Fp2::ZERO)),
+            );
+        }
+        let alpha = self.norm().sqrt()?;
+        // `1/2 = (p + 1)/2 = 2^30`
+        let two_inv = Fp(1 << 30);
+        let mut delta = alpha + self.c0;
+        delta.mul_assign_by_base_field(&two_inv);
+        if delta.legendre().is_qnr() {
+            delta -= &alpha;
+        }
+        let c0 = delta.sqrt()?;
+        let c1 = self.c1 * c0.double().inverse()?;
+        Some(Self::new(c0, c1))
+    }
+}
+
+impl zeroize::Zeroize for Fp4 {
+    fn zeroize(&mut self) {
+        self.c0.zeroize();
+        self.c1.zeroize();
+    }
+}
+
+impl Zero for Fp4 {
+    #[inline]
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+}
+
+impl One for Fp4 {
+    #[inline]
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    #[inline]
+    fn is_one(&self) -> bool {
+        self.c0.is_one() && self.c1.is_zero()
+    }
+}
+
+/// `Fp4` elements are ordered lexicographically.
+impl Ord for Fp4 {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.c1.cmp(&other.c1).then_with(|| self.c0.cmp(&other.c0))
+    }
+}
+
+impl PartialOrd for Fp4 {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<Fp2> for Fp4 {
+    fn from(other: Fp2) -> Self {
+        Self::new(other, Fp2::ZERO)
+    }
+}
+
+impl From<Fp> for Fp4 {
+    fn from(other: Fp) -> Self {
+        Self::from_base_prime_field(other)
+    }
+}
+
+impl From<u128> for Fp4 {
+    fn from(other: u128) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i128> for Fp4 {
+    fn from(other: i128) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<bool> for Fp4 {
+    fn from(other: bool) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u64> for Fp4 {
+    fn from(other: u64) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i64> for Fp4 {
+    fn from(other: i64) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u32> for Fp4 {
+    fn from(other: u32) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i32> for Fp4 {
+    fn from(other: i32) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u16> for Fp4 {
+    fn from(other: u16) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i16> for Fp4 {
+    fn from(other: i16) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<u8> for Fp4 {
+    fn from(other: u8) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl From<i8> for Fp4 {
+    fn from(other: i8) -> Self {
+        Fp::from(other).into()
+    }
+}
+
+impl ark_std::rand::distributions::Distribution<Fp4> for ark_std::rand::distributions::Standard {
+    #[inline]
+    fn sample<R: ark_std::rand::Rng + ?Sized>(&self, rng: &mut R) -> Fp4 {
+        Fp4::new(rng.gen(), rng.gen())
+    }
+}
+
+impl CanonicalSerializeWithFlags for Fp4 {
+    fn serialize_with_flags<W: ark_std::io::Write, F: Flags>(
+        &self,
+        mut writer: W,
+        flags: F,
+    ) -> Result<(), SerializationError> {
+        self.c0.serialize_compressed(&mut writer)?;
+        self.c1.serialize_with_flags(&mut writer, flags)?;
+        Ok(())
+    }
+
+    fn serialized_size_with_flags<F: Flags>(&self) -> usize {
+        self.c0.compressed_size() + self.c1.serialized_size_with_flags::<F>()
+    }
+}
+
+impl CanonicalSerialize for Fp4 {
+    #[inline]
+    fn serialize_with_mode<W: ark_std::io::Write>(
+        &self,
+        writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.serialize_with_flags(writer, EmptyFlags)
+    }
+
+    #[inline]
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        self.serialized_size_with_flags::<EmptyFlags>()
+    }
+}
+
+impl CanonicalDeserializeWithFlags for Fp4 {
+    fn deserialize_with_flags<R: ark_std::io::Read, F: Flags>(
+        mut reader: R,
+    ) -> Result<(Self, F), SerializationError> {
+        let c0 = Fp2::deserialize_compressed(&mut reader)?;
+        let (c1, flags) = Fp2::deserialize_with_flags(&mut reader)?;
+        Ok((Self::new(c0, c1), flags))
+    }
+}
+
+impl Valid for Fp4 {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.c0.check()?;
+        self.c1.check()
+    }
+}
+
+impl CanonicalDeserialize for Fp4 {
+    fn deserialize_with_mode<R: ark_std::io::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let c0 = Fp2::deserialize_with_mode(&mut reader, compress, validate)?;
+        let c1 = Fp2::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self::new(c0, c1))
+    }
+}
+
+/// Outputs a string of the form `(c0) + (c1) * u`.
+impl Display for Fp4 {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "({}) + ({}) * u", self.c0, self.c1)
+    }
+}
+
+impl Neg for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(mut self) -> Self {
+        self.neg_in_place();
+        self
+    }
+}
+
+impl AddAssign<&Self> for Fp4 {
+    #[inline]
+    fn add_assign(&mut self, other: &Self) {
+        self.c0 += other.c0;
+        self.c1 += other.c1;
+    }
+}
+
+impl SubAssign<&Self> for Fp4 {
+    #[inline]
+    fn sub_assign(&mut self, other: &Self) {
+        self.c0 -= other.c0;
+        self.c1 -= other.c1;
+    }
+}
+
+impl MulAssign<&Self> for Fp4 {
+    /// Karatsuba multiplication using the fact that `u^2 = 2 + i`.
+    #[inline]
+    fn mul_assign(&mut self, other: &Self) {
+        let v0 = self.c0 * other.c0;
+        let v1 = self.c1 * other.c1;
+        self.c1 = (self.c0 + self.c1) * (other.c0 + other.c1) - v0 - v1;
+        self.c0 = v0 + mul_by_nonresidue(v1);
+    }
+}
+
+/// Computes `self *= other.inverse()` if `other.inverse()` is `Some`, and
+/// panics otherwise.
+impl DivAssign<&Self> for Fp4 {
+    #[inline]
+    fn div_assign(&mut self, other: &Self) {
+        self.mul_assign(&other.inverse().unwrap());
+    }
+}
+
+impl Add<&Self> for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: &Self) -> Self {
+        self.add_assign(other);
+        self
+    }
+}
+
+impl Sub<&Self> for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: &Self) -> Self {
+        self.sub_assign(other);
+        self
+    }
+}
+
+impl Mul<&Self> for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: &Self) -> Self {
+        self.mul_assign(other);
+        self
+    }
+}
+
+impl Div<&Self> for Fp4 {
+    type Output = Self;
+
+    /// Returns `self * other.inverse()` if `other.inverse()` is `Some`, and
+    /// panics otherwise.
+    #[inline]
+    fn div(mut self, other: &Self) -> Self {
+        self.div_assign(other);
+        self
+    }
+}
+
+impl Add<&Fp4> for &Fp4 {
+    type Output = Fp4;
+
+    #[inline]
+    fn add(self, other: &Fp4) -> Fp4 {
+        *self + other
+    }
+}
+
+impl Sub<&Fp4> for &Fp4 {
+    type Output = Fp4;
+
+    #[inline]
+    fn sub(self, other: &Fp4) -> Fp4 {
+        *self - other
+    }
+}
+
+impl Mul<&Fp4> for &Fp4 {
+    type Output = Fp4;
+
+    #[inline]
+    fn mul(self, other: &Fp4) -> Fp4 {
+        *self * other
+    }
+}
+
+impl Div<&Fp4> for &Fp4 {
+    type Output = Fp4;
+
+    #[inline]
+    fn div(self, other: &Fp4) -> Fp4 {
+        *self / other
+    }
+}
+
+impl AddAssign<&mut Self> for Fp4 {
+    #[inline]
+    fn add_assign(&mut self, other: &mut Self) {
+        self.add_assign(&*other);
+    }
+}
+
+impl SubAssign<&mut Self> for Fp4 {
+    #[inline]
+    fn sub_assign(&mut self, other: &mut Self) {
+        self.sub_assign(&*other);
+    }
+}
+
+impl MulAssign<&mut Self> for Fp4 {
+    #[inline]
+    fn mul_assign(&mut self, other: &mut Self) {
+        self.mul_assign(&*other);
+    }
+}
+
+impl DivAssign<&mut Self> for Fp4 {
+    #[inline]
+    fn div_assign(&mut self, other: &mut Self) {
+        self.div_assign(&*other);
+    }
+}
+
+impl AddAssign<Self> for Fp4 {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        self.add_assign(&other);
+    }
+}
+
+impl SubAssign<Self> for Fp4 {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.sub_assign(&other);
+    }
+}
+
+impl MulAssign<Self> for Fp4 {
+    #[inline]
+    fn mul_assign(&mut self, other: Self) {
+        self.mul_assign(&other);
+    }
+}
+
+impl DivAssign<Self> for Fp4 {
+    #[inline]
+    fn div_assign(&mut self, other: Self) {
+        self.div_assign(&other);
+    }
+}
+
+impl Add<Self> for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: Self) -> Self {
+        self.add_assign(&other);
+        self
+    }
+}
+
+impl Sub<Self> for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: Self) -> Self {
+        self.sub_assign(&other);
+        self
+    }
+}
+
+impl Mul<Self> for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: Self) -> Self {
+        self.mul_assign(&other);
+        self
+    }
+}
+
+impl Div<Self> for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn div(mut self, other: Self) -> Self {
+        self.div_assign(&other);
+        self
+    }
+}
+
+impl Add<&mut Self> for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, other: &mut Self) -> Self {
+        self.add_assign(&*other);
+        self
+    }
+}
+
+impl Sub<&mut Self> for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, other: &mut Self) -> Self {
+        self.sub_assign(&*other);
+        self
+    }
+}
+
+impl Mul<&mut Self> for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(mut self, other: &mut Self) -> Self {
+        self.mul_assign(&*other);
+        self
+    }
+}
+
+impl Div<&mut Self> for Fp4 {
+    type Output = Self;
+
+    #[inline]
+    fn div(mut self, other: &mut Self) -> Self {
+        self.div_assign(&*other);
+        self
+    }
+}
+
+impl Product<Self> for Fp4 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), core::ops::Mul::mul)
+    }
+}
+
+impl<'a> Product<&'a Self> for Fp4 {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+impl Sum<Self> for Fp4 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), core::ops::Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Self> for Fp4 {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), core::ops::Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fp4 as TestField;
+    use crate::{cm31::Fp2, fp31::Fp};
+    use ark_algebra_test_templates::test_field;
+    use ark_ff::Field;
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    test_field!(generated; TestField);
+
+    #[test]
+    fn mul_by_base_fields_matches_mul() {
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            let a = TestField::rand(&mut rng);
+            let b = Fp::rand(&mut rng);
+            let c = Fp2::rand(&mut rng);
+
+            let mut res = a;
+            res.mul_assign_by_base_prime_field(&b);
+            assert_eq!(res, a * TestField::from(b));
+
+            let mut res = a;
+            res.mul_assign_by_base_field(&c);
+            assert_eq!(res, a * TestField::from(c));
+        }
+    }
+
+    #[test]
+    fn base_prime_field_elements_round_trip() {
+        let mut rng = test_rng();
+        for _ in 0..1000 {
+            let a = TestField::rand(&mut rng);
+            let elems = a.to_base_prime_field_elements().collect::<Vec<Fp>>();
+            assert_eq!(TestField::from_base_prime_field_elems(&elems), Some(a));
+        }
+    }
+
+    #[test]
+    fn sqrt_of_squares() {
+        let mut rng = test_rng();
+        for _ in 0..100 {
+            let a = TestField::rand(&mut rng);
+            let root = a.square().sqrt().unwrap();
+            assert!(root == a || root == -a);
+        }
+    }
+}
@@ -0,0 +1,468 @@
+//! Schnorr signatures over the `EcGFp5` group.
+//!
+//! A signature is the pair `(s, e)` where `e` is the challenge. This form
+//! lets a verifier recompute the commitment `R = s*G + e*Q` and compare a
+//! single hash, which is cheap to check inside a STARK over the 64-bit field:
+//! * Keys and commitments are encoded as `GF(p^5)` elements i.e. 5 elements
+//!   of [`Fp`].
+//! * The challenge is derived from the [`CHALLENGE_LEN`] output elements
+//!   `h_0, ..., h_9` of a hash `H(w(R) || w(Q) || m)` over [`Fp`] elements as
+//!   `e = (h_0 + h_1 * 2^64 + ... + h_9 * 2^576) mod n` where each `h_i` is
+//!   taken in `[0, p)`. The digits are below `p` rather than `2^64`, so the
+//!   640-bit integer isn't uniform and `e` is only guaranteed to be within
+//!   statistical distance `10 * 2^-32 + 2^-320` of uniform modulo the 319-bit
+//!   group order `n`. Its min-entropy is nonetheless close to full: at most
+//!   `2^322` integers reduce to the same `e` and each has probability
+//!   `p^-10 < 2^-639`, so no challenge is more likely than `2^-317`. A circuit
+//!   checks the reduction by witnessing a quotient `q < 2^322` and checking
+//!   `sum_i h_i * 2^(64i) = q * n + e` with `e < n`.
+//!
+//! The hash is a parameter so native and in-circuit verification can use the
+//! same algebraic hash. [`Poseidon2`] with width 12 implements it as a sponge
+//! which squeezes two blocks of 8 elements and keeps the first 10.
+
+use crate::{
+    ecgfp5::{Point, Scalar},
+    fp64::{
+        ext5::Fp5,
+        poseidon2::{Poseidon2, Poseidon2Sponge},
+        Fp,
+    },
+};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
+use ark_std::{
+    fmt::{self, Debug, Formatter},
+    rand::Rng,
+    vec::Vec,
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Number of hash output elements the challenge is reduced from
+pub const CHALLENGE_LEN: usize = 10;
+
+/// A hash function from [`Fp`] elements to [`CHALLENGE_LEN`] [`Fp`] elements
+/// used to derive the challenge
+pub trait ChallengeHasher {
+    fn hash(elements: &[Fp]) -> [Fp; CHALLENGE_LEN];
+}
+
+impl ChallengeHasher for Poseidon2<12> {
+    fn hash(elements: &[Fp]) -> [Fp; CHALLENGE_LEN] {
+        let mut sponge = Poseidon2Sponge::new(Self::new());
+        sponge.absorb(elements);
+        let mut output = [Fp::ZERO; CHALLENGE_LEN];
+        sponge.finalize_into(&mut output);
+        output
+    }
+}
+
+/// Derives the challenge `e = H(w(R) || w(Q) || m) mod n` as described in the
+/// [module documentation](self).
+fn challenge<H: ChallengeHasher>(commitment: &Point, public_key: &Point, message: &[Fp]) -> Scalar {
+    let mut elements = Vec::with_capacity(10 + message.len());
+    elements.extend(commitment.encode().to_base_prime_field_elements());
+    elements.extend(public_key.encode().to_base_prime_field_elements());
+    elements.extend_from_slice(message);
+    let mut bytes = [0u8; 8 * CHALLENGE_LEN];
+    for (chunk, element) in bytes.chunks_exact_mut(8).zip(H::hash(&elements)) {
+        chunk.copy_from_slice(&element.into_bigint().0[0].to_le_bytes());
+    }
+    Scalar::from_le_bytes_mod_order(&bytes)
+}
+
+/// A secret key `x` together with its public key. It is zeroized on drop and
+/// its `Debug` output omits the secret. It doesn't implement `PartialEq` since
+/// comparing the secret limbs would leak them through timing; compare the
+/// [verifying keys](Self::verifying_key) instead.
+#[derive(Clone)]
+pub struct SigningKey {
+    secret: Scalar,
+    verifying_key: VerifyingKey,
+}
+
+impl SigningKey {
+    /// Generates a random non-zero secret key.
+    pub fn generate<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        loop {
+            if let Some(key) = Self::new(Scalar::rand(rng)) {
+                return key;
+            }
+        }
+    }
+
+    /// Creates a secret key from a non-zero scalar.
+    #[must_use]
+    pub fn new(secret: Scalar) -> Option<Self> {
+        (!secret.is_zero()).then(|| Self {
+            secret,
+            verifying_key: VerifyingKey(Point::mul_generator(&secret)),
+        })
+    }
+
+    /// Returns the public key `Q = x*G`.
+    #[must_use]
+    pub const fn verifying_key(&self) -> VerifyingKey {
+        self.verifying_key
+    }
+
+    /// Signs `message` with a random nonce `k` as `s = k - e*x` where `e` is the
+    /// challenge for the commitment `R = k*G`. The commitment is computed in
+    /// constant time.
+    pub fn sign<H: ChallengeHasher, R: Rng + ?Sized>(
+        &self,
+        message: &[Fp],
+        rng: &mut R,
+    ) -> Signature {
+        let mut nonce = Scalar::rand(rng);
+        let commitment = Point::mul_generator(&nonce);
+        let e = challenge::<H>(&commitment, &self.verifying_key.0, message);
+        let s = nonce - e * self.secret;
+        nonce.zeroize();
+        Signature { s, e }
+    }
+}
+
+impl Debug for SigningKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("SigningKey(..)")
+    }
+}
+
+impl Zeroize for SigningKey {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl Drop for SigningKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SigningKey {}
+
+/// A public key `Q = x*G`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyingKey(Point);
+
+impl VerifyingKey {
+    /// Creates a public key from a point. Returns `None` for the neutral
+    /// element.
+    #[must_use]
+    pub fn new(point: Point) -> Option<Self> {
+        (!point.is_zero()).then_some(Self(point))
+    }
+
+    /// Returns the encoding `w(Q)` of the public key.
+    #[must_use]
+    pub fn encode(&self) -> Fp5 {
+        self.0.encode()
+    }
+
+    /// Decodes a public key. Returns `None` if `w` does not encode a group
+    /// element or encodes the neutral element.
+    #[must_use]
+    pub fn decode(w: &Fp5) -> Option<Self> {
+        Point::decode(w).and_then(Self::new)
+    }
+
+    /// Returns `true` if `signature` is valid for `message` i.e. the
+    /// commitment `R = s*G + e*Q` hashes to the challenge `e`.
+    #[must_use]
+    pub fn verify<H: ChallengeHasher>(&self, message: &[Fp], signature: &Signature) -> bool {
+        let commitment = Point::GENERATOR * signature.s + self.0 * signature.e;
+        challenge::<H>(&commitment, &self.0, message) == signature.e
+    }
+}
+
+/// A signature `(s, e)`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub s: Scalar,
+    pub e: Scalar,
+}
+
+impl CanonicalSerialize for SigningKey {
+    fn serialize_with_mode<W: ark_std::io::Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.secret.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.secret.serialized_size(compress)
+    }
+}
+
+impl Valid for SigningKey {
+    fn check(&self) -> Result<(), SerializationError> {
+        if self.secret.is_zero() {
+            Err(SerializationError::InvalidData)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl CanonicalDeserialize for SigningKey {
+    fn deserialize_with_mode<R: ark_std::io::Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let secret = Scalar::deserialize_with_mode(reader, compress, validate)?;
+        Self::new(secret).ok_or(SerializationError::InvalidData)
+    }
+}
+
+impl CanonicalSerialize for VerifyingKey {
+    fn serialize_with_mode<W: ark_std::io::Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+impl Valid for VerifyingKey {
+    fn check(&self) -> Result<(), SerializationError> {
+        if self.0.is_zero() {
+            Err(SerializationError::InvalidData)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl CanonicalDeserialize for VerifyingKey {
+    fn deserialize_with_mode<R: ark_std::io::Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let point = Point::deserialize_with_mode(reader, compress, validate)?;
+        Self::new(point).ok_or(SerializationError::InvalidData)
+    }
+}
+
+impl CanonicalSerialize for Signature {
+    fn serialize_with_mode<W: ark_std::io::Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.s.serialize_with_mode(&mut writer, compress)?;
+        self.e.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.s.serialized_size(compress) + self.e.serialized_size(compress)
+    }
+}
+
+impl Valid for Signature {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for Signature {
+    fn deserialize_with_mode<R: ark_std::io::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let s = Scalar::deserialize_with_mode(&mut reader, compress, validate)?;
+        let e = Scalar::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self { s, e })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{challenge, ChallengeHasher, Signature, SigningKey, VerifyingKey, CHALLENGE_LEN};
+    use crate::{
+        ecgfp5::{Point, Scalar},
+        fp64::{ext5::Fp5, poseidon2::Poseidon2, Fp},
+    };
+    use ark_ff::{Field, MontFp, One, Zero};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::{format, test_rng, vec::Vec, UniformRand};
+    use zeroize::Zeroize;
+
+    /// A toy hash which evaluates the input as a polynomial in `GF(p^5)` and
+    /// raises the result to the 7th power. It is only used for testing.
+    struct ToyHasher;
+
+    impl ChallengeHasher for ToyHasher {
+        fn hash(elements: &[Fp]) -> [Fp; CHALLENGE_LEN] {
+            let alpha = Fp5::new(
+                Fp::from(3u8),
+                Fp::from(1u8),
+                Fp::from(4u8),
+                Fp::from(1u8),
+                Fp::from(5u8),
+            );
+            let acc = elements.iter().fold(Fp5::one(), |acc, &element| {
+                (acc * alpha + Fp5::from(element)).pow([7])
+            });
+            let square = acc.square();
+            [
+                acc.c0, acc.c1, acc.c2, acc.c3, acc.c4, square.c0, square.c1, square.c2, square.c3,
+                square.c4,
+            ]
+        }
+    }
+
+    fn random_message(len: usize) -> Vec<Fp> {
+        let mut rng = test_rng();
+        (0..len).map(|_| Fp::rand(&mut rng)).collect()
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let mut rng = test_rng();
+        for len in 0..8 {
+            let message = random_message(len);
+            let signing_key = SigningKey::generate(&mut rng);
+            let verifying_key = signing_key.verifying_key();
+            let signature = signing_key.sign::<ToyHasher, _>(&message, &mut rng);
+            assert!(verifying_key.verify::<ToyHasher>(&message, &signature));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        let mut rng = test_rng();
+        let message = random_message(4);
+        let signing_key = SigningKey::generate(&mut rng);
+        let verifying_key = signing_key.verifying_key();
+        let signature = signing_key.sign::<ToyHasher, _>(&message, &mut rng);
+
+        let mut other_message = message.clone();
+        other_message[0] += Fp::one();
+        assert!(!verifying_key.verify::<ToyHasher>(&other_message, &signature));
+
+        let other_key = SigningKey::generate(&mut rng).verifying_key();
+        assert!(!other_key.verify::<ToyHasher>(&message, &signature));
+
+        let mut forged = signature;
+        forged.s += Scalar::one();
+        assert!(!verifying_key.verify::<ToyHasher>(&message, &forged));
+    }
+
+    #[test]
+    fn encoding_round_trip() {
+        let mut rng = test_rng();
+        let message = random_message(3);
+        let signing_key = SigningKey::generate(&mut rng);
+        let verifying_key = signing_key.verifying_key();
+        let signature = signing_key.sign::<ToyHasher, _>(&message, &mut rng);
+
+        let mut bytes = Vec::new();
+        signing_key.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 40);
+        assert_eq!(
+            SigningKey::deserialize_compressed(&*bytes)
+                .unwrap()
+                .verifying_key(),
+            signing_key.verifying_key()
+        );
+
+        let mut bytes = Vec::new();
+        verifying_key.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 40);
+        assert_eq!(
+            VerifyingKey::deserialize_compressed(&*bytes).unwrap(),
+            verifying_key
+        );
+        assert_eq!(
+            VerifyingKey::decode(&verifying_key.encode()),
+            Some(verifying_key)
+        );
+
+        let mut bytes = Vec::new();
+        signature.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 80);
+        assert_eq!(
+            Signature::deserialize_compressed(&*bytes).unwrap(),
+            signature
+        );
+    }
+
+    #[test]
+    fn poseidon2_challenge_hash_known_answer() {
+        let input = [0u64, 1, 2, 3, 4].map(Fp::from);
+        let hash = <Poseidon2<12> as ChallengeHasher>::hash(&input);
+        let expected = [
+            3_822_540_339_956_317_889,
+            8_799_233_511_501_863_653,
+            16_842_770_992_215_049_719,
+            14_633_955_123_227_739_972,
+            17_964_935_901_589_221_966,
+            2_224_244_198_264_080_004,
+            5_572_187_443_430_101_453,
+            13_844_496_316_678_390_478,
+            18_213_736_308_340_431_363,
+            1_349_433_862_100_866_505u64,
+        ];
+        assert_eq!(hash, expected.map(Fp::from));
+        // the first elements are the sponge digest
+        assert_eq!(hash[..4], Poseidon2::<12>::new().hash(&input));
+    }
+
+    #[test]
+    fn poseidon2_signature_known_answer() {
+        let signing_key = SigningKey::new(Scalar::from(0x1234_5678u64)).unwrap();
+        let verifying_key = signing_key.verifying_key();
+        let expected_key = Fp5::new(
+            Fp::from(13_051_893_740_584_428_939u64),
+            Fp::from(5_313_775_542_641_192_082u64),
+            Fp::from(14_579_692_003_387_861_413u64),
+            Fp::from(13_693_755_782_295_947_828u64),
+            Fp::from(14_683_167_762_977_399_167u64),
+        );
+        assert_eq!(verifying_key.encode(), expected_key);
+
+        let message = [1u64, 2, 3].map(Fp::from);
+        let e: Scalar = MontFp!("936903736232940616808726230640542634216962842537024006637685935598040103196204744759381413943726");
+        assert_eq!(
+            challenge::<Poseidon2<12>>(&Point::GENERATOR, &verifying_key.0, &message),
+            e
+        );
+
+        let signature = Signature {
+            s: MontFp!("991644722906197936282179671849535093052358473121911798451054786475819917222608526217775263783966"),
+            e: MontFp!("442482265215354280844165590287867036508190955544134390624534235915756082077345214035127585177932"),
+        };
+        assert!(verifying_key.verify::<Poseidon2<12>>(&message, &signature));
+        assert!(!verifying_key.verify::<Poseidon2<12>>(&message[..2], &signature));
+    }
+
+    #[test]
+    fn signing_key_hides_secret() {
+        let mut signing_key = SigningKey::new(Scalar::from(1234u64)).unwrap();
+        assert_eq!(format!("{signing_key:?}"), "SigningKey(..)");
+        signing_key.zeroize();
+        assert!(signing_key.secret.is_zero());
+    }
+
+    #[test]
+    fn rejects_neutral_public_key() {
+        assert!(VerifyingKey::decode(&Fp5::ZERO).is_none());
+        let bytes = [0u8; 40];
+        assert!(VerifyingKey::deserialize_compressed(&bytes[..]).is_err());
+        assert!(SigningKey::deserialize_compressed(&bytes[..]).is_err());
+    }
+}
@@ -0,0 +1,439 @@
+//! A Fiat-Shamir transcript built on a duplex sponge over a prime field.
+//!
+//! Absorbed elements are buffered and overwrite the rate portion of the state
+//! once it is full or before the next squeeze. The rest of the rate is zeroed
+//! and the number of absorbed elements is added to the first capacity element,
+//! so inputs that differ only in length are separated. Squeezed elements are
+//! read from the rate portion after a permutation, and absorbing again
+//! discards any squeezed elements that were not used. The transcript is
+//! generic over a [`DuplexPermutation`], which is implemented for Poseidon2
+//! over both [`fp31::Fp`] and [`fp64::Fp`].
+//!
+//! Challenges are derived from squeezed elements by [`TranscriptField`]:
+//! * An [`fp64::Fp`] challenge reduces two squeezed elements as the 128-bit
+//!   integer `a + 2^64 * b` modulo `p`, so challenges are statistically close
+//!   to uniform without rejection sampling.
+//! * An [`fp31::Fp`] challenge is a single squeezed element. The state can
+//!   hold either encoding of zero so the challenge is converted to the
+//!   canonical encoding, and integers are read from canonical values.
+//!
+//! Extension field elements are absorbed and squeezed as their coefficients
+//! over the base field. Bytes are packed into elements that are always below
+//! `p`, and the number of bytes is absorbed first so inputs of different
+//! lengths can't collide.
+
+use crate::{fp31, fp64};
+use ark_ff::{Field, PrimeField};
+use ark_std::vec::Vec;
+
+/// A permutation with a rate used by [`Transcript`]
+pub trait DuplexPermutation<const WIDTH: usize> {
+    type Field: TranscriptField;
+
+    /// Number of elements absorbed or squeezed per permutation. The remaining
+    /// `WIDTH - RATE` elements are the capacity.
+    const RATE: usize;
+
+    fn permute(&self, state: &mut [Self::Field; WIDTH]);
+}
+
+impl<const WIDTH: usize> DuplexPermutation<WIDTH> for fp31::poseidon2::Poseidon2<WIDTH> {
+    type Field = fp31::Fp;
+
+    const RATE: usize = WIDTH - fp31::poseidon2::DIGEST_LEN;
+
+    fn permute(&self, state: &mut [fp31::Fp; WIDTH]) {
+        self.permute(state);
+    }
+}
+
+impl<const WIDTH: usize> DuplexPermutation<WIDTH> for fp64::poseidon2::Poseidon2<WIDTH> {
+    type Field = fp64::Fp;
+
+    const RATE: usize = WIDTH - fp64::poseidon2::DIGEST_LEN;
+
+    fn permute(&self, state: &mut [fp64::Fp; WIDTH]) {
+        self.permute(state);
+    }
+}
+
+/// A prime field that challenges can be derived in
+pub trait TranscriptField: PrimeField {
+    /// Number of squeezed elements used for a challenge, at most 2
+    const ELEMENTS_PER_CHALLENGE: usize;
+
+    /// Derives a challenge from [`Self::ELEMENTS_PER_CHALLENGE`] squeezed
+    /// elements.
+    fn challenge(elements: &[Self]) -> Self;
+}
+
+impl TranscriptField for fp64::Fp {
+    const ELEMENTS_PER_CHALLENGE: usize = 2;
+
+    fn challenge(elements: &[Self]) -> Self {
+        let lo = elements[0].into_bigint().0[0];
+        let hi = elements[1].into_bigint().0[0];
+        Self::from(u128::from(lo) | (u128::from(hi) << 64))
+    }
+}
+
+impl TranscriptField for fp31::Fp {
+    const ELEMENTS_PER_CHALLENGE: usize = 1;
+
+    fn challenge(elements: &[Self]) -> Self {
+        // `into_bigint` maps both encodings of zero to 0
+        Self::from(elements[0].into_bigint())
+    }
+}
+
+/// A duplex sponge Fiat-Shamir transcript
+#[derive(Clone, Debug)]
+pub struct Transcript<P: DuplexPermutation<WIDTH>, const WIDTH: usize> {
+    permutation: P,
+    state: [P::Field; WIDTH],
+    /// Absorbed elements that haven't been written into the state
+    input: Vec<P::Field>,
+    /// Elements of the rate that haven't been squeezed, last one first
+    output: Vec<P::Field>,
+}
+
+impl<P: DuplexPermutation<WIDTH>, const WIDTH: usize> Transcript<P, WIDTH> {
+    /// Creates a transcript and absorbs the domain separator `label`.
+    pub fn new(permutation: P, label: &[u8]) -> Self {
+        let mut transcript = Self {
+            permutation,
+            state: [P::Field::ZERO; WIDTH],
+            input: Vec::with_capacity(P::RATE),
+            output: Vec::with_capacity(P::RATE),
+        };
+        transcript.absorb_bytes(label);
+        transcript
+    }
+
+    /// Writes the buffered input into the rate, permutes and refills the
+    /// output buffer. Without input the state is permuted as it is.
+    fn duplex(&mut self) {
+        if !self.input.is_empty() {
+            let len = P::Field::from(self.input.len() as u64);
+            let mut input = self.input.drain(..);
+            for value in &mut self.state[..P::RATE] {
+                *value = input.next().unwrap_or(P::Field::ZERO);
+            }
+            self.state[P::RATE] += len;
+        }
+        self.permutation.permute(&mut self.state);
+        self.output.clear();
+        self.output.extend(self.state[..P::RATE].iter().rev());
+    }
+
+    pub fn absorb(&mut self, element: P::Field) {
+        self.output.clear();
+        self.input.push(element);
+        if self.input.len() == P::RATE {
+            self.duplex();
+        }
+    }
+
+    pub fn absorb_slice(&mut self, elements: &[P::Field]) {
+        for &element in elements {
+            self.absorb(element);
+        }
+    }
+
+    /// Absorbs the coefficients of an extension field element.
+    pub fn absorb_extension<E: Field<BasePrimeField = P::Field>>(&mut self, element: &E) {
+        for coefficient in element.to_base_prime_field_elements() {
+            self.absorb(coefficient);
+        }
+    }
+
+    /// Absorbs the length of `bytes` followed by the bytes packed little-endian
+    /// into elements of `(MODULUS_BIT_SIZE - 1) / 8` bytes, at most 8.
+    pub fn absorb_bytes(&mut self, bytes: &[u8]) {
+        self.absorb(P::Field::from(bytes.len() as u64));
+        let bytes_per_element = (P::Field::MODULUS_BIT_SIZE as usize - 1) / 8;
+        for chunk in bytes.chunks(bytes_per_element.min(8)) {
+            let mut limb = [0; 8];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            self.absorb(P::Field::from(u64::from_le_bytes(limb)));
+        }
+    }
+
+    /// Squeezes a single element of the rate.
+    fn squeeze_element(&mut self) -> P::Field {
+        if !self.input.is_empty() || self.output.is_empty() {
+            self.duplex();
+        }
+        self.output.pop().unwrap()
+    }
+
+    /// Squeezes a uniformly distributed challenge.
+    pub fn squeeze(&mut self) -> P::Field {
+        let mut elements = [P::Field::ZERO; 2];
+        let elements = &mut elements[..P::Field::ELEMENTS_PER_CHALLENGE];
+        for element in elements.iter_mut() {
+            *element = self.squeeze_element();
+        }
+        P::Field::challenge(elements)
+    }
+
+    /// Squeezes an extension field challenge with uniform coefficients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `E` can't be built from [`Field::extension_degree`]
+    /// coefficients.
+    pub fn squeeze_extension<E: Field<BasePrimeField = P::Field>>(&mut self) -> E {
+        let coefficients: Vec<P::Field> =
+            (0..E::extension_degree()).map(|_| self.squeeze()).collect();
+        E::from_base_prime_field_elems(&coefficients).unwrap()
+    }
+
+    /// Squeezes an integer below `2^bits` from the low bits of a canonical
+    /// element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not below the bit size of the modulus and of
+    /// `usize`, since the top bits of an element are not uniform.
+    pub fn squeeze_bits(&mut self, bits: u32) -> usize {
+        assert!(bits < P::Field::MODULUS_BIT_SIZE && bits < usize::BITS);
+        let value = self.squeeze_element().into_bigint().as_ref()[0];
+        // `bits < usize::BITS` so the masked value fits
+        #[allow(clippy::cast_possible_truncation)]
+        let value = (value & ((1 << bits) - 1)) as usize;
+        value
+    }
+
+    /// Finds a proof of work witness for which [`Self::check_witness`]
+    /// succeeds and leaves the transcript in the state the check does. The
+    /// expected number of attempts is `2^bits`.
+    #[must_use]
+    pub fn grind(&mut self, bits: u32) -> P::Field
+    where
+        P: Clone,
+    {
+        let mut witness = P::Field::ZERO;
+        loop {
+            let mut transcript = self.clone();
+            if transcript.check_witness(bits, witness) {
+                *self = transcript;
+                return witness;
+            }
+            witness += P::Field::ONE;
+        }
+    }
+
+    /// Absorbs `witness` and checks that the next `bits` squeezed bits are
+    /// zero.
+    pub fn check_witness(&mut self, bits: u32, witness: P::Field) -> bool {
+        self.absorb(witness);
+        self.squeeze_bits(bits) == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Transcript, TranscriptField};
+    use crate::{
+        cm31, fp31, fp64,
+        fp64::{ext2::Fp2, ext3::Fp3},
+        qm31,
+    };
+    use ark_ff::{Field, PrimeField};
+    use ark_std::{test_rng, vec::Vec, UniformRand};
+
+    type Transcript64 = Transcript<fp64::poseidon2::Poseidon2<12>, 12>;
+
+    type Transcript31 = Transcript<fp31::poseidon2::Poseidon2<16>, 16>;
+
+    fn transcript64(label: &[u8]) -> Transcript64 {
+        Transcript::new(fp64::poseidon2::Poseidon2::<12>::new(), label)
+    }
+
+    fn transcript31(label: &[u8]) -> Transcript31 {
+        Transcript::new(fp31::poseidon2::Poseidon2::<16>::new(), label)
+    }
+
+    #[test]
+    fn prover_and_verifier_agree() {
+        let mut rng = test_rng();
+        let elements: Vec<fp64::Fp> = (0..20).map(|_| fp64::Fp::rand(&mut rng)).collect();
+        let extension = Fp3::rand(&mut rng);
+        let run = || {
+            let mut transcript = transcript64(b"test");
+            transcript.absorb_slice(&elements);
+            let a = transcript.squeeze();
+            transcript.absorb_extension(&extension);
+            transcript.absorb_bytes(b"hello world");
+            let b: Fp2 = transcript.squeeze_extension();
+            (a, b, transcript.squeeze_bits(20))
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn transcript_binds_inputs() {
+        let squeeze = |label: &[u8], elements: &[fp64::Fp], bytes: &[u8]| {
+            let mut transcript = transcript64(label);
+            transcript.absorb_slice(elements);
+            transcript.absorb_bytes(bytes);
+            transcript.squeeze()
+        };
+        let one = fp64::Fp::ONE;
+        let challenge = squeeze(b"a", &[one], b"");
+        assert_ne!(challenge, squeeze(b"b", &[one], b""));
+        assert_ne!(challenge, squeeze(b"a", &[one, one], b""));
+        assert_ne!(challenge, squeeze(b"a", &[-one], b""));
+        // the length is absorbed so trailing zero bytes change the challenge
+        assert_ne!(challenge, squeeze(b"a", &[one], &[0]));
+        assert_ne!(squeeze(b"a", &[], &[0]), squeeze(b"a", &[], &[0, 0]));
+        let long = [7u8; 100];
+        assert_ne!(squeeze(b"a", &[], &long), squeeze(b"a", &[], &long[1..]));
+    }
+
+    #[test]
+    fn consecutive_squeezes_differ() {
+        let mut transcript = transcript31(b"test");
+        // more squeezes than the rate to cover permuting without input
+        let challenges: Vec<fp31::Fp> = (0..20).map(|_| transcript.squeeze()).collect();
+        for (i, a) in challenges.iter().enumerate() {
+            assert!(challenges[i + 1..].iter().all(|b| a != b));
+        }
+    }
+
+    #[test]
+    fn duplexing_overwrites_the_rate() {
+        let permutation = fp64::poseidon2::Poseidon2::<12>::new();
+        let (x, y) = (fp64::Fp::from(5u64), fp64::Fp::from(7u64));
+        let mut transcript = Transcript::new(permutation.clone(), b"");
+        transcript.absorb(x);
+
+        // the empty label absorbs its length 0
+        let mut state = [fp64::Fp::ZERO; 12];
+        state[1] = x;
+        state[8] = fp64::Fp::from(2u64);
+        permutation.permute(&mut state);
+        assert_eq!(transcript.squeeze_element(), state[0]);
+        assert_eq!(transcript.squeeze_element(), state[1]);
+
+        // absorbing discards the rest of the output and zeroes the unused rate
+        transcript.absorb(y);
+        state[0] = y;
+        state[1..8].fill(fp64::Fp::ZERO);
+        state[8] += fp64::Fp::ONE;
+        permutation.permute(&mut state);
+        assert_eq!(transcript.squeeze_element(), state[0]);
+    }
+
+    #[test]
+    fn duplexing_binds_the_input_length() {
+        let mut transcript = transcript64(b"test");
+        transcript.squeeze();
+        let a = fp64::Fp::from(3u64);
+        // absorbing `[a]` leaves the next rate element untouched without
+        // padding, so `[a, s]` would give the same state
+        let s = transcript.state[1];
+        let squeeze = |elements: &[fp64::Fp]| {
+            let mut transcript = transcript.clone();
+            transcript.absorb_slice(elements);
+            transcript.squeeze()
+        };
+        let challenge = squeeze(&[a]);
+        assert_ne!(challenge, squeeze(&[a, s]));
+        assert_ne!(challenge, squeeze(&[a, fp64::Fp::ZERO]));
+
+        let mut transcript = transcript31(b"test");
+        transcript.squeeze();
+        let a = fp31::Fp::from(3u64);
+        let s = transcript.state[1];
+        let mut short = transcript.clone();
+        short.absorb(a);
+        transcript.absorb_slice(&[a, s]);
+        assert_ne!(short.squeeze(), transcript.squeeze());
+    }
+
+    #[test]
+    fn fp64_challenge_reduces_two_elements() {
+        let p = u128::from(fp64::Fp::MODULUS.0[0]);
+        let max = -fp64::Fp::ONE;
+        let expected = ((p - 1) + ((p - 1) << 64)) % p;
+        assert_eq!(
+            fp64::Fp::challenge(&[max, max]).into_bigint().0[0],
+            u64::try_from(expected).unwrap()
+        );
+        let two_64 = fp64::Fp::from(1u128 << 64);
+        let mut rng = test_rng();
+        for _ in 0..10 {
+            let a = fp64::Fp::rand(&mut rng);
+            let b = fp64::Fp::rand(&mut rng);
+            assert_eq!(fp64::Fp::challenge(&[a, b]), a + b * two_64);
+        }
+    }
+
+    #[test]
+    fn fp31_challenges_are_canonical() {
+        let modulus = u32::try_from(fp31::Fp::MODULUS.0[0]).unwrap();
+        assert_eq!(fp31::Fp::challenge(&[fp31::Fp(modulus)]).0, 0);
+
+        // both encodings of zero are absorbed identically
+        let mut canonical = transcript31(b"test");
+        let mut non_canonical = canonical.clone();
+        canonical.absorb(fp31::Fp(0));
+        non_canonical.absorb(fp31::Fp(modulus));
+        for _ in 0..4 {
+            let (x, y) = (canonical.squeeze(), non_canonical.squeeze());
+            assert_eq!(x.0, y.0);
+            assert!(x.0 < modulus);
+        }
+    }
+
+    #[test]
+    fn squeezes_extension_elements() {
+        let mut transcript = transcript31(b"test");
+        let mut copy = transcript.clone();
+        let challenge: qm31::Fp4 = transcript.squeeze_extension();
+        let coefficients: Vec<fp31::Fp> = (0..4).map(|_| copy.squeeze()).collect();
+        assert_eq!(
+            challenge.to_base_prime_field_elements().collect::<Vec<_>>(),
+            coefficients
+        );
+        transcript.absorb_extension(&cm31::Fp2::new(fp31::Fp::ONE, fp31::Fp::ZERO));
+        copy.absorb_slice(&[fp31::Fp::ONE, fp31::Fp::ZERO]);
+        assert_eq!(transcript.squeeze(), copy.squeeze());
+    }
+
+    #[test]
+    fn squeeze_bits_is_in_range() {
+        let mut transcript = transcript64(b"test");
+        for bits in [0, 1, 5, 20, 63] {
+            assert!(transcript.squeeze_bits(bits) < 1 << bits);
+        }
+        let mut transcript = transcript31(b"test");
+        assert!((0..100).all(|_| transcript.squeeze_bits(4) < 16));
+    }
+
+    #[test]
+    fn grinding_produces_valid_witness() {
+        for bits in [0, 1, 8] {
+            let mut prover = transcript31(b"pow");
+            prover.absorb(fp31::Fp::ONE);
+            let mut verifier = prover.clone();
+            let witness = prover.grind(bits);
+            assert!(verifier.check_witness(bits, witness));
+            // both continue from the same state
+            assert_eq!(prover.squeeze(), verifier.squeeze());
+        }
+
+        let mut prover = transcript64(b"pow");
+        let verifier = prover.clone();
+        let witness = prover.grind(10);
+        assert!(verifier.clone().check_witness(10, witness));
+        let invalid = (0..100u64)
+            .map(fp64::Fp::from)
+            .filter(|&w| w != witness)
+            .filter(|&w| !verifier.clone().check_witness(10, w))
+            .count();
+        assert!(invalid > 0);
+    }
+}